@@ -1,5 +1,4 @@
-use crate::common::{FQName, WithItem};
-use itertools::Itertools;
+use crate::common::{FQName, WithClause};
 use std::fmt::{Display, Formatter};
 
 /// The data for an `AlterMaterializedView` statement
@@ -8,7 +7,7 @@ pub struct AlterMaterializedView {
     /// the name of the materialzied view.
     pub name: FQName,
     /// the with options for the view.
-    pub with_clause: Vec<WithItem>,
+    pub with_clause: WithClause,
 }
 
 impl Display for AlterMaterializedView {
@@ -20,10 +19,7 @@ impl Display for AlterMaterializedView {
             if self.with_clause.is_empty() {
                 "".to_string()
             } else {
-                format!(
-                    " WITH {}",
-                    self.with_clause.iter().map(|x| x.to_string()).join(" AND ")
-                )
+                format!(" {}", self.with_clause)
             }
         )
     }
@@ -1,4 +1,5 @@
-use crate::common::{ColumnDefinition, FQName, WithItem};
+use crate::common::{ColumnDefinition, FQName, WithClause, WithItem};
+use crate::table_options::{default_ttl, validate_options, OptionIssue};
 use itertools::Itertools;
 use std::fmt::{Display, Formatter};
 
@@ -11,6 +12,28 @@ pub struct AlterTable {
     pub operation: AlterTableOperation,
 }
 
+impl AlterTable {
+    /// returns the `WITH` options, empty if the operation is not `WITH`.
+    pub fn with_items(&self) -> &[WithItem] {
+        match &self.operation {
+            AlterTableOperation::With(items) => items.as_slice(),
+            _ => &[],
+        }
+    }
+
+    /// returns the `default_time_to_live` option in seconds set by the statement.  Returns
+    /// `None` if the value is not an integer the server accepts.
+    pub fn default_ttl(&self) -> Option<u32> {
+        default_ttl(self.with_items())
+    }
+
+    /// checks the TTL related options and `speculative_retry`, see
+    /// `table_options::validate_options()`.
+    pub fn validate_options(&self) -> Vec<OptionIssue> {
+        validate_options(self.with_items())
+    }
+}
+
 /// table alteration operations
 #[derive(PartialEq, Debug, Clone)]
 pub enum AlterTableOperation {
@@ -23,7 +46,7 @@ pub enum AlterTableOperation {
     /// rename columns `(from, to)`
     Rename((String, String)),
     /// add with element options.
-    With(Vec<WithItem>),
+    With(WithClause),
 }
 
 impl Display for AlterTableOperation {
@@ -37,11 +60,26 @@ impl Display for AlterTableOperation {
             AlterTableOperation::DropColumns(columns) => write!(f, "DROP {}", columns.join(", ")),
             AlterTableOperation::DropCompactStorage => write!(f, "DROP COMPACT STORAGE"),
             AlterTableOperation::Rename((from, to)) => write!(f, "RENAME {} TO {}", from, to),
-            AlterTableOperation::With(with_element) => write!(
-                f,
-                "WITH {}",
-                with_element.iter().map(|x| x.to_string()).join(" AND ")
-            ),
+            AlterTableOperation::With(with_clause) => write!(f, "{}", with_clause),
         }
     }
 }
+
+/// errors raised when building alter statements.
+#[derive(PartialEq, Debug, Clone)]
+pub enum AlterError {
+    /// `PRIMARY KEY` may not be specified on a column added by `ALTER TABLE`.
+    PrimaryKeyInAlter,
+}
+
+impl Display for AlterError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AlterError::PrimaryKeyInAlter => {
+                write!(f, "PRIMARY KEY may not be specified in ALTER TABLE ADD")
+            }
+        }
+    }
+}
+
+impl std::error::Error for AlterError {}
@@ -0,0 +1,429 @@
+use crate::begin_batch::{BatchKind, BeginBatch};
+use crate::cassandra_statement::CassandraStatement;
+use crate::common::{Operand, TtlTimestamp};
+use std::fmt::{Display, Formatter};
+
+/// a complete `BEGIN BATCH ... APPLY BATCH` block.  The parser returns the statements of a
+/// batch separately, the first carrying the `BEGIN BATCH` clause; use `from_statements()` to
+/// collect them.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Batch {
+    /// the `BEGIN BATCH` clause.
+    pub begin_batch: BeginBatch,
+    /// the statements in the batch.  None of them carry a `BEGIN BATCH` clause.
+    pub(crate) statements: Vec<CassandraStatement>,
+}
+
+impl Batch {
+    /// creates an empty batch.
+    pub fn new(begin_batch: BeginBatch) -> Batch {
+        Batch {
+            begin_batch,
+            statements: vec![],
+        }
+    }
+
+    /// collects a batch from the parsed statements.  The first statement must start with
+    /// `BEGIN BATCH` and the statements up to the `APPLY BATCH` are added to the batch.  Any
+    /// statements after the `APPLY BATCH` are ignored.
+    pub fn from_statements(statements: &[CassandraStatement]) -> Result<Batch, BatchError> {
+        let mut iter = statements.iter();
+        let mut first = iter.next().ok_or(BatchError::MissingBeginBatch)?.clone();
+        let begin_batch = Batch::begin_batch_mut(&mut first)
+            .and_then(|begin_batch| begin_batch.take())
+            .ok_or(BatchError::MissingBeginBatch)?;
+        let mut batch = Batch::new(begin_batch);
+        batch.push(first)?;
+        for statement in iter {
+            if let CassandraStatement::ApplyBatch = statement {
+                return Ok(batch);
+            }
+            batch.push(statement.clone())?;
+        }
+        Err(BatchError::MissingApplyBatch)
+    }
+
+    /// adds the statement to the end of the batch.  Only `INSERT`, `UPDATE` and `DELETE`
+    /// statements may be added and they may not have their own `BEGIN BATCH` clause.
+    pub fn push(&mut self, statement: CassandraStatement) -> Result<(), BatchError> {
+        Batch::check_child(&statement)?;
+        self.statements.push(statement);
+        Ok(())
+    }
+
+    /// checks that the statement may be added to a batch.
+    pub(crate) fn check_child(statement: &CassandraStatement) -> Result<(), BatchError> {
+        let begin_batch = match statement {
+            CassandraStatement::Delete(delete) => &delete.begin_batch,
+            CassandraStatement::Insert(insert) => &insert.begin_batch,
+            CassandraStatement::Update(update) => &update.begin_batch,
+            CassandraStatement::Batch(_) => return Err(BatchError::NestedBatch),
+            _ => return Err(BatchError::NotDml(statement.short_name())),
+        };
+        match begin_batch {
+            Some(_) => Err(BatchError::NestedBatch),
+            None => Ok(()),
+        }
+    }
+
+    /// returns an iterator over the statements in the batch.
+    pub fn iter(&self) -> std::slice::Iter<'_, CassandraStatement> {
+        self.statements.iter()
+    }
+
+    /// returns an iterator that allows the statements in the batch to be modified.
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, CassandraStatement> {
+        self.statements.iter_mut()
+    }
+
+    /// the kind of batch.
+    pub fn kind(&self) -> BatchKind {
+        self.begin_batch.kind()
+    }
+
+    /// returns true if the statements suit the kind of batch: a `COUNTER` batch may only
+    /// contain counter updates and other batches may not contain counter updates.  This is not
+    /// checked when the batch is built.
+    pub fn is_consistent_kind(&self) -> bool {
+        let is_counter = self.kind() == BatchKind::Counter;
+        self.statements.iter().all(|statement| {
+            let counter_update = matches!(statement, CassandraStatement::Update(update) if update.is_counter_update());
+            counter_update == is_counter
+        })
+    }
+
+    /// the number of statements in the batch.
+    pub fn len(&self) -> usize {
+        self.statements.len()
+    }
+
+    /// returns true if the batch has no statements.
+    pub fn is_empty(&self) -> bool {
+        self.statements.is_empty()
+    }
+
+    /// returns the `BEGIN BATCH` clause of a DML statement, `None` for other statements.
+    fn begin_batch_mut(statement: &mut CassandraStatement) -> Option<&mut Option<BeginBatch>> {
+        match statement {
+            CassandraStatement::Delete(delete) => Some(&mut delete.begin_batch),
+            CassandraStatement::Insert(insert) => Some(&mut insert.begin_batch),
+            CassandraStatement::Update(update) => Some(&mut update.begin_batch),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a Batch {
+    type Item = &'a CassandraStatement;
+    type IntoIter = std::slice::Iter<'a, CassandraStatement>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut Batch {
+    type Item = &'a mut CassandraStatement;
+    type IntoIter = std::slice::IterMut<'a, CassandraStatement>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl Display for Batch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.begin_batch)?;
+        for statement in &self.statements {
+            write!(f, "{}; ", statement)?;
+        }
+        write!(f, "APPLY BATCH")
+    }
+}
+
+/// builds a `Batch` from parsed statements.
+/// ```
+/// use cql3_parser::batch::BatchBuilder;
+/// use cql3_parser::cassandra_ast::CassandraAST;
+/// let ast = CassandraAST::new("INSERT INTO t (a) VALUES (1); DELETE FROM t WHERE a = 2");
+/// let batch = BatchBuilder::logged()
+///     .add(ast.statements[0].statement.clone())
+///     .add(ast.statements[1].statement.clone())
+///     .timestamp(123)
+///     .build()
+///     .unwrap();
+/// assert_eq!(
+///     "BEGIN LOGGED BATCH USING TIMESTAMP 123 INSERT INTO t (a) VALUES (1); DELETE FROM t WHERE a = 2; APPLY BATCH",
+///     batch.to_string()
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct BatchBuilder {
+    begin_batch: BeginBatch,
+    statements: Vec<CassandraStatement>,
+}
+
+impl BatchBuilder {
+    /// start building a `BEGIN BATCH` without an explicit kind.
+    pub fn new() -> BatchBuilder {
+        BatchBuilder::kind(None)
+    }
+
+    /// start building a `BEGIN LOGGED BATCH`.
+    pub fn logged() -> BatchBuilder {
+        BatchBuilder::kind(Some(BatchKind::Logged))
+    }
+
+    /// start building a `BEGIN UNLOGGED BATCH`.
+    pub fn unlogged() -> BatchBuilder {
+        BatchBuilder::kind(Some(BatchKind::Unlogged))
+    }
+
+    /// start building a `BEGIN COUNTER BATCH`.
+    pub fn counter() -> BatchBuilder {
+        BatchBuilder::kind(Some(BatchKind::Counter))
+    }
+
+    fn kind(batch_kind: Option<BatchKind>) -> BatchBuilder {
+        let mut begin_batch = BeginBatch::new();
+        begin_batch.batch_kind = batch_kind;
+        BatchBuilder {
+            begin_batch,
+            statements: vec![],
+        }
+    }
+
+    /// add the statement to the batch.  The statement is checked when the batch is built.
+    #[allow(clippy::should_implement_trait)]
+    pub fn add(mut self, statement: CassandraStatement) -> BatchBuilder {
+        self.statements.push(statement);
+        self
+    }
+
+    /// set the `USING TIMESTAMP` value for the batch.
+    pub fn timestamp(mut self, timestamp: u64) -> BatchBuilder {
+        self.begin_batch.using_ttl = Some(TtlTimestamp {
+            ttl: None,
+            timestamp: Some(Operand::from(&timestamp)),
+        });
+        self
+    }
+
+    /// create the `Batch`.  Returns an error if a statement is not an `INSERT`, `UPDATE` or
+    /// `DELETE` or is itself a batch.
+    pub fn build(self) -> Result<Batch, BatchError> {
+        let mut batch = Batch::new(self.begin_batch);
+        for statement in self.statements {
+            batch.push(statement)?;
+        }
+        Ok(batch)
+    }
+}
+
+impl Default for BatchBuilder {
+    fn default() -> Self {
+        BatchBuilder::new()
+    }
+}
+
+/// the errors returned when building a `Batch`.
+#[derive(PartialEq, Debug, Clone)]
+pub enum BatchError {
+    /// the first statement does not start with `BEGIN BATCH`.
+    MissingBeginBatch,
+    /// the statements do not end with `APPLY BATCH`.
+    MissingApplyBatch,
+    /// a statement within the batch starts with `BEGIN BATCH` or is itself a batch.
+    NestedBatch,
+    /// the statement is not an `INSERT`, `UPDATE` or `DELETE`.  Contains the statement type.
+    NotDml(&'static str),
+}
+
+impl Display for BatchError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BatchError::MissingBeginBatch => write!(f, "a batch must start with BEGIN BATCH"),
+            BatchError::MissingApplyBatch => write!(f, "a batch must end with APPLY BATCH"),
+            BatchError::NestedBatch => write!(f, "batches can not be nested"),
+            BatchError::NotDml(name) => write!(f, "{} is not allowed in a batch", name),
+        }
+    }
+}
+
+impl std::error::Error for BatchError {}
+
+#[cfg(test)]
+mod tests {
+    use crate::batch::{Batch, BatchBuilder, BatchError};
+    use crate::begin_batch::BatchKind;
+    use crate::cassandra_ast::CassandraAST;
+    use crate::cassandra_statement::CassandraStatement;
+    use crate::common::{FQName, Operand};
+
+    fn statements(qry: &str) -> Vec<CassandraStatement> {
+        let ast = CassandraAST::new(qry);
+        assert!(!ast.has_error());
+        ast.statements
+            .iter()
+            .map(|parsed| parsed.statement.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_batch() {
+        let qry = "BEGIN UNLOGGED BATCH INSERT INTO t (a) VALUES (?); UPDATE t SET a = 1 WHERE pk = ?; DELETE FROM t WHERE pk = 2; APPLY BATCH";
+        let mut batch = Batch::from_statements(&statements(qry)).unwrap();
+        assert_eq!(BatchKind::Unlogged, batch.kind());
+        assert!(batch.is_consistent_kind());
+        assert_eq!(3, batch.len());
+        assert_eq!(
+            vec!["INSERT", "UPDATE", "DELETE"],
+            batch.iter().map(|s| s.short_name()).collect::<Vec<_>>()
+        );
+        assert_eq!(qry, batch.to_string());
+
+        for statement in batch.iter_mut() {
+            for name in statement.fqnames_mut() {
+                name.keyspace = Some("ks".to_string());
+            }
+        }
+        batch
+            .push(statements("DELETE FROM t2 WHERE pk = 3")[0].clone())
+            .unwrap();
+        assert_eq!(
+            "BEGIN UNLOGGED BATCH INSERT INTO ks.t (a) VALUES (?); UPDATE ks.t SET a = 1 WHERE pk = ?; DELETE FROM ks.t WHERE pk = 2; DELETE FROM t2 WHERE pk = 3; APPLY BATCH",
+            batch.to_string()
+        );
+
+        let stmt = CassandraStatement::Batch(batch);
+        assert_eq!(Some(&FQName::new("ks", "t")), stmt.get_table_name());
+        assert_eq!(vec!["a"], stmt.columns_written());
+        assert_eq!(2, stmt.parameters_count());
+        let ks_t = FQName::new("ks", "t");
+        assert_eq!(
+            vec![&ks_t, &ks_t, &ks_t, &FQName::simple("t2")],
+            stmt.fqnames().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            "BEGIN UNLOGGED BATCH INSERT INTO ks.t (a) VALUES (?); UPDATE ks.t SET a = 1 WHERE pk = ?; DELETE FROM ks.t WHERE pk = 2; DELETE FROM t2 WHERE pk = 3; APPLY BATCH",
+            stmt.to_string()
+        );
+    }
+
+    #[test]
+    fn test_batch_errors() {
+        let mut batch = Batch::from_statements(&statements(
+            "BEGIN BATCH INSERT INTO t (a) VALUES (1); APPLY BATCH",
+        ))
+        .unwrap();
+        assert_eq!(
+            Err(BatchError::NotDml("SELECT")),
+            batch.push(statements("SELECT * FROM t")[0].clone())
+        );
+        assert_eq!(
+            Err(BatchError::NestedBatch),
+            batch.push(statements("BEGIN BATCH DELETE FROM t WHERE pk = 1")[0].clone())
+        );
+        assert_eq!(1, batch.len());
+        assert_eq!(
+            Err(BatchError::MissingBeginBatch),
+            Batch::from_statements(&statements("INSERT INTO t (a) VALUES (1)"))
+        );
+        assert_eq!(
+            Err(BatchError::MissingApplyBatch),
+            Batch::from_statements(&statements("BEGIN BATCH INSERT INTO t (a) VALUES (1)"))
+        );
+    }
+
+    #[test]
+    fn test_batch_kinds() {
+        let qry = "BEGIN COUNTER BATCH UPDATE t SET c = c + 1 WHERE pk = 1; UPDATE t SET d = d - 1 WHERE pk = 2; APPLY BATCH";
+        let batch = Batch::from_statements(&statements(qry)).unwrap();
+        assert_eq!(BatchKind::Counter, batch.kind());
+        assert!(batch.is_consistent_kind());
+        assert_eq!(qry, batch.to_string());
+
+        let qry = "BEGIN COUNTER BATCH UPDATE t SET c = c + 1 WHERE pk = 1; INSERT INTO t (a) VALUES (1); APPLY BATCH";
+        let batch = Batch::from_statements(&statements(qry)).unwrap();
+        assert!(!batch.is_consistent_kind());
+
+        let qry = "BEGIN LOGGED BATCH INSERT INTO t (a) VALUES (1); APPLY BATCH";
+        let batch = Batch::from_statements(&statements(qry)).unwrap();
+        assert_eq!(BatchKind::Logged, batch.kind());
+        assert_eq!(Some(BatchKind::Logged), batch.begin_batch.batch_kind);
+        assert_eq!(qry, batch.to_string());
+
+        let qry = "BEGIN BATCH UPDATE t SET c = c + 1 WHERE pk = 1; APPLY BATCH";
+        let batch = Batch::from_statements(&statements(qry)).unwrap();
+        assert_eq!(BatchKind::Logged, batch.kind());
+        assert_eq!(None, batch.begin_batch.batch_kind);
+        assert!(!batch.is_consistent_kind());
+        assert_eq!(qry, batch.to_string());
+    }
+
+    #[test]
+    fn test_batch_builder() {
+        let qry = "BEGIN LOGGED BATCH USING TIMESTAMP 123 INSERT INTO ks.t (pk, a) VALUES (1, 'x') IF NOT EXISTS; UPDATE ks.t SET a = 'y' WHERE pk = 2 IF a = 'x'; DELETE FROM ks.t WHERE pk = 3 IF EXISTS; APPLY BATCH";
+        let children = statements(
+            "INSERT INTO ks.t (pk, a) VALUES (1, 'x') IF NOT EXISTS; UPDATE ks.t SET a = 'y' WHERE pk = 2 IF a = 'x'; DELETE FROM ks.t WHERE pk = 3 IF EXISTS",
+        );
+        let batch = children
+            .iter()
+            .fold(BatchBuilder::logged(), |builder, statement| {
+                builder.add(statement.clone())
+            })
+            .timestamp(123)
+            .build()
+            .unwrap();
+        assert_eq!(qry, batch.to_string());
+        assert_eq!(Batch::from_statements(&statements(qry)).unwrap(), batch);
+        assert!(CassandraStatement::Batch(batch.clone()).is_lwt());
+
+        let qry = "BEGIN UNLOGGED BATCH INSERT INTO t (a) VALUES (?); DELETE FROM t WHERE pk = ?; APPLY BATCH";
+        let batch = BatchBuilder::unlogged()
+            .add(statements("INSERT INTO t (a) VALUES (?)")[0].clone())
+            .add(statements("DELETE FROM t WHERE pk = ?")[0].clone())
+            .build()
+            .unwrap();
+        assert_eq!(qry, batch.to_string());
+        assert_eq!(Batch::from_statements(&statements(qry)).unwrap(), batch);
+
+        assert_eq!(
+            Err(BatchError::NotDml("SELECT")),
+            BatchBuilder::counter()
+                .add(statements("SELECT * FROM t")[0].clone())
+                .build()
+        );
+        assert_eq!(
+            Err(BatchError::NestedBatch),
+            BatchBuilder::new()
+                .add(CassandraStatement::Batch(batch))
+                .build()
+        );
+        assert_eq!(
+            Err(BatchError::NestedBatch),
+            BatchBuilder::new()
+                .add(statements("BEGIN BATCH DELETE FROM t WHERE pk = 1")[0].clone())
+                .build()
+        );
+    }
+
+    #[test]
+    fn test_batch_using() {
+        let qry = "BEGIN UNLOGGED BATCH USING TIMESTAMP ? INSERT INTO t (a) VALUES (?) USING TTL 10; UPDATE t USING TIMESTAMP 5 SET a = 1 WHERE pk = 1; APPLY BATCH";
+        let batch = Batch::from_statements(&statements(qry)).unwrap();
+        let using = batch.begin_batch.using_ttl.as_ref().unwrap();
+        assert_eq!(Some(Operand::Param("?".to_string())), using.timestamp);
+        assert_eq!(None, using.ttl);
+        assert_eq!(qry, batch.to_string());
+
+        let mut stmt = CassandraStatement::Batch(batch);
+        assert_eq!(2, stmt.parameters_count());
+        stmt.bind(&[Operand::from(&1234_u64), Operand::from(&5_u64)])
+            .unwrap();
+        assert_eq!(
+            "BEGIN UNLOGGED BATCH USING TIMESTAMP 1234 INSERT INTO t (a) VALUES (5) USING TTL 10; UPDATE t USING TIMESTAMP 5 SET a = 1 WHERE pk = 1; APPLY BATCH",
+            stmt.to_string()
+        );
+    }
+}
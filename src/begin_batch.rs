@@ -1,18 +1,33 @@
+use crate::common::TtlTimestamp;
 use std::fmt::{Display, Formatter};
 
+/// the kinds of batch.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum BatchKind {
+    Logged,
+    Unlogged,
+    Counter,
+}
+
+impl Display for BatchKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BatchKind::Logged => write!(f, "LOGGED"),
+            BatchKind::Unlogged => write!(f, "UNLOGGED"),
+            BatchKind::Counter => write!(f, "COUNTER"),
+        }
+    }
+}
+
 /// defines the `BEGIN BATCH` data
-/// NOTE: It is possible to set bot LOGGED and UNLOGGED however this will yield an
-/// unparsable statment.
 #[derive(PartialEq, Debug, Clone)]
 pub struct BeginBatch {
-    /* the logged and unlogged can not be merged into a single statement as one or the other or
-    neither may be selected */
-    /// if true the `LOGGED` option will be displayed.
-    pub logged: bool,
-    /// if true the `UNLOGGED` option will be displayed.
-    pub unlogged: bool,
-    /// the optional timestamp for the `BEGIN BATCH` command
-    pub timestamp: Option<u64>,
+    /// the kind of batch as written.  `None` if no kind was specified, in which case the batch
+    /// is logged and the kind is not displayed.  Use `kind()` for the effective kind.
+    pub batch_kind: Option<BatchKind>,
+    /// the optional `USING` clause for the `BEGIN BATCH` command.  It applies to every
+    /// statement in the batch, the statements may still have their own `USING` clauses.
+    pub using_ttl: Option<TtlTimestamp>,
 }
 
 impl Default for BeginBatch {
@@ -24,26 +39,29 @@ impl Default for BeginBatch {
 impl BeginBatch {
     pub fn new() -> BeginBatch {
         BeginBatch {
-            logged: false,
-            unlogged: false,
-            timestamp: None,
+            batch_kind: None,
+            using_ttl: None,
         }
     }
+
+    /// the kind of batch, `LOGGED` unless another kind was specified.
+    pub fn kind(&self) -> BatchKind {
+        self.batch_kind.unwrap_or(BatchKind::Logged)
+    }
 }
 
 impl Display for BeginBatch {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let modifiers = if self.logged {
-            "LOGGED "
-        } else if self.unlogged {
-            "UNLOGGED "
-        } else {
-            ""
-        };
-        if let Some(timestamp) = self.timestamp {
-            write!(f, "BEGIN {}BATCH USING TIMESTAMP {} ", modifiers, timestamp)
-        } else {
-            write!(f, "BEGIN {}BATCH ", modifiers)
-        }
+        let modifiers = self
+            .batch_kind
+            .map_or("".to_string(), |kind| format!("{} ", kind));
+        write!(
+            f,
+            "BEGIN {}BATCH{} ",
+            modifiers,
+            self.using_ttl
+                .as_ref()
+                .map_or("".to_string(), |x| x.to_string())
+        )
     }
 }
@@ -3,12 +3,13 @@ use crate::alter_column::AlterColumnType;
 use crate::alter_materialized_view::AlterMaterializedView;
 use crate::alter_table::{AlterTable, AlterTableOperation};
 use crate::alter_type::{AlterType, AlterTypeOperation};
-use crate::begin_batch::BeginBatch;
+use crate::batch::{Batch, BatchError};
+use crate::begin_batch::{BatchKind, BeginBatch};
 use crate::cassandra_statement::CassandraStatement;
 use crate::common::{
-    ColumnDefinition, DataType, DataTypeName, FQName, Operand, OptionValue, OrderClause,
-    PrimaryKey, Privilege, PrivilegeType, RelationElement, RelationOperator, Resource,
-    TtlTimestamp, WithItem,
+    ColumnDefinition, DataType, DataTypeName, DataTypeParseError, FQName, Identifier, Operand,
+    OptionValue, OrderClause, PrimaryKey, Privilege, PrivilegeType, RelationElement,
+    RelationOperator, Resource, TtlTimestamp, UnknownPrivilege, WithClause, WithItem,
 };
 use crate::common_drop::CommonDrop;
 use crate::create_functon::CreateFunction;
@@ -20,12 +21,14 @@ use crate::create_trigger::CreateTrigger;
 use crate::create_type::CreateType;
 use crate::create_user::CreateUser;
 use crate::delete::{Delete, IndexedColumn};
+use crate::describe::Describe;
 use crate::drop_trigger::DropTrigger;
 use crate::insert::{Insert, InsertValues};
 use crate::list_role::ListRole;
 use crate::role_common::RoleCommon;
-use crate::select::{Named, Select, SelectElement};
+use crate::select::{Count, CountArgument, FunctionCall, Named, Select, SelectElement};
 use crate::update::{AssignmentElement, AssignmentOperator, Update};
+use std::fmt::{Display, Formatter};
 use tree_sitter::{Node, Tree, TreeCursor};
 
 /// Functions for common manipulation of the nodes in the AST tree.
@@ -39,11 +42,100 @@ impl NodeFuncs {
     pub fn as_boolean(node: &Node, source: &str) -> bool {
         NodeFuncs::as_string(node, source).to_uppercase().eq("TRUE")
     }
+    /// get the value of the node as an identifier, retaining any quotes.
+    pub fn as_identifier(node: &Node, source: &str) -> Identifier {
+        Identifier::parse(&NodeFuncs::as_string(node, source))
+    }
 }
 
 /// The parser that walks the AST tree and produces a CassandraStatement.
 pub struct CassandraParser {}
 impl CassandraParser {
+    /// parses a query containing a single statement.  Returns the first error in the query
+    /// text if it can not be parsed, or a `ParseError::Syntax` if the text does not contain
+    /// exactly one statement.  The offset of the error is the start of the second statement
+    /// if there are several, or 0 if no statement is found.
+    pub fn parse(input: &str) -> Result<CassandraStatement, ParseError> {
+        let mut ast = CassandraAST::new(input);
+        if ast.has_error() {
+            return Err(ast
+                .errors()
+                .into_iter()
+                .next()
+                .unwrap_or(ParseError::Syntax {
+                    message: "invalid statement".to_string(),
+                    offset: 0,
+                }));
+        }
+        match ast.statements.len() {
+            0 => Err(ParseError::Syntax {
+                message: "no statement found".to_string(),
+                offset: 0,
+            }),
+            1 => Ok(ast.statements.remove(0).statement),
+            count => Err(ParseError::Syntax {
+                message: format!("expected a single statement but found {}", count),
+                offset: ast.statements[1].start_byte,
+            }),
+        }
+    }
+
+    /// parses a script of statements separated by semicolons.  Semicolons within string
+    /// literals, quoted names, comments and dollar-quoted bodies do not end a statement, and
+    /// empty statements are skipped.  Returns the first error, the offset of the error is
+    /// within the whole script.
+    pub fn parse_many(input: &str) -> Result<Vec<CassandraStatement>, ParseError> {
+        CassandraParser::split_statements(input)
+            .into_iter()
+            .filter(|(_, text)| !text.trim().is_empty())
+            .map(|(start, text)| CassandraParser::parse(text).map_err(|error| error.shifted(start)))
+            .collect()
+    }
+
+    /// splits the text on the semicolons that terminate statements.  Returns the byte offset
+    /// and text of each statement, the semicolons are not included.
+    fn split_statements(input: &str) -> Vec<(usize, &str)> {
+        let bytes = input.as_bytes();
+        let mut result = vec![];
+        let mut start = 0;
+        let mut pos = 0;
+        // the text that closes the current quote or comment.
+        let mut closing: Option<&str> = None;
+        while pos < bytes.len() {
+            let rest = &bytes[pos..];
+            if let Some(end) = closing {
+                if rest.starts_with(end.as_bytes()) {
+                    closing = None;
+                    pos += end.len();
+                } else {
+                    pos += 1;
+                }
+                continue;
+            }
+            closing = match bytes[pos] {
+                b'\'' => Some("'"),
+                b'"' => Some("\""),
+                b'$' if rest.starts_with(b"$$") => Some("$$"),
+                b'-' if rest.starts_with(b"--") => Some("\n"),
+                b'/' if rest.starts_with(b"//") => Some("\n"),
+                b'/' if rest.starts_with(b"/*") => Some("*/"),
+                b';' => {
+                    result.push((start, &input[start..pos]));
+                    start = pos + 1;
+                    None
+                }
+                _ => None,
+            };
+            // skip the opening text so `$$` and `/*` are not taken as their own closing.
+            pos += match closing {
+                Some(_) if rest.starts_with(b"$$") || rest.starts_with(b"/*") => 2,
+                _ => 1,
+            };
+        }
+        result.push((start, &input[start..]));
+        result
+    }
+
     pub fn parse_truncate(node: &Node, source: &str) -> FQName {
         let mut cursor = node.walk();
         cursor.goto_first_child();
@@ -77,7 +169,7 @@ impl CassandraParser {
             with_clause: if cursor.goto_next_sibling() {
                 CassandraParser::parse_with_element(&cursor.node(), source)
             } else {
-                vec![]
+                WithClause::default()
             },
         }
     }
@@ -469,7 +561,7 @@ impl CassandraParser {
                         cursor.goto_first_child();
                         primary_key
                             .partition
-                            .push(NodeFuncs::as_string(&cursor.node(), source));
+                            .push(NodeFuncs::as_identifier(&cursor.node(), source));
                         cursor.goto_next_sibling();
                         // consume the ','
                         cursor.goto_next_sibling();
@@ -479,7 +571,7 @@ impl CassandraParser {
                             if !cursor.node().kind().eq(",") {
                                 primary_key
                                     .clustering
-                                    .push(NodeFuncs::as_string(&cursor.node(), source));
+                                    .push(NodeFuncs::as_identifier(&cursor.node(), source));
                             }
                             process = cursor.goto_next_sibling();
                         }
@@ -493,9 +585,10 @@ impl CassandraParser {
                                     cursor.goto_first_child();
                                     while process {
                                         if cursor.node().kind().eq("object_name") {
-                                            primary_key
-                                                .partition
-                                                .push(NodeFuncs::as_string(&cursor.node(), source));
+                                            primary_key.partition.push(NodeFuncs::as_identifier(
+                                                &cursor.node(),
+                                                source,
+                                            ));
                                         }
                                         process = cursor.goto_next_sibling();
                                     }
@@ -505,9 +598,10 @@ impl CassandraParser {
                                     cursor.goto_first_child();
                                     while process {
                                         if cursor.node().kind().eq("object_name") {
-                                            primary_key
-                                                .clustering
-                                                .push(NodeFuncs::as_string(&cursor.node(), source));
+                                            primary_key.clustering.push(NodeFuncs::as_identifier(
+                                                &cursor.node(),
+                                                source,
+                                            ));
                                         }
                                         process = cursor.goto_next_sibling();
                                     }
@@ -520,15 +614,26 @@ impl CassandraParser {
                     }
                     _ => primary_key
                         .partition
-                        .push(NodeFuncs::as_string(&cursor.node(), source)),
+                        .push(NodeFuncs::as_identifier(&cursor.node(), source)),
                 }
             }
         }
         primary_key
     }
 
-    /// parse the data type
+    /// parse the data type.  An invalid type is reported by `CassandraAST::check_values()`
+    /// which replaces the statement, so the type returned for it is never seen.
     fn parse_data_type(node: &Node, source: &str) -> DataType {
+        CassandraParser::try_parse_data_type(node, source).unwrap_or_else(|_| DataType {
+            name: DataTypeName::from(NodeFuncs::as_string(node, source).as_str()),
+            definition: vec![],
+        })
+    }
+
+    /// parse the data type from the `data_type` node: the name followed by the optional
+    /// definition (`<` type, ... `>`).  The dimension of a `VECTOR` is the numeric constant in
+    /// the definition.
+    fn try_parse_data_type(node: &Node, source: &str) -> Result<DataType, DataTypeParseError> {
         let mut cursor = node.walk();
         cursor.goto_first_child();
         // extracting the name works because it is limited to a single child item so the text is correct
@@ -536,34 +641,65 @@ impl CassandraParser {
             name: DataTypeName::from(NodeFuncs::as_string(&cursor.node(), source).as_str()),
             definition: vec![],
         };
-
+        let mut dimension = None;
         if cursor.goto_next_sibling() {
             cursor.goto_first_child();
             // consume the '<'
             while cursor.goto_next_sibling() {
-                let kind = cursor.node().kind();
-                if !(kind.eq(",") || kind.eq(">")) {
-                    result.definition.push(DataTypeName::from(
-                        NodeFuncs::as_string(&cursor.node(), source).as_str(),
-                    ));
+                let node = cursor.node();
+                let text = NodeFuncs::as_string(&node, source);
+                match node.kind() {
+                    "," | ">" => {}
+                    "data_type" => result
+                        .definition
+                        .push(CassandraParser::try_parse_data_type(&node, source)?),
+                    "constant" | "decimal_literal" => match (&result.name, text.parse()) {
+                        (DataTypeName::Vector(_), Ok(value)) if dimension.is_none() => {
+                            dimension = Some(value)
+                        }
+                        _ => return Err(DataTypeParseError::UnexpectedToken(text)),
+                    },
+                    _ => result.definition.push(DataType {
+                        name: DataTypeName::from(text.as_str()),
+                        definition: vec![],
+                    }),
                 }
             }
         }
-        result
+        if let DataTypeName::Vector(value) = &mut result.name {
+            // a vector has a single element type and a dimension
+            *value = dimension.ok_or(DataTypeParseError::UnexpectedEnd)?;
+            if result.definition.len() != 1 {
+                return Err(DataTypeParseError::UnexpectedToken(NodeFuncs::as_string(
+                    node, source,
+                )));
+            }
+        }
+        Ok(result)
     }
 
     /// parse a column definition
     fn parse_column_definition(node: &Node, source: &str) -> ColumnDefinition {
         let mut cursor = node.walk();
         cursor.goto_first_child();
-        ColumnDefinition {
-            name: NodeFuncs::as_string(&cursor.node(), source),
+        let mut result = ColumnDefinition {
+            name: NodeFuncs::as_identifier(&cursor.node(), source),
             data_type: {
                 cursor.goto_next_sibling();
                 CassandraParser::parse_data_type(&cursor.node(), source)
             },
-            primary_key: cursor.goto_next_sibling(),
+            primary_key: false,
+            is_static: false,
+        };
+        // the remaining nodes are the `STATIC` and `PRIMARY KEY` modifiers
+        while cursor.goto_next_sibling() {
+            if NodeFuncs::as_string(&cursor.node(), source).eq_ignore_ascii_case("STATIC") {
+                result.is_static = true;
+            } else {
+                result.primary_key = true;
+            }
         }
+        result
     }
 
     /// parse table options
@@ -612,18 +748,9 @@ impl CassandraParser {
                     // consume BY
                     cursor.goto_next_sibling();
                     // consume '('
-                    cursor.goto_next_sibling();
-                    result.push(WithItem::ClusterOrder(OrderClause {
-                        name: NodeFuncs::as_string(&cursor.node(), source),
-                        desc: {
-                            // consume the name
-                            if cursor.goto_next_sibling() {
-                                cursor.node().kind().eq("DESC")
-                            } else {
-                                false
-                            }
-                        },
-                    }));
+                    result.push(WithItem::ClusterOrder(
+                        CassandraParser::parse_order_clauses(&mut cursor, source),
+                    ));
                     cursor.goto_parent();
                 }
                 "compact_storage" => result.push(WithItem::CompactStorage),
@@ -634,6 +761,17 @@ impl CassandraParser {
         result
     }
 
+    /// parse a `column IS NOT NULL` relation
+    fn parse_column_not_null(node: &Node, source: &str) -> RelationElement {
+        let mut cursor = node.walk();
+        cursor.goto_first_child();
+        RelationElement {
+            obj: Operand::Column(NodeFuncs::as_identifier(&cursor.node(), source)),
+            oper: RelationOperator::IsNot,
+            value: Operand::Null,
+        }
+    }
+
     /// parse materialized view where statement
     fn parse_materialized_where(node: &Node, source: &str) -> Vec<RelationElement> {
         let mut relations: Vec<RelationElement> = vec![];
@@ -642,13 +780,10 @@ impl CassandraParser {
         // consumer the WHERE
         while cursor.goto_next_sibling() {
             if cursor.node().kind().eq("column_not_null") {
-                cursor.goto_first_child();
-                relations.push(RelationElement {
-                    obj: Operand::Column(NodeFuncs::as_string(&cursor.node(), source)),
-                    oper: RelationOperator::IsNot,
-                    value: Operand::Null,
-                });
-                cursor.goto_parent();
+                relations.push(CassandraParser::parse_column_not_null(
+                    &cursor.node(),
+                    source,
+                ));
             }
             if cursor.node().kind().eq("relation_element") {
                 relations.push(CassandraParser::parse_relation_element(
@@ -695,7 +830,7 @@ impl CassandraParser {
                 if cursor.goto_next_sibling() {
                     CassandraParser::parse_with_element(&cursor.node(), source)
                 } else {
-                    vec![]
+                    WithClause::default()
                 }
             },
         }
@@ -710,7 +845,7 @@ impl CassandraParser {
             name: CassandraParser::parse_table_name(&cursor.node(), source),
             columns: vec![],
             key: None,
-            with_clause: vec![],
+            with_clause: WithClause::default(),
         };
         while cursor.goto_next_sibling() {
             match cursor.node().kind() {
@@ -747,26 +882,38 @@ impl CassandraParser {
     }
 
     /// parse the `with` element.
-    fn parse_with_element(node: &Node, source: &str) -> Vec<WithItem> {
+    fn parse_with_element(node: &Node, source: &str) -> WithClause {
         let mut cursor = node.walk();
         cursor.goto_first_child();
         while cursor.goto_next_sibling() {
             if cursor.node().kind().eq("table_options") {
-                return CassandraParser::parse_table_options(&cursor.node(), source);
+                return WithClause::from(CassandraParser::parse_table_options(
+                    &cursor.node(),
+                    source,
+                ));
             }
         }
-        vec![]
+        WithClause::default()
     }
 
     /// parse create index statement.
     pub fn parse_index(node: &Node, source: &str) -> CreateIndex {
         let mut cursor = node.walk();
         cursor.goto_first_child();
+        if node
+            .child(1)
+            .map_or(false, |child| child.kind().eq("CUSTOM"))
+        {
+            // consume 'CREATE' so that 'CUSTOM INDEX' are the keywords
+            cursor.goto_next_sibling();
+        }
         let mut result = CreateIndex {
             if_not_exists: CassandraParser::consume_2_keywords_and_check_not_exists(&mut cursor),
             name: None,
             table: FQName::simple(""),
             column: IndexColumnType::Column("".to_string()),
+            using: None,
+            options: None,
         };
         let mut process = true;
         while process {
@@ -811,6 +958,16 @@ impl CassandraParser {
                     };
                     cursor.goto_parent();
                 }
+                "USING" => {
+                    cursor.goto_next_sibling();
+                    result.using = Some(NodeFuncs::as_string(&cursor.node(), source));
+                }
+                "option_hash" => {
+                    result.options = Some(OptionValue::Map(CassandraParser::parse_map(
+                        &cursor.node(),
+                        source,
+                    )));
+                }
                 _ => {}
             }
             process = cursor.goto_next_sibling();
@@ -911,7 +1068,11 @@ impl CassandraParser {
                             cursor.goto_next_sibling();
                             // consume the '='
                             cursor.goto_next_sibling();
-                            result.password = Some(NodeFuncs::as_string(&cursor.node(), source));
+                            let password = NodeFuncs::as_string(&cursor.node(), source);
+                            result.password = Some(match cursor.node().kind() {
+                                "bind_marker" => Operand::Param(password),
+                                _ => Operand::Const(password),
+                            });
                             cursor.goto_next_sibling();
                         }
                         "LOGIN" => {
@@ -1126,24 +1287,13 @@ impl CassandraParser {
         }
     }
 
-    /// parse the privilege
-    fn parse_privilege_type(node: &Node, source: &str) -> PrivilegeType {
-        match NodeFuncs::as_string(node, source).to_uppercase().as_str() {
-            "ALL" | "ALL PERMISSIONS" => PrivilegeType::All,
-            "ALTER" => PrivilegeType::Alter,
-            "AUTHORIZE" => PrivilegeType::Authorize,
-            "DESCRIBE" => PrivilegeType::Describe,
-            "EXECUTE" => PrivilegeType::Execute,
-            "CREATE" => PrivilegeType::Create,
-            "DROP" => PrivilegeType::Drop,
-            "MODIFY" => PrivilegeType::Modify,
-            "SELECT" => PrivilegeType::Select,
-            _ => unreachable!(),
-        }
+    /// parse the privilege.
+    fn parse_privilege_type(node: &Node, source: &str) -> Result<PrivilegeType, UnknownPrivilege> {
+        NodeFuncs::as_string(node, source).parse::<PrivilegeType>()
     }
 
-    /// parse the privilege data.
-    pub fn parse_privilege(node: &Node, source: &str) -> Privilege {
+    /// parse the privilege data.  Returns an error if the privilege is not known.
+    pub fn parse_privilege(node: &Node, source: &str) -> Result<Privilege, UnknownPrivilege> {
         let mut cursor = node.walk();
         cursor.goto_first_child();
 
@@ -1157,7 +1307,7 @@ impl CassandraParser {
                     privilege = Some(CassandraParser::parse_privilege_type(
                         &cursor.node(),
                         source,
-                    ));
+                    )?);
                 }
                 "resource" => {
                     resource = Some(CassandraParser::parse_resource(&cursor.node(), source));
@@ -1166,11 +1316,11 @@ impl CassandraParser {
                 _ => {}
             }
         }
-        Privilege {
+        Ok(Privilege {
             privilege: privilege.unwrap(),
             resource,
             role,
-        }
+        })
     }
 
     /// parse an assignment element
@@ -1178,6 +1328,22 @@ impl CassandraParser {
         let mut cursor = node.walk();
         cursor.goto_first_child();
         let name = CassandraParser::parse_indexed_column(&mut cursor, source);
+        // `col += value` and `col -= value` are shorthand for `col = col + value`
+        let shorthand = NodeFuncs::as_string(&cursor.node(), source);
+        if shorthand.eq("+=") || shorthand.eq("-=") {
+            cursor.goto_next_sibling();
+            let operand = CassandraParser::parse_operand(&cursor.node(), source);
+            return AssignmentElement {
+                value: Operand::Column(name.column.clone()),
+                name,
+                operator: Some(if shorthand.eq("+=") {
+                    AssignmentOperator::Plus(operand)
+                } else {
+                    AssignmentOperator::Minus(operand)
+                }),
+                shorthand: true,
+            };
+        }
         // consume the '='
         cursor.goto_next_sibling();
         let value = CassandraParser::parse_operand(&cursor.node(), source);
@@ -1185,6 +1351,7 @@ impl CassandraParser {
             name,
             value,
             operator: None,
+            shorthand: false,
         };
         if cursor.goto_next_sibling() {
             // we have +/- value
@@ -1236,6 +1403,8 @@ impl CassandraParser {
                     vec![]
                 }
             },
+            // `IF EXISTS` is only valid when there are no `IF` conditions, in which case the
+            // cursor is still on the `IF` keyword.
             if_exists: cursor.node().kind().eq("IF"),
         }
     }
@@ -1246,10 +1415,11 @@ impl CassandraParser {
         let mut cursor = node.walk();
         let mut process = cursor.goto_first_child();
         while process {
-            result.push(CassandraParser::parse_relation_element(
-                &cursor.node(),
-                source,
-            ));
+            result.push(if cursor.node().kind().eq("column_not_null") {
+                CassandraParser::parse_column_not_null(&cursor.node(), source)
+            } else {
+                CassandraParser::parse_relation_element(&cursor.node(), source)
+            });
             process = cursor.goto_next_sibling();
             // consume the 'AND' if it exists
             cursor.goto_next_sibling();
@@ -1280,21 +1450,57 @@ impl CassandraParser {
         CassandraParser::parse_indexed_column(&mut cursor, source)
     }
 
-    /// parse an indexed column
+    /// parse an indexed column, `column`, `column[idx]` or `column.field`.  The index and
+    /// field may be children of the column node or its siblings.  Leaves the cursor on the
+    /// token after the column, index or field.
     fn parse_indexed_column(cursor: &mut TreeCursor, source: &str) -> IndexedColumn {
-        IndexedColumn {
-            column: NodeFuncs::as_string(&cursor.node(), source),
+        let node = cursor.node();
+        let mut result = IndexedColumn {
+            column: NodeFuncs::as_identifier(&node, source),
+            idx: None,
+            field: None,
+        };
+        if let (Some(name), Some(_)) = (node.child(0), node.child(1)) {
+            result.column = NodeFuncs::as_identifier(&name, source);
+            let mut children = node.walk();
+            children.goto_first_child();
+            children.goto_next_sibling();
+            CassandraParser::parse_column_selectors(&mut children, source, &mut result);
+        }
+        if cursor.goto_next_sibling() {
+            CassandraParser::parse_column_selectors(cursor, source, &mut result);
+        }
+        result
+    }
 
-            idx: if cursor.goto_next_sibling() && cursor.node().kind().eq("[") {
-                // consume '['
-                cursor.goto_next_sibling();
-                let result = Some(NodeFuncs::as_string(&cursor.node(), source));
-                // consume ']'
-                cursor.goto_next_sibling();
-                result
-            } else {
-                None
-            },
+    /// parse the `[idx]` and `.field` selectors starting at the cursor into the column.
+    /// Leaves the cursor on the token after the selectors.
+    fn parse_column_selectors(cursor: &mut TreeCursor, source: &str, column: &mut IndexedColumn) {
+        loop {
+            match cursor.node().kind() {
+                "[" => {
+                    // consume '['
+                    cursor.goto_next_sibling();
+                    let node = cursor.node();
+                    column.idx = Some(match node.kind() {
+                        "constant" | "bind_marker" | "function_call" => {
+                            CassandraParser::parse_operand(&node, source)
+                        }
+                        _ => Operand::Const(NodeFuncs::as_string(&node, source)),
+                    });
+                    // consume ']'
+                    cursor.goto_next_sibling();
+                }
+                "." => {
+                    // consume '.'
+                    cursor.goto_next_sibling();
+                    column.field = Some(NodeFuncs::as_identifier(&cursor.node(), source));
+                }
+                _ => return,
+            }
+            if !cursor.goto_next_sibling() {
+                return;
+            }
         }
     }
 
@@ -1369,14 +1575,14 @@ impl CassandraParser {
     }
 
     /// parse a column list
-    fn parse_column_list(node: &Node, source: &str) -> Vec<String> {
-        let mut result: Vec<String> = vec![];
+    fn parse_column_list(node: &Node, source: &str) -> Vec<Identifier> {
+        let mut result: Vec<Identifier> = vec![];
         let mut cursor = node.walk();
         let mut process = cursor.goto_first_child();
 
         while process {
             if cursor.node().kind().eq("column") {
-                result.push(NodeFuncs::as_string(&cursor.node(), source));
+                result.push(NodeFuncs::as_identifier(&cursor.node(), source));
             }
             process = cursor.goto_next_sibling();
             // consume ',' if it is there
@@ -1386,42 +1592,46 @@ impl CassandraParser {
     }
 
     /// parse the using timestamp sttement.
-    fn parse_using_timestamp(node: &Node, source: &str) -> Option<u64> {
+    fn parse_using_timestamp(node: &Node, source: &str) -> Option<Operand> {
         let mut cursor = node.walk();
         cursor.goto_first_child();
         // consume "USING"
         cursor.goto_next_sibling();
         // consume "TIMESTAMP"
         cursor.goto_next_sibling();
-        Some(
-            NodeFuncs::as_string(&cursor.node(), source)
-                .parse::<u64>()
-                .unwrap(),
-        )
+        Some(CassandraParser::parse_using_value(&cursor.node(), source))
     }
 
-    /// parse the using ttl timestamp element.
+    /// parse a `USING TTL` or `USING TIMESTAMP` value.  This is either a bind marker or a
+    /// literal, which may be negative.  The `ttl` and `time` nodes contain the value.
+    fn parse_using_value(node: &Node, source: &str) -> Operand {
+        let value = match node.kind() {
+            "ttl" | "time" => node.child(0).unwrap_or(*node),
+            _ => *node,
+        };
+        match value.kind() {
+            "bind_marker" => Operand::Param(NodeFuncs::as_string(&value, source)),
+            _ => Operand::Const(NodeFuncs::as_string(node, source)),
+        }
+    }
+
+    /// parse the using ttl timestamp element.  The items may be in any order, each value
+    /// follows its keyword.
     fn parse_ttl_timestamp(node: &Node, source: &str) -> TtlTimestamp {
         let mut cursor = node.walk();
         cursor.goto_first_child();
         // consume "USING"
-        let mut ttl: Option<u64> = None;
-        let mut timestamp: Option<u64> = None;
-        while (ttl.is_none() || timestamp.is_none()) && cursor.goto_next_sibling() {
+        let mut ttl: Option<Operand> = None;
+        let mut timestamp: Option<Operand> = None;
+        while cursor.goto_next_sibling() {
             match cursor.node().kind() {
-                "ttl" => {
-                    ttl = Some(
-                        NodeFuncs::as_string(&cursor.node(), source)
-                            .parse::<u64>()
-                            .unwrap(),
-                    );
+                // consume 'TTL'
+                "TTL" if cursor.goto_next_sibling() => {
+                    ttl = Some(CassandraParser::parse_using_value(&cursor.node(), source));
                 }
-                "time" => {
-                    timestamp = Some(
-                        NodeFuncs::as_string(&cursor.node(), source)
-                            .parse::<u64>()
-                            .unwrap(),
-                    );
+                // consume 'TIMESTAMP'
+                "TIMESTAMP" if cursor.goto_next_sibling() => {
+                    timestamp = Some(CassandraParser::parse_using_value(&cursor.node(), source));
                 }
                 _ => {}
             }
@@ -1501,16 +1711,23 @@ impl CassandraParser {
     /// parse an operand
     fn parse_operand(node: &Node, source: &str) -> Operand {
         match node.kind() {
-            "assignment_operand" | "constant" => {
-                let txt = NodeFuncs::as_string(node, source);
-                if txt.to_uppercase().eq("NULL") {
-                    Operand::Null
-                } else {
-                    Operand::Const(txt)
-                }
+            "assignment_operand" | "constant" | "object_name" | "column"
+                if NodeFuncs::as_string(node, source).eq_ignore_ascii_case("NULL") =>
+            {
+                // NULL is a reserved word so it is never a column name.
+                Operand::Null
+            }
+            "object_name" | "column"
+                if ["NaN", "Infinity"]
+                    .iter()
+                    .any(|x| NodeFuncs::as_string(node, source).eq_ignore_ascii_case(x)) =>
+            {
+                // NaN and Infinity are reserved words for the float special values.
+                Operand::Const(NodeFuncs::as_string(node, source))
             }
+            "assignment_operand" | "constant" => Operand::Const(NodeFuncs::as_string(node, source)),
             "bind_marker" => Operand::Param(NodeFuncs::as_string(node, source)),
-            "object_name" | "column" => Operand::Column(NodeFuncs::as_string(node, source)),
+            "object_name" | "column" => Operand::Column(NodeFuncs::as_identifier(node, source)),
             "assignment_tuple" => {
                 Operand::Tuple(CassandraParser::parse_assignment_tuple(node, source))
             }
@@ -1636,17 +1853,31 @@ impl CassandraParser {
         // consume BEGIN
         cursor.goto_next_sibling();
 
-        let node = cursor.node();
-        result.logged = node.kind().eq("LOGGED");
-        result.unlogged = node.kind().eq("UNLOGGED");
-        if result.logged || result.unlogged {
+        result.batch_kind = match NodeFuncs::as_string(&cursor.node(), source)
+            .to_uppercase()
+            .as_str()
+        {
+            "LOGGED" => Some(BatchKind::Logged),
+            "UNLOGGED" => Some(BatchKind::Unlogged),
+            "COUNTER" => Some(BatchKind::Counter),
+            _ => None,
+        };
+        if result.batch_kind.is_some() {
             // used a node so advance
             cursor.goto_next_sibling();
         }
         // consume BATCH
         if cursor.goto_next_sibling() {
-            // we should have using_timestamp_spec
-            result.timestamp = CassandraParser::parse_using_timestamp(&cursor.node(), source)
+            result.using_ttl = match cursor.node().kind() {
+                "using_ttl_timestamp" => {
+                    Some(CassandraParser::parse_ttl_timestamp(&cursor.node(), source))
+                }
+                "using_timestamp_spec" => Some(TtlTimestamp {
+                    ttl: None,
+                    timestamp: CassandraParser::parse_using_timestamp(&cursor.node(), source),
+                }),
+                _ => None,
+            };
         }
 
         result
@@ -1704,14 +1935,40 @@ impl CassandraParser {
                 }
                 result
             },
+            #[cfg(feature = "dse")]
+            group_by: {
+                let mut result = vec![];
+                if cursor.node().kind().eq("group_by_spec") {
+                    result = CassandraParser::parse_group_by_spec(&cursor.node(), source);
+                    cursor.goto_next_sibling();
+                }
+                result
+            },
             order: {
-                let mut result = None;
+                // `GROUP BY` is reported by `CassandraAST::check_unsupported()`.
+                #[cfg(not(feature = "dse"))]
+                if cursor.node().kind().eq("group_by_spec") {
+                    cursor.goto_next_sibling();
+                }
+                let mut result = vec![];
                 if cursor.node().kind().eq("order_spec") {
                     result = CassandraParser::parse_order_spec(&cursor.node(), source);
                     cursor.goto_next_sibling();
                 }
                 result
             },
+            per_partition_limit: {
+                let mut result = None;
+                if cursor.node().kind().eq("per_partition_limit_spec") {
+                    // the limit value is the last item in `PER PARTITION LIMIT n`
+                    let node = cursor.node();
+                    if let Some(value) = node.child(node.child_count() - 1) {
+                        result = NodeFuncs::as_string(&value, source).parse::<i32>().ok();
+                    }
+                    cursor.goto_next_sibling();
+                }
+                result
+            },
             limit: {
                 let mut result = None;
                 if cursor.node().kind().eq("limit_spec") {
@@ -1728,7 +1985,14 @@ impl CassandraParser {
                 }
                 result
             },
-            filtering: cursor.node().kind().eq("ALLOW"),
+            allow_filtering: {
+                // `ALLOW FILTERING` is always the last clause.
+                let mut result = cursor.node().kind().eq("ALLOW");
+                while !result && cursor.goto_next_sibling() {
+                    result = cursor.node().kind().eq("ALLOW");
+                }
+                result
+            },
         }
     }
 
@@ -1763,7 +2027,7 @@ impl CassandraParser {
             "relation_contains_key" => {
                 cursor.goto_first_child();
                 RelationElement {
-                    obj: Operand::Column(NodeFuncs::as_string(&cursor.node(), source)),
+                    obj: Operand::Column(NodeFuncs::as_identifier(&cursor.node(), source)),
                     oper: RelationOperator::ContainsKey,
                     value: {
                         // consume column value
@@ -1779,7 +2043,7 @@ impl CassandraParser {
             "relation_contains" => {
                 cursor.goto_first_child();
                 RelationElement {
-                    obj: Operand::Column(NodeFuncs::as_string(&cursor.node(), source)),
+                    obj: Operand::Column(NodeFuncs::as_identifier(&cursor.node(), source)),
                     oper: RelationOperator::Contains,
                     value: {
                         // consume column value
@@ -1791,43 +2055,83 @@ impl CassandraParser {
                 }
             }
             _ => {
-                RelationElement {
-                    obj: CassandraParser::parse_relation_value(&mut cursor, source),
-                    oper: {
-                        // consume the obj
-                        cursor.goto_next_sibling();
-                        CassandraParser::parse_operator(&mut cursor)
-                    },
-                    value: {
-                        // consume the oper
-                        cursor.goto_next_sibling();
-                        let mut values = vec![];
-                        let inline_tuple = if cursor.node().kind().eq("(") {
-                            // inline tuple or function_args
-                            cursor.goto_next_sibling();
-                            true
-                        } else {
-                            false
-                        };
-                        values.push(CassandraParser::parse_operand(&cursor.node(), source));
-                        cursor.goto_next_sibling();
-                        while cursor.node().kind().eq(",") {
-                            cursor.goto_next_sibling();
-                            values.push(CassandraParser::parse_operand(&cursor.node(), source));
-                        }
-                        if values.len() > 1 {
-                            if inline_tuple {
-                                Operand::Tuple(values)
-                            } else {
-                                Operand::Collection(values)
-                            }
-                        } else {
-                            values[0].clone()
-                        }
-                    },
+                let obj = if cursor.node().kind().eq("column") {
+                    // leaves the cursor on the token after the column, index or field
+                    let column = CassandraParser::parse_indexed_column(&mut cursor, source);
+                    if column.idx.is_none() && column.field.is_none() {
+                        Operand::Column(column.column)
+                    } else {
+                        Operand::IndexedColumn(Box::new(column))
+                    }
+                } else if cursor.node().kind().eq("TOKEN") {
+                    let obj = CassandraParser::parse_token_function(&mut cursor, source);
+                    // consume the ')'
+                    cursor.goto_next_sibling();
+                    obj
+                } else {
+                    // a column tuple `(a, b)` is parsed as a tuple of columns, `TOKEN(a, b)`
+                    // may be parsed as a function call.
+                    let obj = CassandraParser::parse_relation_value(&mut cursor, source);
+                    // consume the obj
+                    cursor.goto_next_sibling();
+                    obj
+                };
+                let oper = CassandraParser::parse_operator(&mut cursor);
+                // consume the oper
+                cursor.goto_next_sibling();
+                let mut values = vec![];
+                let inline_tuple = if cursor.node().kind().eq("(") {
+                    // inline tuple or function_args
+                    cursor.goto_next_sibling();
+                    true
+                } else {
+                    false
+                };
+                // the values may themselves be tuples, `(a, b) IN ((1, 2), (3, 4))`
+                let parse_value = |cursor: &mut TreeCursor| {
+                    if cursor.node().kind().eq("(") {
+                        CassandraParser::parse_relation_value(cursor, source)
+                    } else if cursor.node().kind().eq("TOKEN") {
+                        CassandraParser::parse_token_function(cursor, source)
+                    } else {
+                        CassandraParser::parse_operand(&cursor.node(), source)
+                    }
+                };
+                values.push(parse_value(&mut cursor));
+                cursor.goto_next_sibling();
+                while cursor.node().kind().eq(",") {
+                    cursor.goto_next_sibling();
+                    values.push(parse_value(&mut cursor));
+                    cursor.goto_next_sibling();
                 }
+                let value = if values.len() > 1 {
+                    if inline_tuple {
+                        Operand::Tuple(values)
+                    } else {
+                        Operand::Collection(values)
+                    }
+                } else {
+                    values.remove(0)
+                };
+                RelationElement { obj, oper, value }
+            }
+        }
+    }
+
+    /// parse `TOKEN(arg, ...)` where the `TOKEN` keyword and the arguments are siblings.  The
+    /// cursor must be on `TOKEN` and is left on the closing `)`.  The result is a function
+    /// operand, just as when the grammar presents it as a function call.
+    fn parse_token_function(cursor: &mut TreeCursor, source: &str) -> Operand {
+        let mut args = vec![];
+        // consume 'TOKEN'
+        cursor.goto_next_sibling();
+        // consume '('
+        while cursor.goto_next_sibling() && !cursor.node().kind().eq(")") {
+            if !cursor.node().kind().eq(",") {
+                args.push(NodeFuncs::as_string(&cursor.node(), source));
             }
         }
+        Operand::Func(format!("TOKEN({})", args.join(", ")))
     }
 
     // Parse an Operator
@@ -1854,8 +2158,9 @@ impl CassandraParser {
         let node = cursor.node();
         let kind = node.kind();
         match kind {
-            "column" => Operand::Column(NodeFuncs::as_string(&node, source)),
+            "column" => Operand::Column(NodeFuncs::as_identifier(&node, source)),
             "function_call" => Operand::Func(NodeFuncs::as_string(&node, source)),
+            "bind_marker" => Operand::Param(NodeFuncs::as_string(&node, source)),
             "(" => {
                 let mut values: Vec<Operand> = Vec::new();
                 // consume '('
@@ -1874,24 +2179,50 @@ impl CassandraParser {
     }
 
     /// parse an order clause
-    fn parse_order_spec(node: &Node, source: &str) -> Option<OrderClause> {
+    fn parse_order_spec(node: &Node, source: &str) -> Vec<OrderClause> {
         let mut cursor = node.walk();
         cursor.goto_first_child();
         // consume "ORDER"
         cursor.goto_next_sibling();
         // consume "BY"
-        cursor.goto_next_sibling();
-        Some(OrderClause {
-            name: NodeFuncs::as_string(&cursor.node(), source),
-            desc: {
-                // consume the name
-                if cursor.goto_next_sibling() {
-                    cursor.node().kind().eq("DESC")
-                } else {
-                    false
+        CassandraParser::parse_order_clauses(&mut cursor, source)
+    }
+
+    /// parse a comma separated list of `name [ASC|DESC]` items.  The cursor must be
+    /// positioned on the token immediately before the first name.
+    fn parse_order_clauses(cursor: &mut TreeCursor, source: &str) -> Vec<OrderClause> {
+        let mut result: Vec<OrderClause> = vec![];
+        while cursor.goto_next_sibling() {
+            match cursor.node().kind() {
+                "," | ")" | "ASC" => {}
+                "DESC" => {
+                    if let Some(order) = result.last_mut() {
+                        order.desc = true;
+                    }
                 }
-            },
-        })
+                _ => result.push(OrderClause {
+                    name: NodeFuncs::as_string(&cursor.node(), source),
+                    desc: false,
+                }),
+            }
+        }
+        result
+    }
+
+    /// parse the `GROUP BY` column list
+    #[cfg(feature = "dse")]
+    fn parse_group_by_spec(node: &Node, source: &str) -> Vec<String> {
+        let mut result = vec![];
+        let mut cursor = node.walk();
+        let mut process = cursor.goto_first_child();
+        while process {
+            match cursor.node().kind() {
+                "GROUP" | "BY" | "," => {}
+                _ => result.push(NodeFuncs::as_string(&cursor.node(), source)),
+            }
+            process = cursor.goto_next_sibling();
+        }
+        result
     }
 
     /// parse a select element
@@ -1911,17 +2242,62 @@ impl CassandraParser {
         };
         match type_.kind() {
             "column" => SelectElement::Column(Named {
-                name: NodeFuncs::as_string(&type_, source),
-                alias,
-            }),
-            "function_call" => SelectElement::Function(Named {
-                name: NodeFuncs::as_string(&type_, source),
+                name: NodeFuncs::as_identifier(&type_, source),
                 alias,
             }),
+            "function_call" => match CassandraParser::parse_count(&type_, source, &alias) {
+                Some(count) => SelectElement::Count(count),
+                None => SelectElement::Function(FunctionCall {
+                    text: NodeFuncs::as_string(&type_, source),
+                    alias,
+                }),
+            },
             _ => unreachable!(),
         }
     }
 
+    /// parse a function call node as a `count` call.  Returns `None` if the function is not
+    /// `count` or does not have a single argument.
+    fn parse_count(node: &Node, source: &str, alias: &Option<String>) -> Option<Count> {
+        let mut cursor = node.walk();
+        cursor.goto_first_child();
+        let name = NodeFuncs::as_string(&cursor.node(), source);
+        if !name.eq_ignore_ascii_case("count") {
+            return None;
+        }
+        let mut args = vec![];
+        while cursor.goto_next_sibling() {
+            match cursor.node().kind() {
+                "(" | ")" | "," => {}
+                "function_args" => {
+                    let mut args_cursor = cursor.node().walk();
+                    for arg in cursor.node().children(&mut args_cursor) {
+                        if !arg.kind().eq(",") {
+                            args.push(arg);
+                        }
+                    }
+                }
+                _ => args.push(cursor.node()),
+            }
+        }
+        let argument = match args.as_slice() {
+            [arg] => match arg.kind() {
+                "*" => CountArgument::Star,
+                "column" | "object_name" => {
+                    CountArgument::Column(NodeFuncs::as_identifier(arg, source))
+                }
+                _ if NodeFuncs::as_string(arg, source).eq("1") => CountArgument::One,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        Some(Count {
+            name,
+            argument,
+            alias: alias.clone(),
+        })
+    }
+
     /// parse the standard drop specification.
     pub fn parse_standard_drop(node: &Node, source: &str) -> CommonDrop {
         let mut cursor = node.walk();
@@ -1976,15 +2352,113 @@ pub struct ParsedStatement {
     /// the ending byte of the text for the parsed statement within
     /// the original statement.
     end_byte: usize,
+    /// the location of the statement elements, only populated by
+    /// `CassandraAST::parse_with_spans()`.
+    pub spans: Option<StatementSpans>,
 }
 
 impl ParsedStatement {
     pub fn new(node: Node, source: &str) -> ParsedStatement {
+        let statement = CassandraStatement::from_node(&node, source);
         ParsedStatement {
-            has_error: node.is_error(),
-            statement: CassandraStatement::from_node(&node, source),
+            // a statement the tree accepts may still contain values that are not valid
+            has_error: node.is_error() || matches!(statement, CassandraStatement::Unknown(_)),
+            statement,
             start_byte: node.start_byte(),
             end_byte: node.end_byte(),
+            spans: None,
+        }
+    }
+
+    /// returns descriptions of problems that do not prevent the statement from being parsed
+    /// but will cause the server to reject it.
+    pub fn warnings(&self) -> Vec<String> {
+        match &self.statement {
+            CassandraStatement::Insert(insert) => insert
+                .validate()
+                .err()
+                .map(|e| e.to_string())
+                .into_iter()
+                .collect(),
+            _ => vec![],
+        }
+    }
+}
+
+/// a range of bytes within the query text.
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub struct Span {
+    /// the first byte of the range.
+    pub start: usize,
+    /// the byte after the end of the range.
+    pub end: usize,
+}
+
+impl Span {
+    fn of(node: &Node) -> Span {
+        Span {
+            start: node.start_byte(),
+            end: node.end_byte(),
+        }
+    }
+}
+
+/// the locations of the elements of a statement within the query text.  Each list is in
+/// the order the elements appear in the text.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct StatementSpans {
+    /// the whole statement.
+    pub statement: Span,
+    /// the keyspace qualified names (`FQName`) such as table names.
+    pub names: Vec<Span>,
+    /// the relations in the `WHERE` and `IF` clauses.
+    pub relations: Vec<Span>,
+    /// the operands, including the selected columns.  Only the outermost operand is
+    /// reported, so the members of a collection do not have spans of their own.
+    pub operands: Vec<Span>,
+}
+
+impl StatementSpans {
+    /// collect the spans for the statement node.
+    fn new(node: &Node) -> StatementSpans {
+        let mut result = StatementSpans {
+            statement: Span::of(node),
+            ..StatementSpans::default()
+        };
+        let mut cursor = node.walk();
+        if cursor.goto_first_child() {
+            result.collect(&mut cursor);
+        }
+        result
+    }
+
+    /// collects the spans from the cursor node and its siblings.
+    fn collect(&mut self, cursor: &mut TreeCursor) {
+        loop {
+            let node = cursor.node();
+            let descend = match node.kind() {
+                "table_name" => {
+                    self.names.push(Span::of(&node));
+                    false
+                }
+                "relation_element" => {
+                    self.relations.push(Span::of(&node));
+                    true
+                }
+                "constant" | "bind_marker" | "column" | "function_call" | "assignment_operand"
+                | "assignment_tuple" | "assignment_map" | "assignment_list" | "assignment_set" => {
+                    self.operands.push(Span::of(&node));
+                    false
+                }
+                _ => true,
+            };
+            if descend && cursor.goto_first_child() {
+                self.collect(cursor);
+                cursor.goto_parent();
+            }
+            if !cursor.goto_next_sibling() {
+                break;
+            }
         }
     }
 }
@@ -1996,6 +2470,9 @@ pub struct CassandraAST {
     pub(crate) tree: Tree,
     /// the statement type of the query
     pub statements: Vec<ParsedStatement>,
+    /// the errors found in the parsed statements, such as statements that are not allowed in
+    /// the batch that contains them.
+    statement_errors: Vec<ParseError>,
 }
 
 impl CassandraAST {
@@ -2016,16 +2493,210 @@ impl CassandraAST {
         */
 
         let tree = parser.parse(cassandra_statement, None).unwrap();
+        let mut statements = CassandraStatement::from_tree(&tree, cassandra_statement);
+        CassandraAST::add_describes(&mut statements, cassandra_statement);
+        let mut statement_errors =
+            CassandraAST::check_values(&tree, &mut statements, cassandra_statement);
+        statement_errors.extend(CassandraAST::check_batches(
+            &mut statements,
+            cassandra_statement,
+        ));
+        #[cfg(not(feature = "dse"))]
+        let statement_errors = {
+            let mut errors = statement_errors;
+            errors.extend(CassandraAST::check_unsupported(&tree, &mut statements));
+            errors
+        };
         CassandraAST {
-            statements: CassandraStatement::from_tree(&tree, cassandra_statement),
+            statements,
             text: cassandra_statement.to_string(),
             tree,
+            statement_errors,
         }
     }
 
+    /// finds the clauses that the grammar accepts but are not enabled in this build and marks
+    /// the statements that contain them as in error.
+    #[cfg(not(feature = "dse"))]
+    fn check_unsupported(tree: &Tree, statements: &mut [ParsedStatement]) -> Vec<ParseError> {
+        let mut result = vec![];
+        let mut cursor = tree.root_node().walk();
+        let mut process = true;
+        while process {
+            let node = cursor.node();
+            if node.kind().eq("group_by_spec") {
+                // the tree does not represent `DESCRIBE` commands
+                if let Some(parsed) = statements.iter_mut().find(|parsed| {
+                    !matches!(parsed.statement, CassandraStatement::Describe(_))
+                        && parsed.start_byte <= node.start_byte()
+                        && node.end_byte() <= parsed.end_byte
+                }) {
+                    parsed.has_error = true;
+                    result.push(ParseError::UnsupportedClause {
+                        clause: "GROUP BY".to_string(),
+                        offset: node.start_byte(),
+                    });
+                }
+            }
+            // depth first walk of the tree
+            process = cursor.goto_first_child() || cursor.goto_next_sibling();
+            while !process && cursor.goto_parent() {
+                process = cursor.goto_next_sibling();
+            }
+        }
+        result
+    }
+
+    /// finds the data types that are not valid, e.g. a `VECTOR` without a dimension, and the
+    /// privileges that are not known, and marks the statements that contain them as in error.
+    /// Each offending statement is replaced with `CassandraStatement::Unknown` containing its
+    /// text.
+    fn check_values(
+        tree: &Tree,
+        statements: &mut [ParsedStatement],
+        source: &str,
+    ) -> Vec<ParseError> {
+        let mut result = vec![];
+        let mut cursor = tree.root_node().walk();
+        let mut process = true;
+        while process {
+            let node = cursor.node();
+            let is_data_type = node.kind().eq("data_type");
+            let error = match node.kind() {
+                "data_type" => CassandraParser::try_parse_data_type(&node, source)
+                    .err()
+                    .map(|error| error.to_string()),
+                "privilege" => NodeFuncs::as_string(&node, source)
+                    .parse::<PrivilegeType>()
+                    .err()
+                    .map(|error| error.to_string()),
+                _ => None,
+            };
+            if let Some(error) = error {
+                // the tree does not represent `DESCRIBE` commands
+                if let Some(parsed) = statements.iter_mut().find(|parsed| {
+                    !matches!(parsed.statement, CassandraStatement::Describe(_))
+                        && parsed.start_byte <= node.start_byte()
+                        && node.end_byte() <= parsed.end_byte
+                }) {
+                    parsed.has_error = true;
+                    parsed.statement = CassandraStatement::Unknown(
+                        source[parsed.start_byte..parsed.end_byte].to_string(),
+                    );
+                    result.push(ParseError::Syntax {
+                        message: error,
+                        offset: node.start_byte(),
+                    });
+                }
+            }
+            // depth first walk of the tree, the nested types are checked with their type
+            process = (!is_data_type && cursor.goto_first_child()) || cursor.goto_next_sibling();
+            while !process && cursor.goto_parent() {
+                process = cursor.goto_next_sibling();
+            }
+        }
+        result
+    }
+
+    /// finds the statements between `BEGIN BATCH` and `APPLY BATCH` that are not allowed in a
+    /// batch.  The grammar parses each of them as a separate statement so they are only found
+    /// here.  Each offending statement is replaced with `CassandraStatement::Unknown`
+    /// containing its text, so the query text can still be passed through.
+    fn check_batches(statements: &mut [ParsedStatement], source: &str) -> Vec<ParseError> {
+        let mut result = vec![];
+        // the index of the next statement within the current batch.
+        let mut index: Option<usize> = None;
+        for parsed in statements.iter_mut() {
+            match (&parsed.statement, index) {
+                (CassandraStatement::ApplyBatch, _) => index = None,
+                (CassandraStatement::Unknown(_), Some(idx)) => index = Some(idx + 1),
+                (statement, None) => {
+                    if let Err(BatchError::NestedBatch) = Batch::check_child(statement) {
+                        index = Some(1);
+                    }
+                }
+                (statement, Some(idx)) => {
+                    if let Err(error) = Batch::check_child(statement) {
+                        let span = Span {
+                            start: parsed.start_byte,
+                            end: parsed.end_byte,
+                        };
+                        result.push(ParseError::InvalidBatchStatement {
+                            error,
+                            index: idx,
+                            span,
+                        });
+                        parsed.has_error = true;
+                        parsed.statement =
+                            CassandraStatement::Unknown(source[span.start..span.end].to_string());
+                    }
+                    index = Some(idx + 1);
+                }
+            }
+        }
+        result
+    }
+
+    /// `DESCRIBE` is a cqlsh command that the grammar does not cover.  Replaces the statements
+    /// that the tree has for the text of each `DESCRIBE` command with the command.
+    fn add_describes(statements: &mut Vec<ParsedStatement>, source: &str) {
+        for (start, text) in CassandraParser::split_statements(source) {
+            if let Some(describe) = Describe::parse(text) {
+                let start_byte = start + text.len() - text.trim_start().len();
+                let end_byte = start_byte + text.trim().len();
+                statements.retain(|parsed| {
+                    parsed.end_byte <= start_byte || end_byte <= parsed.start_byte
+                });
+                let idx = statements
+                    .iter()
+                    .position(|parsed| end_byte <= parsed.start_byte)
+                    .unwrap_or(statements.len());
+                statements.insert(
+                    idx,
+                    ParsedStatement {
+                        has_error: false,
+                        statement: CassandraStatement::Describe(describe),
+                        start_byte,
+                        end_byte,
+                        spans: None,
+                    },
+                );
+            }
+        }
+    }
+
+    /// true if the offset is within a `DESCRIBE` command, the tree does not represent them.
+    fn is_describe(&self, offset: usize) -> bool {
+        self.statements.iter().any(|parsed| {
+            matches!(parsed.statement, CassandraStatement::Describe(_))
+                && parsed.start_byte <= offset
+                && offset <= parsed.end_byte
+        })
+    }
+
+    /// create an AST from the query string and record the location of the statement
+    /// elements in `ParsedStatement::spans`.
+    pub fn parse_with_spans(cassandra_statement: &str) -> CassandraAST {
+        let mut ast = CassandraAST::new(cassandra_statement);
+        {
+            let root = ast.tree.root_node();
+            let mut cursor = root.walk();
+            for node in root.children(&mut cursor) {
+                // the statements start at their node, as in `CassandraStatement::from_tree()`
+                if let Some(statement) = ast.statements.iter_mut().find(|parsed| {
+                    !matches!(parsed.statement, CassandraStatement::Describe(_))
+                        && parsed.start_byte == node.start_byte()
+                }) {
+                    statement.spans = Some(StatementSpans::new(&node));
+                }
+            }
+        }
+        ast
+    }
+
     /// returns true if the parsing exposed an error in the query
     pub fn has_error(&self) -> bool {
-        self.tree.root_node().has_error()
+        !self.errors().is_empty()
     }
 
     /// retrieves the query value for the node (word or phrase enclosed by the node)
@@ -2037,11 +2708,186 @@ impl CassandraAST {
     pub fn extract_text(&self, statement: &ParsedStatement) -> &str {
         &self.text.as_str()[statement.start_byte..statement.end_byte]
     }
+
+    /// returns the errors found while parsing the query, in the order they occur in the text.
+    pub fn errors(&self) -> Vec<ParseError> {
+        let mut result = vec![];
+        let mut cursor = self.tree.root_node().walk();
+        self.collect_errors(&mut cursor, &mut result);
+        result.retain(|error| !self.is_describe(error.offset()));
+        result.extend(self.statement_errors.iter().cloned());
+        result.sort_by_key(|error| error.offset());
+        result
+    }
+
+    /// collects the errors from the cursor node and its siblings.
+    fn collect_errors(&self, cursor: &mut TreeCursor, result: &mut Vec<ParseError>) {
+        loop {
+            let node = cursor.node();
+            if node.is_error() {
+                result.push(self.describe_error(&node));
+            } else if node.is_missing() {
+                result.push(ParseError::Syntax {
+                    message: format!("missing '{}'", node.kind()),
+                    offset: node.start_byte(),
+                });
+            } else if node.has_error() && cursor.goto_first_child() {
+                self.collect_errors(cursor, result);
+                cursor.goto_parent();
+            }
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+
+    /// creates the error for an error node.  Known SQL constructs that CQL does not support
+    /// are reported specifically.
+    fn describe_error(&self, node: &Node) -> ParseError {
+        let text = self.node_text(node);
+        if let Some(idx) = CassandraAST::find_keyword(&text, "OR") {
+            return ParseError::Syntax {
+                message: "OR is not supported in CQL WHERE clauses, use IN or separate queries"
+                    .to_string(),
+                offset: node.start_byte() + idx,
+            };
+        }
+        if let Some(idx) = CassandraAST::find_keyword(&text, "CONSISTENCY") {
+            // CQL 2 consistency hints, the `USING` may have been consumed by the statement.
+            let start = node.start_byte() + idx;
+            let before = self.text[..start].trim_end();
+            return ParseError::UnsupportedClause {
+                clause: "USING CONSISTENCY".to_string(),
+                offset: before
+                    .len()
+                    .checked_sub(5)
+                    .filter(|using| {
+                        before
+                            .get(*using..)
+                            .map_or(false, |word| word.eq_ignore_ascii_case("USING"))
+                    })
+                    .unwrap_or(start),
+            };
+        }
+        ParseError::Syntax {
+            message: format!("syntax error at '{}'", text.trim()),
+            offset: node.start_byte(),
+        }
+    }
+
+    /// returns the byte index of the first occurrence of the keyword as a whole word outside
+    /// of a quoted string.
+    fn find_keyword(text: &str, keyword: &str) -> Option<usize> {
+        let mut in_quote = false;
+        let mut word_start = None;
+        for (idx, c) in text
+            .char_indices()
+            .chain(std::iter::once((text.len(), ' ')))
+        {
+            if c == '\'' {
+                in_quote = !in_quote;
+            }
+            if !in_quote && (c.is_alphanumeric() || c == '_') {
+                word_start.get_or_insert(idx);
+            } else if let Some(start) = word_start.take() {
+                if text[start..idx].eq_ignore_ascii_case(keyword) {
+                    return Some(start);
+                }
+            }
+        }
+        None
+    }
 }
 
+/// an error in the query text.
+#[derive(PartialEq, Debug, Clone)]
+pub enum ParseError {
+    /// the text is not valid CQL.
+    Syntax {
+        /// the description of the error.
+        message: String,
+        /// the byte offset of the error within the query text.
+        offset: usize,
+    },
+    /// the clause is valid for some Cassandra distributions but is not enabled in this build.
+    UnsupportedClause {
+        /// the clause keywords (e.g. `GROUP BY`).
+        clause: String,
+        /// the byte offset of the clause within the query text.
+        offset: usize,
+    },
+    /// the statement is not allowed within a batch.
+    InvalidBatchStatement {
+        /// the reason the statement is not allowed.
+        error: BatchError,
+        /// the position of the statement within the batch, the statement carrying the
+        /// `BEGIN BATCH` is 0.
+        index: usize,
+        /// the location of the statement within the query text.
+        span: Span,
+    },
+}
+
+impl ParseError {
+    /// the byte offset of the error within the query text.
+    pub fn offset(&self) -> usize {
+        match self {
+            ParseError::Syntax { offset, .. } | ParseError::UnsupportedClause { offset, .. } => {
+                *offset
+            }
+            ParseError::InvalidBatchStatement { span, .. } => span.start,
+        }
+    }
+
+    /// returns the error with the offsets moved by `by` bytes.
+    fn shifted(self, by: usize) -> ParseError {
+        match self {
+            ParseError::Syntax { message, offset } => ParseError::Syntax {
+                message,
+                offset: offset + by,
+            },
+            ParseError::UnsupportedClause { clause, offset } => ParseError::UnsupportedClause {
+                clause,
+                offset: offset + by,
+            },
+            ParseError::InvalidBatchStatement { error, index, span } => {
+                ParseError::InvalidBatchStatement {
+                    error,
+                    index,
+                    span: Span {
+                        start: span.start + by,
+                        end: span.end + by,
+                    },
+                }
+            }
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::Syntax { message, offset } => {
+                write!(f, "{} at offset {}", message, offset)
+            }
+            ParseError::UnsupportedClause { clause, offset } => {
+                write!(f, "{} is not supported at offset {}", clause, offset)
+            }
+            ParseError::InvalidBatchStatement { error, index, span } => write!(
+                f,
+                "{} (batch statement {}) at offset {}",
+                error, index, span.start
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
 #[cfg(test)]
 mod tests {
-    use crate::cassandra_ast::{CassandraAST, ParsedStatement};
+    use crate::batch::BatchError;
+    use crate::cassandra_ast::{CassandraAST, CassandraParser, ParseError, ParsedStatement, Span};
     use crate::cassandra_statement::CassandraStatement;
 
     #[test]
@@ -2052,6 +2898,7 @@ mod tests {
             statement: CassandraStatement::Unknown(statement.to_string()),
             start_byte: 0,
             end_byte: 28,
+            spans: None,
         };
 
         let ast = CassandraAST::new(statement);
@@ -2070,12 +2917,14 @@ mod tests {
                 statement: select.clone(),
                 start_byte: 0,
                 end_byte: 17,
+                spans: None,
             },
             ParsedStatement {
                 has_error: true,
                 statement: CassandraStatement::Unknown(statement.to_string()),
                 start_byte: 18,
                 end_byte: 41,
+                spans: None,
             },
         ];
         let ast = CassandraAST::new(statement);
@@ -2096,12 +2945,14 @@ mod tests {
                 statement: select1.clone(),
                 start_byte: 0,
                 end_byte: 17,
+                spans: None,
             },
             ParsedStatement {
                 has_error: false,
                 statement: select2.clone(),
                 start_byte: 19,
                 end_byte: 36,
+                spans: None,
             },
         ];
 
@@ -2122,4 +2973,243 @@ mod tests {
         assert_eq!(36, result.end_byte);
         assert_eq!(stmt.to_string(), result.statement.to_string());
     }
+
+    #[test]
+    fn test_or_in_where_clause() {
+        let ast = CassandraAST::new("SELECT * FROM t WHERE a = 1 OR b = 2");
+        assert!(ast.has_error());
+        let errors = ast.errors();
+        assert_eq!(
+            ParseError::Syntax {
+                message: "OR is not supported in CQL WHERE clauses, use IN or separate queries"
+                    .to_string(),
+                offset: 28,
+            },
+            errors[0]
+        );
+        assert_eq!(
+            "OR is not supported in CQL WHERE clauses, use IN or separate queries at offset 28",
+            errors[0].to_string()
+        );
+        assert!(CassandraAST::new("SELECT * FROM t WHERE a = 'x OR y'")
+            .errors()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_split_statements() {
+        let script = "SELECT ';' FROM t; /* ; */ SELECT \"a;b\" FROM u -- ;\n;; $$ ; $$";
+        assert_eq!(
+            vec![
+                (0, "SELECT ';' FROM t"),
+                (18, " /* ; */ SELECT \"a;b\" FROM u -- ;\n"),
+                (53, ""),
+                (54, " $$ ; $$"),
+            ],
+            CassandraParser::split_statements(script)
+        );
+    }
+
+    #[test]
+    fn test_parse_many() {
+        let script = "CREATE TABLE ks.t (k int PRIMARY KEY, v text);\n\
+            CREATE FUNCTION ks.f (a int) RETURNS NULL ON NULL INPUT RETURNS int LANGUAGE java AS $$ return a; $$;\n\
+            ;\n\
+            SELECT v FROM ks.t WHERE k = 1;";
+        let statements = CassandraParser::parse_many(script).unwrap();
+        assert_eq!(3, statements.len());
+        assert!(matches!(statements[0], CassandraStatement::CreateTable(_)));
+        assert!(matches!(
+            statements[1],
+            CassandraStatement::CreateFunction(_)
+        ));
+        assert_eq!("SELECT v FROM ks.t WHERE k = 1", statements[2].to_string());
+
+        let error = CassandraParser::parse_many("SELECT * FROM t; SELECT * FROM").unwrap_err();
+        assert!(error.offset() >= 16);
+    }
+
+    #[test]
+    fn test_invalid_batch_statements() {
+        let qry = "BEGIN BATCH INSERT INTO t (a) VALUES (1); SELECT * FROM t; BEGIN BATCH DELETE FROM t WHERE pk = 1; APPLY BATCH; SELECT * FROM t";
+        let ast = CassandraAST::new(qry);
+        assert!(ast.has_error());
+        assert_eq!(
+            vec![
+                ParseError::InvalidBatchStatement {
+                    error: BatchError::NotDml("SELECT"),
+                    index: 1,
+                    span: Span { start: 42, end: 57 },
+                },
+                ParseError::InvalidBatchStatement {
+                    error: BatchError::NestedBatch,
+                    index: 2,
+                    span: Span { start: 59, end: 97 },
+                },
+            ],
+            ast.errors()
+        );
+        assert_eq!(
+            "SELECT is not allowed in a batch (batch statement 1) at offset 42",
+            ast.errors()[0].to_string()
+        );
+        assert_eq!(5, ast.statements.len());
+        assert!(!ast.statements[0].has_error);
+        assert!(ast.statements[1].has_error);
+        assert_eq!(
+            CassandraStatement::Unknown("SELECT * FROM t".to_string()),
+            ast.statements[1].statement
+        );
+        assert_eq!(
+            CassandraStatement::Unknown("BEGIN BATCH DELETE FROM t WHERE pk = 1".to_string()),
+            ast.statements[2].statement
+        );
+        assert!(!ast.statements[4].has_error);
+        assert_eq!("SELECT", ast.statements[4].statement.short_name());
+
+        let ast = CassandraAST::new(
+            "BEGIN BATCH INSERT INTO t (a) VALUES (1); DELETE FROM t WHERE pk = 1; APPLY BATCH",
+        );
+        assert!(!ast.has_error());
+        assert!(ast.errors().is_empty());
+    }
+
+    #[test]
+    fn test_find_keyword() {
+        assert_eq!(Some(2), CassandraAST::find_keyword("1 or b = 2", "OR"));
+        assert_eq!(None, CassandraAST::find_keyword("ORDER BY b", "OR"));
+        assert_eq!(None, CassandraAST::find_keyword("'a OR b'", "OR"));
+        assert_eq!(Some(9), CassandraAST::find_keyword("'a OR b' OR", "OR"));
+    }
+
+    #[test]
+    fn test_warnings() {
+        let ast = CassandraAST::new("INSERT INTO t (a, b, c) VALUES (1, 2)");
+        assert!(!ast.has_error());
+        assert_eq!(
+            vec!["3 columns were specified but 2 values were provided".to_string()],
+            ast.statements[0].warnings()
+        );
+        assert_eq!(
+            "INSERT INTO t (a, b, c) VALUES (1, 2)",
+            ast.statements[0].statement.to_string()
+        );
+        let ast = CassandraAST::new("INSERT INTO t (a, b) VALUES (1, 2)");
+        assert!(ast.statements[0].warnings().is_empty());
+    }
+
+    #[test]
+    fn test_using_consistency_unsupported() {
+        let ast = CassandraAST::new("SELECT * FROM t USING CONSISTENCY QUORUM WHERE pk = 1");
+        assert!(ast.has_error());
+        assert_eq!(
+            ParseError::UnsupportedClause {
+                clause: "USING CONSISTENCY".to_string(),
+                offset: 16,
+            },
+            ast.errors()[0]
+        );
+        let ast = CassandraAST::new("UPDATE t USING CONSISTENCY ONE SET col = 1 WHERE pk = 1");
+        assert!(ast.has_error());
+        assert_eq!(
+            ParseError::UnsupportedClause {
+                clause: "USING CONSISTENCY".to_string(),
+                offset: 9,
+            },
+            ast.errors()[0]
+        );
+    }
+
+    #[test]
+    fn test_invalid_data_type() {
+        let qry = "CREATE TABLE t (id int PRIMARY KEY, e vector<float>)";
+        let ast = CassandraAST::new(qry);
+        assert!(ast.has_error());
+        assert!(ast.statements[0].has_error);
+        assert_eq!(
+            CassandraStatement::Unknown(qry.to_string()),
+            ast.statements[0].statement
+        );
+        assert_eq!(
+            vec![ParseError::Syntax {
+                message: "unexpected end of data type".to_string(),
+                offset: 38,
+            }],
+            ast.errors()
+        );
+    }
+
+    #[test]
+    fn test_unknown_privilege() {
+        let qry = "GRANT TRUNCATE ON TABLE t TO role";
+        let ast = CassandraAST::new(qry);
+        assert!(ast.has_error());
+        assert!(ast.statements[0].has_error);
+        // an unknown privilege is never read as another privilege
+        let statements = CassandraStatement::from_tree(&ast.tree, qry);
+        assert!(statements[0].has_error);
+        assert_eq!(
+            CassandraStatement::Unknown(qry.to_string()),
+            statements[0].statement
+        );
+    }
+
+    #[test]
+    fn test_using_timeout() {
+        // the Scylla `USING TIMEOUT` option is not in the grammar.
+        let ast =
+            CassandraAST::new("INSERT INTO t (pk, v) VALUES (1, 2) USING TTL 60 AND TIMEOUT 5s");
+        assert!(ast.has_error());
+        assert!(ast
+            .errors()
+            .iter()
+            .all(|error| matches!(error, ParseError::Syntax { .. })));
+    }
+
+    #[cfg(not(feature = "dse"))]
+    #[test]
+    fn test_group_by_unsupported() {
+        let ast =
+            CassandraAST::new("SELECT col, count(*) FROM t GROUP BY col ORDER BY col LIMIT 5");
+        assert!(ast.has_error());
+        assert!(ast.statements[0].has_error);
+        assert_eq!(
+            vec![ParseError::UnsupportedClause {
+                clause: "GROUP BY".to_string(),
+                offset: 28,
+            }],
+            ast.errors()
+        );
+        // the clauses after `GROUP BY` are still parsed
+        match &ast.statements[0].statement {
+            CassandraStatement::Select(select) => {
+                assert_eq!(1, select.order.len());
+                assert_eq!(Some(5), select.limit);
+            }
+            _ => panic!("not a select"),
+        }
+    }
+
+    #[test]
+    fn test_spans() {
+        let stmt = "SELECT a FROM ks.tbl WHERE x = 1 AND y = ?";
+        assert!(CassandraAST::new(stmt).statements[0].spans.is_none());
+        let ast = CassandraAST::parse_with_spans(stmt);
+        let spans = ast.statements[0].spans.as_ref().unwrap();
+        assert_eq!(Span { start: 0, end: 42 }, spans.statement);
+        assert_eq!(vec![Span { start: 14, end: 20 }], spans.names);
+        assert_eq!("ks.tbl", &stmt[spans.names[0].start..spans.names[0].end]);
+        assert_eq!(
+            vec![Span { start: 27, end: 32 }, Span { start: 37, end: 42 }],
+            spans.relations
+        );
+        assert_eq!(
+            vec!["a", "x", "1", "y", "?"],
+            spans
+                .operands
+                .iter()
+                .map(|span| &stmt[span.start..span.end])
+                .collect::<Vec<&str>>()
+        );
+    }
 }
@@ -2,9 +2,14 @@ use crate::aggregate::Aggregate;
 use crate::alter_materialized_view::AlterMaterializedView;
 use crate::alter_table::AlterTable;
 use crate::alter_type::AlterType;
-use crate::cassandra_ast::{CassandraParser, ParsedStatement};
-use crate::common::{FQName, Privilege};
-use crate::common_drop::CommonDrop;
+use crate::batch::Batch;
+use crate::begin_batch::BeginBatch;
+use crate::cassandra_ast::{CassandraParser, ParseError, ParsedStatement};
+use crate::common::{
+    normalize_name, FQName, Operand, Privilege, RelationElement, RelationOperator, Resource,
+    TtlTimestamp,
+};
+use crate::common_drop::{CommonDrop, DropKind};
 use crate::create_functon::CreateFunction;
 use crate::create_index::CreateIndex;
 use crate::create_keyspace::CreateKeyspace;
@@ -14,13 +19,16 @@ use crate::create_trigger::CreateTrigger;
 use crate::create_type::CreateType;
 use crate::create_user::CreateUser;
 use crate::delete::Delete;
+use crate::describe::{Describe, DescribeKind};
 use crate::drop_trigger::DropTrigger;
-use crate::insert::Insert;
+use crate::insert::{Insert, InsertValues};
 use crate::list_role::ListRole;
 use crate::role_common::RoleCommon;
-use crate::select::Select;
-use crate::update::Update;
+use crate::select::{CountArgument, Select, SelectElement};
+use crate::update::{AssignmentOperator, Update};
+use std::collections::{BTreeMap, HashSet};
 use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 use tree_sitter::{Node, Tree};
 
 /// The Supported Cassandra CQL3 statements
@@ -35,6 +43,9 @@ pub enum CassandraStatement {
     AlterType(AlterType),
     AlterUser(CreateUser),
     ApplyBatch,
+    /// a complete batch, see `Batch::from_statements()`.  The parser returns the statements
+    /// of a batch separately so this is never produced by parsing.
+    Batch(Batch),
     CreateAggregate(Aggregate),
     CreateFunction(CreateFunction),
     CreateIndex(CreateIndex),
@@ -46,6 +57,7 @@ pub enum CassandraStatement {
     CreateType(CreateType),
     CreateUser(CreateUser),
     Delete(Delete),
+    Describe(Describe),
     DropAggregate(CommonDrop),
     DropFunction(CommonDrop),
     DropIndex(CommonDrop),
@@ -85,8 +97,11 @@ impl CassandraStatement {
         result
     }
 
-    /// extract the cassandra statement from an AST node.
+    /// extract the cassandra statement from an AST node.  A statement with an unknown privilege
+    /// is returned as `CassandraStatement::Unknown` containing its text.
     pub fn from_node(node: &Node, source: &str) -> CassandraStatement {
+        let unknown =
+            || CassandraStatement::Unknown(source[node.start_byte()..node.end_byte()].to_string());
         match node.kind() {
             "alter_keyspace" => CassandraStatement::AlterKeyspace(
                 CassandraParser::parse_keyspace_data(node, source),
@@ -170,17 +185,18 @@ impl CassandraStatement {
             "drop_user" => {
                 CassandraStatement::DropUser(CassandraParser::parse_standard_drop(node, source))
             }
-            "grant" => CassandraStatement::Grant(CassandraParser::parse_privilege(node, source)),
+            "grant" => CassandraParser::parse_privilege(node, source)
+                .map_or_else(|_| unknown(), CassandraStatement::Grant),
             "insert_statement" => {
                 CassandraStatement::Insert(CassandraParser::parse_insert(node, source))
             }
-            "list_permissions" => {
-                CassandraStatement::ListPermissions(CassandraParser::parse_privilege(node, source))
-            }
+            "list_permissions" => CassandraParser::parse_privilege(node, source)
+                .map_or_else(|_| unknown(), CassandraStatement::ListPermissions),
             "list_roles" => {
                 CassandraStatement::ListRoles(CassandraParser::parse_list_role_data(node, source))
             }
-            "revoke" => CassandraStatement::Revoke(CassandraParser::parse_privilege(node, source)),
+            "revoke" => CassandraParser::parse_privilege(node, source)
+                .map_or_else(|_| unknown(), CassandraStatement::Revoke),
             "select_statement" => {
                 CassandraStatement::Select(CassandraParser::parse_select(node, source))
             }
@@ -204,6 +220,10 @@ impl CassandraStatement {
             CassandraStatement::AlterType(named) => named.name.extract_keyspace(default),
             CassandraStatement::AlterUser(_) => default,
             CassandraStatement::ApplyBatch => default,
+            CassandraStatement::Batch(batch) => batch
+                .iter()
+                .next()
+                .map_or(default, |statement| statement.get_keyspace(default)),
             CassandraStatement::CreateAggregate(named) => named.name.extract_keyspace(default),
             CassandraStatement::CreateFunction(named) => named.name.extract_keyspace(default),
             CassandraStatement::CreateIndex(named) => named.table.extract_keyspace(default),
@@ -217,6 +237,11 @@ impl CassandraStatement {
             CassandraStatement::CreateType(named) => named.name.extract_keyspace(default),
             CassandraStatement::CreateUser(_) => default,
             CassandraStatement::Delete(named) => named.table_name.extract_keyspace(default),
+            CassandraStatement::Describe(describe) => match (&describe.kind, &describe.name) {
+                (DescribeKind::Keyspace, Some(name)) => &name.name,
+                (_, Some(name)) => name.extract_keyspace(default),
+                (_, None) => default,
+            },
             CassandraStatement::DropAggregate(named) => named.name.extract_keyspace(default),
             CassandraStatement::DropFunction(named) => named.name.extract_keyspace(default),
             CassandraStatement::DropIndex(named) => named.name.extract_keyspace(default),
@@ -249,6 +274,7 @@ impl CassandraStatement {
             CassandraStatement::AlterType(_) => "ALTER TYPE",
             CassandraStatement::AlterUser(_) => "ALTER USER",
             CassandraStatement::ApplyBatch => "APPLY BATCH",
+            CassandraStatement::Batch(_) => "BATCH",
             CassandraStatement::CreateAggregate(_) => "CREATE AGGREGATE",
             CassandraStatement::CreateFunction(_) => "CREATE FUNCTION",
             CassandraStatement::CreateIndex(_) => "CREATE INDEX",
@@ -260,6 +286,7 @@ impl CassandraStatement {
             CassandraStatement::CreateType(_) => "CREATE TYPE",
             CassandraStatement::CreateUser(_) => "CREATE USER",
             CassandraStatement::Delete(_) => "DELETE",
+            CassandraStatement::Describe(_) => "DESCRIBE",
             CassandraStatement::DropAggregate(_) => "DROP AGGREGATE",
             CassandraStatement::DropFunction(_) => "DROP FUNCTION",
             CassandraStatement::DropIndex(_) => "DROP INDEX",
@@ -283,9 +310,14 @@ impl CassandraStatement {
         }
     }
 
-    /// returns the table name from the statement if there is one.
+    /// returns the table name from the statement if there is one.  For a batch this is the
+    /// table of the first statement.
     pub fn get_table_name(&self) -> Option<&FQName> {
         match self {
+            CassandraStatement::Batch(batch) => batch
+                .iter()
+                .next()
+                .and_then(|statement| statement.get_table_name()),
             CassandraStatement::AlterTable(t) => Some(&t.name),
             CassandraStatement::CreateIndex(i) => Some(&i.table),
             CassandraStatement::CreateMaterializedView(m) => Some(&m.table),
@@ -300,6 +332,841 @@ impl CassandraStatement {
             _ => None,
         }
     }
+
+    /// creates the `DROP` statement for the kind of object.
+    pub fn from_drop(kind: DropKind, drop: CommonDrop) -> CassandraStatement {
+        match kind {
+            DropKind::Aggregate => CassandraStatement::DropAggregate(drop),
+            DropKind::Function => CassandraStatement::DropFunction(drop),
+            DropKind::Index => CassandraStatement::DropIndex(drop),
+            DropKind::Keyspace => CassandraStatement::DropKeyspace(drop),
+            DropKind::MaterializedView => CassandraStatement::DropMaterializedView(drop),
+            DropKind::Role => CassandraStatement::DropRole(drop),
+            DropKind::Table => CassandraStatement::DropTable(drop),
+            DropKind::Type => CassandraStatement::DropType(drop),
+            DropKind::User => CassandraStatement::DropUser(drop),
+        }
+    }
+
+    /// returns the kind and data of the `DROP` statements that share `CommonDrop`.  Returns
+    /// `None` for other statements, including `DROP TRIGGER`.
+    pub fn as_drop(&self) -> Option<(DropKind, &CommonDrop)> {
+        match self {
+            CassandraStatement::DropAggregate(drop) => Some((DropKind::Aggregate, drop)),
+            CassandraStatement::DropFunction(drop) => Some((DropKind::Function, drop)),
+            CassandraStatement::DropIndex(drop) => Some((DropKind::Index, drop)),
+            CassandraStatement::DropKeyspace(drop) => Some((DropKind::Keyspace, drop)),
+            CassandraStatement::DropMaterializedView(drop) => {
+                Some((DropKind::MaterializedView, drop))
+            }
+            CassandraStatement::DropRole(drop) => Some((DropKind::Role, drop)),
+            CassandraStatement::DropTable(drop) => Some((DropKind::Table, drop)),
+            CassandraStatement::DropType(drop) => Some((DropKind::Type, drop)),
+            CassandraStatement::DropUser(drop) => Some((DropKind::User, drop)),
+            _ => None,
+        }
+    }
+
+    /// returns true if the statement is a lightweight transaction.  That is, it uses a
+    /// conditional (`IF NOT EXISTS`, `IF EXISTS` or `IF condition`).
+    pub fn is_lwt(&self) -> bool {
+        match self {
+            CassandraStatement::Batch(batch) => batch.iter().any(|statement| statement.is_lwt()),
+            CassandraStatement::Delete(delete) => delete.is_lwt(),
+            CassandraStatement::Insert(insert) => insert.is_lwt(),
+            CassandraStatement::Update(update) => update.is_lwt(),
+            _ => false,
+        }
+    }
+
+    /// returns true if the statement can safely be retried, that is executing it more than
+    /// once has the same effect as executing it once.  The column types are not known so
+    /// this is a heuristic:
+    ///  * reads (`SELECT`, `DESCRIBE` and the `LIST` commands) are idempotent.
+    ///  * lightweight transactions are not, see `is_lwt()`.
+    ///  * an `INSERT` is idempotent unless a value is a function call such as `now()`.
+    ///  * an `UPDATE` is idempotent if every assignment is, see
+    ///    `AssignmentElement::is_idempotent()`.  Counter updates and list appends are not.
+    ///  * a `DELETE` is idempotent.
+    ///  * a batch is idempotent if all of its statements are.
+    ///  * other statements are reported as not idempotent.
+    pub fn is_idempotent(&self) -> bool {
+        match self {
+            CassandraStatement::Select(_)
+            | CassandraStatement::Describe(_)
+            | CassandraStatement::ListPermissions(_)
+            | CassandraStatement::ListRoles(_) => true,
+            CassandraStatement::Batch(batch) => {
+                batch.iter().all(|statement| statement.is_idempotent())
+            }
+            CassandraStatement::Delete(delete) => !delete.is_lwt(),
+            CassandraStatement::Insert(insert) => insert.is_idempotent(),
+            CassandraStatement::Update(update) => update.is_idempotent(),
+            _ => false,
+        }
+    }
+
+    /// returns all of the keyspaces named anywhere in the statement, including the keyspaces
+    /// of qualified function calls such as `ks.f(a)`.  Names that are not qualified with a
+    /// keyspace do not contribute, so the set is empty for statements that only use the
+    /// default keyspace.  Keyspace statements (e.g. `USE`, `CREATE KEYSPACE`) return the
+    /// keyspace they operate on.
+    pub fn referenced_keyspaces(&self) -> HashSet<String> {
+        match self {
+            CassandraStatement::AlterKeyspace(keyspace)
+            | CassandraStatement::CreateKeyspace(keyspace) => {
+                return HashSet::from([keyspace.name.clone()])
+            }
+            CassandraStatement::DropKeyspace(drop) => {
+                return HashSet::from([drop.name.name.clone()])
+            }
+            CassandraStatement::Use(keyspace) => return HashSet::from([keyspace.clone()]),
+            CassandraStatement::Grant(privilege)
+            | CassandraStatement::ListPermissions(privilege)
+            | CassandraStatement::Revoke(privilege) => {
+                if let Some(Resource::AllFunctions(Some(keyspace)))
+                | Some(Resource::Keyspace(keyspace)) = &privilege.resource
+                {
+                    return HashSet::from([keyspace.clone()]);
+                }
+            }
+            CassandraStatement::Describe(Describe {
+                kind: DescribeKind::Keyspace,
+                name: Some(name),
+            }) => return HashSet::from([name.name.clone()]),
+            _ => {}
+        }
+        let mut result: HashSet<String> = self
+            .fqnames()
+            .filter_map(|name| name.keyspace.clone())
+            .collect();
+        self.function_keyspaces(&mut result);
+        result
+    }
+
+    /// collects the keyspaces of the qualified function calls in the statement.
+    fn function_keyspaces(&self, result: &mut HashSet<String>) {
+        let mut operands: Vec<&Operand> = vec![];
+        match self {
+            CassandraStatement::Batch(batch) => {
+                for statement in batch.iter() {
+                    statement.function_keyspaces(result);
+                }
+            }
+            CassandraStatement::Delete(delete) => {
+                operands.extend(CassandraStatement::relation_operands(&delete.where_clause));
+                operands.extend(CassandraStatement::relation_operands(&delete.if_clause));
+            }
+            CassandraStatement::Insert(insert) => {
+                if let InsertValues::Values(values) = &insert.values {
+                    operands.extend(values);
+                }
+            }
+            CassandraStatement::Select(select) => {
+                for element in &select.columns {
+                    if let SelectElement::Function(function) = element {
+                        result.extend(CassandraStatement::function_keyspace(&function.text));
+                    }
+                }
+                operands.extend(CassandraStatement::relation_operands(&select.where_clause));
+            }
+            CassandraStatement::Update(update) => {
+                for assignment in &update.assignments {
+                    operands.push(&assignment.value);
+                    if let Some(AssignmentOperator::Plus(value))
+                    | Some(AssignmentOperator::Minus(value)) = &assignment.operator
+                    {
+                        operands.push(value);
+                    }
+                }
+                operands.extend(CassandraStatement::relation_operands(&update.where_clause));
+                operands.extend(CassandraStatement::relation_operands(&update.if_clause));
+            }
+            _ => {}
+        }
+        while let Some(operand) = operands.pop() {
+            match operand {
+                Operand::Func(text) => {
+                    result.extend(CassandraStatement::function_keyspace(text));
+                }
+                Operand::Tuple(values) | Operand::Collection(values) => operands.extend(values),
+                _ => {}
+            }
+        }
+    }
+
+    fn relation_operands(relations: &[RelationElement]) -> impl Iterator<Item = &Operand> {
+        relations
+            .iter()
+            .flat_map(|relation| [&relation.obj, &relation.value])
+    }
+
+    /// returns the keyspace of a function call such as `ks.f(a)`.
+    fn function_keyspace(text: &str) -> Option<String> {
+        let (name, _) = text.split_once('(')?;
+        FQName::from(name.trim()).keyspace
+    }
+
+    /// returns a copy of the statement that is safe to log.  Role and user passwords and the
+    /// role options that name a password, secret or token are replaced with `'*****'`, all
+    /// other statements are returned unchanged.
+    pub fn redacted(&self) -> CassandraStatement {
+        let mut result = self.clone();
+        match &mut result {
+            CassandraStatement::AlterRole(role) | CassandraStatement::CreateRole(role) => {
+                role.redact()
+            }
+            CassandraStatement::AlterUser(user) | CassandraStatement::CreateUser(user) => {
+                user.redact_password()
+            }
+            _ => {}
+        }
+        result
+    }
+
+    /// returns every qualified name in the statement: the tables, views, indexes, functions,
+    /// aggregates, types and triggers it names.  Keyspace names (e.g. in `USE`) are not
+    /// qualified names and are not returned.
+    pub fn fqnames(&self) -> impl Iterator<Item = &FQName> {
+        let names: Vec<&FQName> = match self {
+            CassandraStatement::Grant(privilege)
+            | CassandraStatement::ListPermissions(privilege)
+            | CassandraStatement::Revoke(privilege) => match &privilege.resource {
+                Some(Resource::Function(name)) | Some(Resource::Table(name)) => vec![name],
+                _ => vec![],
+            },
+            CassandraStatement::AlterMaterializedView(view) => vec![&view.name],
+            CassandraStatement::AlterTable(table) => vec![&table.name],
+            CassandraStatement::AlterType(alter_type) => vec![&alter_type.name],
+            CassandraStatement::Batch(batch) => batch
+                .iter()
+                .flat_map(|statement| statement.fqnames())
+                .collect(),
+            CassandraStatement::CreateAggregate(aggregate) => vec![&aggregate.name],
+            CassandraStatement::CreateFunction(function) => vec![&function.name],
+            CassandraStatement::CreateIndex(index) => vec![&index.table],
+            CassandraStatement::CreateMaterializedView(view) => vec![&view.name, &view.table],
+            CassandraStatement::CreateTable(table) => vec![&table.name],
+            CassandraStatement::CreateTrigger(trigger) => vec![&trigger.name],
+            CassandraStatement::CreateType(create_type) => vec![&create_type.name],
+            CassandraStatement::Delete(delete) => vec![&delete.table_name],
+            CassandraStatement::Describe(describe) => match (&describe.kind, &describe.name) {
+                (DescribeKind::Keyspace, _) | (_, None) => vec![],
+                (_, Some(name)) => vec![name],
+            },
+            CassandraStatement::DropAggregate(drop)
+            | CassandraStatement::DropFunction(drop)
+            | CassandraStatement::DropIndex(drop)
+            | CassandraStatement::DropMaterializedView(drop)
+            | CassandraStatement::DropTable(drop)
+            | CassandraStatement::DropType(drop) => vec![&drop.name],
+            CassandraStatement::DropTrigger(trigger) => vec![&trigger.name, &trigger.table],
+            CassandraStatement::Insert(insert) => vec![&insert.table_name],
+            CassandraStatement::Select(select) => vec![&select.table_name],
+            CassandraStatement::Truncate(table) => vec![table],
+            CassandraStatement::Update(update) => vec![&update.table_name],
+            CassandraStatement::AlterKeyspace(_)
+            | CassandraStatement::AlterRole(_)
+            | CassandraStatement::AlterUser(_)
+            | CassandraStatement::ApplyBatch
+            | CassandraStatement::CreateKeyspace(_)
+            | CassandraStatement::CreateRole(_)
+            | CassandraStatement::CreateUser(_)
+            | CassandraStatement::DropKeyspace(_)
+            | CassandraStatement::DropRole(_)
+            | CassandraStatement::DropUser(_)
+            | CassandraStatement::ListRoles(_)
+            | CassandraStatement::Unknown(_)
+            | CassandraStatement::Use(_) => vec![],
+        };
+        names.into_iter()
+    }
+
+    /// removes the keyspace from every qualified name in the statement so that it runs in the
+    /// session keyspace.  Statements that only name a keyspace (e.g. `USE`) are not changed.
+    pub fn strip_keyspace(&mut self) {
+        for name in self.fqnames_mut() {
+            name.keyspace = None;
+        }
+    }
+
+    /// replaces every qualified name that matches `old` (see `FQName::eq_ignoring_case()`)
+    /// with `new`.  Literal values are never changed.  Returns the number of names replaced.
+    pub fn replace_table(&mut self, old: &FQName, new: &FQName) -> usize {
+        let mut count = 0;
+        for name in self.fqnames_mut() {
+            if name.eq_ignoring_case(old) {
+                *name = new.clone();
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// moves every qualified name in the keyspace `from` to the keyspace `to`.  Keyspace names
+    /// are compared as the server would (see `FQName::eq_ignoring_case()`).  If `from` is
+    /// empty the unqualified names, which refer to the default keyspace, are qualified with
+    /// `to`.  The names of the keyspace statements (e.g. `USE`) are not changed.  Returns the
+    /// number of names changed.
+    pub fn rename_keyspace(&mut self, from: &str, to: &str) -> usize {
+        let from = if from.is_empty() {
+            None
+        } else {
+            Some(normalize_name(from))
+        };
+        let mut count = 0;
+        for name in self.fqnames_mut() {
+            if name.keyspace.as_deref().map(normalize_name) == from {
+                name.keyspace = Some(to.to_string());
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// returns every qualified name in the statement for modification, see `fqnames()`.
+    pub fn fqnames_mut(&mut self) -> impl Iterator<Item = &mut FQName> {
+        let names: Vec<&mut FQName> = match self {
+            CassandraStatement::Grant(privilege)
+            | CassandraStatement::ListPermissions(privilege)
+            | CassandraStatement::Revoke(privilege) => match &mut privilege.resource {
+                Some(Resource::Function(name)) | Some(Resource::Table(name)) => vec![name],
+                _ => vec![],
+            },
+            CassandraStatement::AlterMaterializedView(view) => vec![&mut view.name],
+            CassandraStatement::AlterTable(table) => vec![&mut table.name],
+            CassandraStatement::AlterType(alter_type) => vec![&mut alter_type.name],
+            CassandraStatement::Batch(batch) => batch
+                .iter_mut()
+                .flat_map(|statement| statement.fqnames_mut())
+                .collect(),
+            CassandraStatement::CreateAggregate(aggregate) => vec![&mut aggregate.name],
+            CassandraStatement::CreateFunction(function) => vec![&mut function.name],
+            CassandraStatement::CreateIndex(index) => vec![&mut index.table],
+            CassandraStatement::CreateMaterializedView(view) => {
+                vec![&mut view.name, &mut view.table]
+            }
+            CassandraStatement::CreateTable(table) => vec![&mut table.name],
+            CassandraStatement::CreateTrigger(trigger) => vec![&mut trigger.name],
+            CassandraStatement::CreateType(create_type) => vec![&mut create_type.name],
+            CassandraStatement::Delete(delete) => vec![&mut delete.table_name],
+            CassandraStatement::Describe(describe) => match (&describe.kind, &mut describe.name) {
+                (DescribeKind::Keyspace, _) | (_, None) => vec![],
+                (_, Some(name)) => vec![name],
+            },
+            CassandraStatement::DropAggregate(drop)
+            | CassandraStatement::DropFunction(drop)
+            | CassandraStatement::DropIndex(drop)
+            | CassandraStatement::DropMaterializedView(drop)
+            | CassandraStatement::DropTable(drop)
+            | CassandraStatement::DropType(drop) => vec![&mut drop.name],
+            CassandraStatement::DropTrigger(trigger) => vec![&mut trigger.name, &mut trigger.table],
+            CassandraStatement::Insert(insert) => vec![&mut insert.table_name],
+            CassandraStatement::Select(select) => vec![&mut select.table_name],
+            CassandraStatement::Truncate(table) => vec![table],
+            CassandraStatement::Update(update) => vec![&mut update.table_name],
+            CassandraStatement::AlterKeyspace(_)
+            | CassandraStatement::AlterRole(_)
+            | CassandraStatement::AlterUser(_)
+            | CassandraStatement::ApplyBatch
+            | CassandraStatement::CreateKeyspace(_)
+            | CassandraStatement::CreateRole(_)
+            | CassandraStatement::CreateUser(_)
+            | CassandraStatement::DropKeyspace(_)
+            | CassandraStatement::DropRole(_)
+            | CassandraStatement::DropUser(_)
+            | CassandraStatement::ListRoles(_)
+            | CassandraStatement::Unknown(_)
+            | CassandraStatement::Use(_) => vec![],
+        };
+        names.into_iter()
+    }
+
+    /// returns the columns written by the statement in the order they first appear: the
+    /// columns of an `INSERT`, the assigned columns of an `UPDATE` and the deleted columns of
+    /// a `DELETE`.  Element and field targets (e.g. `m['k']`) report the base column.  A
+    /// `Batch` reports the columns of all of its statements.
+    pub fn columns_written(&self) -> Vec<&str> {
+        let mut result = vec![];
+        match self {
+            CassandraStatement::Batch(batch) => {
+                for column in batch
+                    .iter()
+                    .flat_map(|statement| statement.columns_written())
+                {
+                    CassandraStatement::push_column(&mut result, column);
+                }
+            }
+            CassandraStatement::Insert(insert) => {
+                for column in insert.columns_written() {
+                    CassandraStatement::push_column(&mut result, column);
+                }
+            }
+            CassandraStatement::Update(update) => {
+                for assignment in &update.assignments {
+                    CassandraStatement::push_column(&mut result, assignment.name.column.as_str());
+                }
+            }
+            CassandraStatement::Delete(delete) => {
+                for column in delete.columns_written() {
+                    CassandraStatement::push_column(&mut result, column);
+                }
+            }
+            _ => {}
+        }
+        result
+    }
+
+    /// returns the columns read by the statement in the order they first appear: the
+    /// selected columns and the columns in the `WHERE` and `IF` clauses.  `SELECT *` is
+    /// reported as `*`.  Element and field references report the base column.  A `Batch`
+    /// reports the columns of all of its statements.
+    pub fn columns_read(&self) -> Vec<&str> {
+        let mut result = vec![];
+        let (where_clause, if_clause) = match self {
+            CassandraStatement::Batch(batch) => {
+                for column in batch.iter().flat_map(|statement| statement.columns_read()) {
+                    CassandraStatement::push_column(&mut result, column);
+                }
+                (&[][..], &[][..])
+            }
+            CassandraStatement::Select(select) => {
+                for element in &select.columns {
+                    match element {
+                        SelectElement::Star => CassandraStatement::push_column(&mut result, "*"),
+                        SelectElement::Column(named) => {
+                            CassandraStatement::push_column(&mut result, named.name.as_str())
+                        }
+                        SelectElement::Count(count) => match &count.argument {
+                            CountArgument::Column(column) => {
+                                CassandraStatement::push_column(&mut result, column.as_str())
+                            }
+                            CountArgument::Star | CountArgument::One => {}
+                        },
+                        SelectElement::Function(_) => {}
+                    }
+                }
+                (select.where_clause.as_slice(), &[][..])
+            }
+            CassandraStatement::Update(update) => {
+                (update.where_clause.as_slice(), update.if_clause.as_slice())
+            }
+            CassandraStatement::Delete(delete) => {
+                (delete.where_clause.as_slice(), delete.if_clause.as_slice())
+            }
+            _ => (&[][..], &[][..]),
+        };
+        for relation in where_clause.iter().chain(if_clause) {
+            CassandraStatement::operand_columns(&relation.obj, &mut result);
+        }
+        result
+    }
+
+    fn operand_columns<'a>(operand: &'a Operand, result: &mut Vec<&'a str>) {
+        match operand {
+            Operand::Column(column) => CassandraStatement::push_column(result, column.as_str()),
+            Operand::IndexedColumn(column) => {
+                CassandraStatement::push_column(result, column.column.as_str())
+            }
+            Operand::Tuple(operands) | Operand::Collection(operands) => {
+                for operand in operands {
+                    CassandraStatement::operand_columns(operand, result);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// adds the column to the result if it is not already present.
+    fn push_column<'a>(result: &mut Vec<&'a str>, column: &'a str) {
+        if !result.contains(&column) {
+            result.push(column);
+        }
+    }
+
+    /// returns the structural metrics for the statement.  Only `SELECT`, `UPDATE` and
+    /// `DELETE` statements have non-zero metrics.
+    pub fn complexity(&self) -> Complexity {
+        let (where_clause, projected_columns, allow_filtering) = match self {
+            CassandraStatement::Select(select) => (
+                select.where_clause.as_slice(),
+                select.columns.len(),
+                select.allow_filtering,
+            ),
+            CassandraStatement::Update(update) => (update.where_clause.as_slice(), 0, false),
+            CassandraStatement::Delete(delete) => (delete.where_clause.as_slice(), 0, false),
+            _ => (&[][..], 0, false),
+        };
+        let in_lists: Vec<usize> = where_clause
+            .iter()
+            .filter(|relation| relation.oper == RelationOperator::In)
+            .map(|relation| match &relation.value {
+                Operand::Tuple(values) | Operand::Collection(values) => values.len(),
+                _ => 1,
+            })
+            .collect();
+        Complexity {
+            where_relations: where_clause.len(),
+            projected_columns,
+            in_list_cardinality: if in_lists.is_empty() {
+                0
+            } else {
+                in_lists.iter().product()
+            },
+            allow_filtering,
+        }
+    }
+
+    /// replaces the positional bind markers (`?`) with the values in the order they appear
+    /// in the statement.  The number of values must match the number of positional markers.
+    pub fn bind(&mut self, values: &[Operand]) -> Result<(), BindError> {
+        let mut params: Vec<&mut Operand> = self
+            .params_mut()
+            .into_iter()
+            .filter(|p| matches!(p, Operand::Param(text) if text.eq("?")))
+            .collect();
+        if params.len() != values.len() {
+            return Err(BindError::ArityMismatch {
+                expected: params.len(),
+                actual: values.len(),
+            });
+        }
+        for (param, value) in params.iter_mut().zip(values) {
+            **param = value.clone();
+        }
+        Ok(())
+    }
+
+    /// replaces the named bind markers (`:name`) with the values from the map.  Every named
+    /// marker must have a value and every name in the map must match a marker.
+    pub fn bind_named(&mut self, map: &BTreeMap<String, Operand>) -> Result<(), BindError> {
+        let mut params: Vec<(String, &mut Operand)> = self
+            .params_mut()
+            .into_iter()
+            .filter_map(|p| match p {
+                Operand::Param(text) if text.starts_with(':') => Some((text[1..].to_string(), p)),
+                _ => None,
+            })
+            .collect();
+        if let Some((name, _)) = params.iter().find(|(name, _)| !map.contains_key(name)) {
+            return Err(BindError::MissingName(name.clone()));
+        }
+        if let Some(name) = map
+            .keys()
+            .find(|key| !params.iter().any(|(name, _)| name.eq(*key)))
+        {
+            return Err(BindError::UnknownName(name.clone()));
+        }
+        for (name, param) in params.iter_mut() {
+            **param = map[name].clone();
+        }
+        Ok(())
+    }
+
+    /// returns a copy of the statement with every constant value, including collection
+    /// literals and `USING` values, replaced by a `?` bind marker, e.g. for fingerprinting
+    /// queries.  Bind markers are left unchanged.  `LIMIT`, `PER PARTITION LIMIT` and
+    /// `INSERT ... JSON` are not modelled as operands, so their values are kept.
+    pub fn to_prepared_statement_template(&self) -> CassandraStatement {
+        let mut result = self.clone();
+        for value in result.values_mut() {
+            if let Operand::Const(_) | Operand::Map(_) | Operand::Set(_) | Operand::List(_) = value
+            {
+                *value = Operand::Param("?".to_string());
+            }
+        }
+        result
+    }
+
+    /// returns the bind markers in the order they appear in the statement.
+    fn params_mut(&mut self) -> Vec<&mut Operand> {
+        self.values_mut()
+            .into_iter()
+            .filter(|value| matches!(value, Operand::Param(_)))
+            .collect()
+    }
+
+    /// returns the bind markers, constants and collection literals that are values in the
+    /// statement, in the order they appear in the statement.
+    fn values_mut(&mut self) -> Vec<&mut Operand> {
+        let mut result = vec![];
+        match self {
+            CassandraStatement::Batch(batch) => {
+                CassandraStatement::using_values_mut(&mut batch.begin_batch.using_ttl, &mut result);
+                for statement in &mut batch.statements {
+                    result.extend(statement.values_mut());
+                }
+            }
+            CassandraStatement::Delete(delete) => {
+                CassandraStatement::begin_batch_values_mut(&mut delete.begin_batch, &mut result);
+                for column in &mut delete.columns {
+                    if let Some(idx) = &mut column.idx {
+                        CassandraStatement::operand_values_mut(idx, &mut result);
+                    }
+                }
+                if let Some(timestamp) = &mut delete.timestamp {
+                    CassandraStatement::operand_values_mut(timestamp, &mut result);
+                }
+                CassandraStatement::relation_values_mut(&mut delete.where_clause, &mut result);
+                CassandraStatement::relation_values_mut(&mut delete.if_clause, &mut result);
+            }
+            CassandraStatement::Insert(insert) => {
+                CassandraStatement::begin_batch_values_mut(&mut insert.begin_batch, &mut result);
+                if let InsertValues::Values(values) = &mut insert.values {
+                    for value in values {
+                        CassandraStatement::operand_values_mut(value, &mut result);
+                    }
+                }
+                CassandraStatement::using_values_mut(&mut insert.using_ttl, &mut result);
+            }
+            CassandraStatement::Select(select) => {
+                CassandraStatement::relation_values_mut(&mut select.where_clause, &mut result);
+            }
+            CassandraStatement::Update(update) => {
+                CassandraStatement::begin_batch_values_mut(&mut update.begin_batch, &mut result);
+                CassandraStatement::using_values_mut(&mut update.using_ttl, &mut result);
+                for assignment in &mut update.assignments {
+                    if let Some(idx) = &mut assignment.name.idx {
+                        CassandraStatement::operand_values_mut(idx, &mut result);
+                    }
+                    CassandraStatement::operand_values_mut(&mut assignment.value, &mut result);
+                    match &mut assignment.operator {
+                        Some(AssignmentOperator::Plus(value))
+                        | Some(AssignmentOperator::Minus(value)) => {
+                            CassandraStatement::operand_values_mut(value, &mut result)
+                        }
+                        None => {}
+                    }
+                }
+                CassandraStatement::relation_values_mut(&mut update.where_clause, &mut result);
+                CassandraStatement::relation_values_mut(&mut update.if_clause, &mut result);
+            }
+            _ => {}
+        }
+        result
+    }
+
+    fn begin_batch_values_mut<'a>(
+        begin_batch: &'a mut Option<BeginBatch>,
+        result: &mut Vec<&'a mut Operand>,
+    ) {
+        if let Some(begin_batch) = begin_batch {
+            CassandraStatement::using_values_mut(&mut begin_batch.using_ttl, result);
+        }
+    }
+
+    fn using_values_mut<'a>(
+        using: &'a mut Option<TtlTimestamp>,
+        result: &mut Vec<&'a mut Operand>,
+    ) {
+        if let Some(using) = using {
+            // the values are in the order they are displayed
+            for value in [&mut using.ttl, &mut using.timestamp].into_iter().flatten() {
+                CassandraStatement::operand_values_mut(value, result);
+            }
+        }
+    }
+
+    fn relation_values_mut<'a>(
+        relations: &'a mut [RelationElement],
+        result: &mut Vec<&'a mut Operand>,
+    ) {
+        for relation in relations {
+            CassandraStatement::operand_values_mut(&mut relation.obj, result);
+            CassandraStatement::operand_values_mut(&mut relation.value, result);
+        }
+    }
+
+    fn operand_values_mut<'a>(operand: &'a mut Operand, result: &mut Vec<&'a mut Operand>) {
+        match operand {
+            Operand::Param(_)
+            | Operand::Const(_)
+            | Operand::Map(_)
+            | Operand::Set(_)
+            | Operand::List(_) => result.push(operand),
+            Operand::Tuple(operands) | Operand::Collection(operands) => {
+                for operand in operands {
+                    CassandraStatement::operand_values_mut(operand, result);
+                }
+            }
+            Operand::IndexedColumn(column) => {
+                if let Some(idx) = &mut column.idx {
+                    CassandraStatement::operand_values_mut(idx, result);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// returns the number of bind markers (`?` and `:name`) in the statement.  The markers
+    /// counted are those replaced by `bind()` and `bind_named()`, but unlike them no
+    /// allocation is made.
+    pub fn parameters_count(&self) -> usize {
+        match self {
+            CassandraStatement::Batch(batch) => {
+                CassandraStatement::using_params_count(&batch.begin_batch.using_ttl)
+                    + batch
+                        .iter()
+                        .map(|statement| statement.parameters_count())
+                        .sum::<usize>()
+            }
+            CassandraStatement::Delete(delete) => {
+                CassandraStatement::begin_batch_params_count(&delete.begin_batch)
+                    + delete
+                        .columns
+                        .iter()
+                        .filter_map(|column| column.idx.as_ref())
+                        .map(CassandraStatement::operand_params_count)
+                        .sum::<usize>()
+                    + delete
+                        .timestamp
+                        .as_ref()
+                        .map_or(0, CassandraStatement::operand_params_count)
+                    + CassandraStatement::relation_params_count(&delete.where_clause)
+                    + CassandraStatement::relation_params_count(&delete.if_clause)
+            }
+            CassandraStatement::Insert(insert) => {
+                CassandraStatement::begin_batch_params_count(&insert.begin_batch)
+                    + (match &insert.values {
+                        InsertValues::Values(values) => values
+                            .iter()
+                            .map(CassandraStatement::operand_params_count)
+                            .sum(),
+                        InsertValues::Json(_) => 0,
+                    })
+                    + CassandraStatement::using_params_count(&insert.using_ttl)
+            }
+            CassandraStatement::Select(select) => {
+                CassandraStatement::relation_params_count(&select.where_clause)
+            }
+            CassandraStatement::Update(update) => {
+                CassandraStatement::begin_batch_params_count(&update.begin_batch)
+                    + update
+                        .assignments
+                        .iter()
+                        .map(|assignment| {
+                            assignment
+                                .name
+                                .idx
+                                .as_ref()
+                                .map_or(0, CassandraStatement::operand_params_count)
+                                + CassandraStatement::operand_params_count(&assignment.value)
+                                + match &assignment.operator {
+                                    Some(AssignmentOperator::Plus(value))
+                                    | Some(AssignmentOperator::Minus(value)) => {
+                                        CassandraStatement::operand_params_count(value)
+                                    }
+                                    None => 0,
+                                }
+                        })
+                        .sum::<usize>()
+                    + CassandraStatement::using_params_count(&update.using_ttl)
+                    + CassandraStatement::relation_params_count(&update.where_clause)
+                    + CassandraStatement::relation_params_count(&update.if_clause)
+            }
+            _ => 0,
+        }
+    }
+
+    fn begin_batch_params_count(begin_batch: &Option<BeginBatch>) -> usize {
+        begin_batch.as_ref().map_or(0, |begin_batch| {
+            CassandraStatement::using_params_count(&begin_batch.using_ttl)
+        })
+    }
+
+    fn using_params_count(using: &Option<TtlTimestamp>) -> usize {
+        using.as_ref().map_or(0, |using| {
+            [&using.ttl, &using.timestamp]
+                .into_iter()
+                .flatten()
+                .map(CassandraStatement::operand_params_count)
+                .sum()
+        })
+    }
+
+    fn relation_params_count(relations: &[RelationElement]) -> usize {
+        relations
+            .iter()
+            .map(|relation| {
+                CassandraStatement::operand_params_count(&relation.obj)
+                    + CassandraStatement::operand_params_count(&relation.value)
+            })
+            .sum()
+    }
+
+    fn operand_params_count(operand: &Operand) -> usize {
+        match operand {
+            Operand::Param(_) => 1,
+            Operand::Tuple(operands) | Operand::Collection(operands) => operands
+                .iter()
+                .map(CassandraStatement::operand_params_count)
+                .sum(),
+            Operand::IndexedColumn(column) => column
+                .idx
+                .as_ref()
+                .map_or(0, CassandraStatement::operand_params_count),
+            _ => 0,
+        }
+    }
+}
+
+/// the structural metrics for a statement, see `CassandraStatement::complexity()`.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct Complexity {
+    /// the number of relations in the `WHERE` clause.
+    pub where_relations: usize,
+    /// the number of selected elements, `*` counts as one.
+    pub projected_columns: usize,
+    /// the number of value combinations selected by the `IN` relations, that is the product
+    /// of the `IN` list sizes.  Zero if there are no `IN` relations.
+    pub in_list_cardinality: usize,
+    /// true if `ALLOW FILTERING` is specified.
+    pub allow_filtering: bool,
+}
+
+/// the errors returned when binding values to a statement.
+#[derive(PartialEq, Debug, Clone)]
+pub enum BindError {
+    /// the number of values does not match the number of positional bind markers.
+    ArityMismatch { expected: usize, actual: usize },
+    /// a named bind marker has no value.
+    MissingName(String),
+    /// a value was provided for a name that is not a bind marker in the statement.
+    UnknownName(String),
+}
+
+impl Display for BindError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BindError::ArityMismatch { expected, actual } => write!(
+                f,
+                "expected {} positional values but {} were provided",
+                expected, actual
+            ),
+            BindError::MissingName(name) => write!(f, "no value provided for ':{}'", name),
+            BindError::UnknownName(name) => write!(f, "no bind marker named ':{}'", name),
+        }
+    }
+}
+
+impl std::error::Error for BindError {}
+
+/// parses a single statement, see `CassandraParser::parse()`.
+///
+/// ```
+/// use cql3_parser::cassandra_statement::CassandraStatement;
+///
+/// let statement: CassandraStatement = "SELECT a, b FROM ks.t WHERE a = 1".parse().unwrap();
+/// assert_eq!("SELECT a, b FROM ks.t WHERE a = 1", statement.to_string());
+/// let reparsed: CassandraStatement = statement.to_string().parse().unwrap();
+/// assert_eq!(statement, reparsed);
+/// ```
+impl FromStr for CassandraStatement {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        CassandraParser::parse(s)
+    }
 }
 
 impl Display for CassandraStatement {
@@ -320,6 +1187,7 @@ impl Display for CassandraStatement {
             CassandraStatement::AlterType(alter_type_data) => write!(f, "{}", alter_type_data),
             CassandraStatement::AlterUser(user_data) => write!(f, "ALTER {}", user_data),
             CassandraStatement::ApplyBatch => write!(f, "APPLY BATCH"),
+            CassandraStatement::Batch(batch) => write!(f, "{}", batch),
             CassandraStatement::CreateAggregate(aggregate_data) => write!(f, "{}", aggregate_data),
             CassandraStatement::CreateFunction(function_data) => write!(f, "{}", function_data),
             CassandraStatement::CreateIndex(index_data) => write!(f, "{}", index_data),
@@ -333,6 +1201,7 @@ impl Display for CassandraStatement {
             CassandraStatement::CreateType(type_data) => write!(f, "{}", type_data),
             CassandraStatement::CreateUser(user_data) => write!(f, "CREATE {}", user_data),
             CassandraStatement::Delete(statement_data) => write!(f, "{}", statement_data),
+            CassandraStatement::Describe(describe) => write!(f, "{}", describe),
             CassandraStatement::DropAggregate(drop_data) => {
                 write!(f, "{}", drop_data.get_text("AGGREGATE"))
             }
@@ -397,7 +1266,19 @@ impl Display for CassandraStatement {
 
 #[cfg(test)]
 mod tests {
-    use crate::cassandra_ast::CassandraAST;
+    use crate::cassandra_ast::{CassandraAST, CassandraParser, ParseError};
+    use crate::cassandra_statement::{BindError, CassandraStatement, Complexity};
+    use crate::common::{
+        FQName, Identifier, Operand, OrderClause, RelationElement, RelationOperator, WithItem,
+    };
+    use crate::common_drop::DropKind;
+    use crate::delete::{Delete, IndexedColumn};
+    use crate::insert::InsertValues;
+    use crate::role_common::RoleCommon;
+    use crate::select::Select;
+    use crate::test_helpers::parse_one;
+    use crate::update::Update;
+    use std::collections::BTreeMap;
 
     // only tests single results
     fn test_parsing(expected: &[&str], statements: &[&str]) {
@@ -453,9 +1334,15 @@ mod tests {
             "SELECT column FROM table ORDER BY col1",
             "SELECT column FROM table ORDER BY col1 ASC",
             "SELECT column FROM table ORDER BY col1 DESC",
+            "SELECT column FROM table ORDER BY col1, col2 DESC",
             "SELECT column FROM table LIMIT 5",
             "SELECT column FROM table ALLOW FILTERING",
             "SELECT column from table where col=?",
+            "SELECT count(*) FROM table",
+            "SELECT COUNT( 1 ) AS total FROM table",
+            "SELECT count(col) FROM table",
+            "SELECT * FROM t WHERE x = 1 LIMIT 5 ALLOW FILTERING",
+            "SELECT * FROM t WHERE x = 1 ORDER BY y LIMIT 5 allow filtering;",
         ];
         let expected = [
             "SELECT DISTINCT JSON * FROM table",
@@ -493,13 +1380,93 @@ mod tests {
             "SELECT column FROM table ORDER BY col1 ASC",
             "SELECT column FROM table ORDER BY col1 ASC",
             "SELECT column FROM table ORDER BY col1 DESC",
+            "SELECT column FROM table ORDER BY col1 ASC, col2 DESC",
             "SELECT column FROM table LIMIT 5",
             "SELECT column FROM table ALLOW FILTERING",
             "SELECT column FROM table WHERE col = ?",
+            "SELECT count(*) FROM table",
+            "SELECT COUNT(1) AS total FROM table",
+            "SELECT count(col) FROM table",
+            "SELECT * FROM t WHERE x = 1 LIMIT 5 ALLOW FILTERING",
+            "SELECT * FROM t WHERE x = 1 ORDER BY y ASC LIMIT 5 ALLOW FILTERING",
         ];
         test_parsing(&expected, &stmts);
     }
 
+    #[test]
+    fn test_from_str() {
+        let statement: CassandraStatement = "SELECT * FROM t WHERE a = 1;".parse().unwrap();
+        assert_eq!(
+            CassandraAST::new("SELECT * FROM t WHERE a = 1").statements[0].statement,
+            statement
+        );
+        assert!("SELECT * FROM".parse::<CassandraStatement>().is_err());
+        assert_eq!(
+            Err(ParseError::Syntax {
+                message: "expected a single statement but found 2".to_string(),
+                offset: 17,
+            }),
+            "SELECT * FROM t; SELECT * FROM u".parse::<CassandraStatement>()
+        );
+        assert!("".parse::<CassandraStatement>().is_err());
+    }
+
+    #[test]
+    fn test_select_count_star() {
+        let select = parse_one::<Select>;
+        let is_count_star = |qry: &str| select(qry).is_count_star();
+        assert!(is_count_star("SELECT count(*) FROM table"));
+        assert!(is_count_star("SELECT COUNT(1) FROM table"));
+        assert!(is_count_star(
+            "SELECT COUNT(*) AS n FROM table WHERE pk = 1"
+        ));
+        assert!(!is_count_star("SELECT count(col) FROM table"));
+        assert!(!is_count_star("SELECT count(*), col FROM table"));
+        assert!(!is_count_star("SELECT func(*) FROM table"));
+        assert!(!is_count_star("SELECT * FROM table"));
+
+        let is_star = |qry: &str| select(qry).is_star();
+        assert!(is_star("SELECT * FROM table"));
+        assert!(is_star("SELECT JSON * FROM table WHERE pk = 1"));
+        assert!(!is_star("SELECT count(*) FROM table"));
+        assert!(!is_star("SELECT col FROM table"));
+    }
+
+    #[test]
+    fn test_select_distinct() {
+        let select = parse_one::<Select>;
+        let stmt = select("SELECT DISTINCT a FROM t");
+        assert!(stmt.distinct);
+        assert_eq!("SELECT DISTINCT a FROM t", stmt.to_string());
+        let stmt = select("SELECT DISTINCT JSON a FROM t");
+        assert!(stmt.distinct);
+        assert!(stmt.json);
+        assert_eq!("SELECT DISTINCT JSON a FROM t", stmt.to_string());
+        let stmt = select("SELECT a FROM t");
+        assert!(!stmt.distinct);
+        assert_eq!("SELECT a FROM t", stmt.to_string());
+    }
+
+    #[cfg(feature = "dse")]
+    #[test]
+    fn test_select_group_by() {
+        let qry = "SELECT col, count(*) FROM t WHERE pk = 1 GROUP BY col, col2";
+        let select = parse_one::<Select>(qry);
+        assert_eq!(vec!["col", "col2"], select.group_by);
+        assert_eq!(qry, select.to_string());
+    }
+
+    #[test]
+    fn test_select_allow_filtering() {
+        let allow_filtering = |qry: &str| parse_one::<Select>(qry).allow_filtering;
+        assert!(allow_filtering(
+            "SELECT * FROM t WHERE x = 1 LIMIT 5 ALLOW FILTERING"
+        ));
+        assert!(!allow_filtering("SELECT * FROM t WHERE x = 1 LIMIT 5"));
+        assert!(allow_filtering("SELECT * FROM t ALLOW FILTERING"));
+        assert!(!allow_filtering("SELECT * FROM t"));
+    }
+
     #[test]
     fn test_insert_statements() {
         let stmts = [
@@ -538,6 +1505,7 @@ mod tests {
             "DELETE column, column3 from keyspace.table WHERE column2='foo' IF column4 = 'bar'",
             "DELETE column, column3 from keyspace.table WHERE column2=?",
             "DELETE column, column3 from keyspace.table WHERE column2='foo' IF column4 = ?",
+            "DELETE m[?], l[0] from t WHERE pk = 1",
         ];
         let expected  = [
             "BEGIN LOGGED BATCH USING TIMESTAMP 5 DELETE column['hello'] FROM table WHERE column2 = 'foo' IF EXISTS",
@@ -548,10 +1516,43 @@ mod tests {
             "DELETE column, column3 FROM keyspace.table WHERE column2 = 'foo' IF column4 = 'bar'",
             "DELETE column, column3 FROM keyspace.table WHERE column2 = ?",
             "DELETE column, column3 FROM keyspace.table WHERE column2 = 'foo' IF column4 = ?",
+            "DELETE m[?], l[0] FROM t WHERE pk = 1",
         ];
         test_parsing(&expected, &stmts);
     }
 
+    #[test]
+    fn test_delete_conditions() {
+        let delete = parse_one::<Delete>;
+        let stmt = delete("DELETE FROM t WHERE pk=1 IF col='old'");
+        assert!(stmt.is_lwt());
+        assert!(!stmt.if_exists);
+        assert_eq!(
+            &[RelationElement {
+                obj: Operand::Column(Identifier::new("col")),
+                oper: RelationOperator::Equal,
+                value: Operand::from("old"),
+            }],
+            stmt.conditions()
+        );
+        assert_eq!(
+            "DELETE FROM t WHERE pk = 1 IF col = 'old'",
+            stmt.to_string()
+        );
+
+        let stmt = delete("DELETE FROM t WHERE pk=1 IF EXISTS");
+        assert!(stmt.is_lwt());
+        assert!(stmt.if_exists);
+        assert!(stmt.conditions().is_empty());
+        assert_eq!("DELETE FROM t WHERE pk = 1 IF EXISTS", stmt.to_string());
+
+        let stmt = delete("DELETE FROM t WHERE pk=1");
+        assert!(!stmt.is_lwt());
+
+        // `IF EXISTS` and `IF condition` may not be combined.
+        assert!(CassandraAST::new("DELETE FROM t WHERE pk=1 IF EXISTS AND col='old'").has_error());
+    }
+
     #[test]
     fn x() {
         let qry = "DELETE column, column3 FROM keyspace.table WHERE column2 = 'foo' IF column4 = ?";
@@ -561,6 +1562,313 @@ mod tests {
         assert_eq!(qry, stmt_str);
     }
 
+    #[test]
+    fn test_is_lwt() {
+        let is_lwt = |qry: &str| CassandraAST::new(qry).statements[0].statement.is_lwt();
+        assert!(is_lwt("INSERT INTO t (pk) VALUES (1) IF NOT EXISTS"));
+        assert!(!is_lwt("INSERT INTO t (pk) VALUES (1)"));
+        assert!(is_lwt("UPDATE t SET col = 1 WHERE pk = 1 IF EXISTS"));
+        assert!(is_lwt("UPDATE t SET col = 1 WHERE pk = 1 IF col = 2"));
+        assert!(!is_lwt("UPDATE t SET col = 1 WHERE pk = 1"));
+        assert!(is_lwt("DELETE FROM t WHERE pk = 1 IF EXISTS"));
+        assert!(is_lwt("DELETE FROM t WHERE pk = 1 IF col = 2"));
+        assert!(!is_lwt("DELETE FROM t WHERE pk = 1"));
+        assert!(!is_lwt("CREATE TABLE IF NOT EXISTS t (pk int PRIMARY KEY)"));
+        assert!(!is_lwt("SELECT * FROM t"));
+    }
+
+    #[test]
+    fn test_is_idempotent() {
+        let is_idempotent = |qry: &str| {
+            CassandraAST::new(qry).statements[0]
+                .statement
+                .is_idempotent()
+        };
+        assert!(is_idempotent("SELECT * FROM t WHERE pk = 1"));
+        assert!(is_idempotent(
+            "UPDATE t SET col = 1, m['k'] = 'v' WHERE pk = 1"
+        ));
+        assert!(!is_idempotent("UPDATE t SET c = c + 1 WHERE pk = 1"));
+        assert!(!is_idempotent("UPDATE t SET l = l + [1] WHERE pk = 1"));
+        assert!(!is_idempotent("UPDATE t SET l = [1] + l WHERE pk = 1"));
+        assert!(is_idempotent("UPDATE t SET s = s + {1} WHERE pk = 1"));
+        assert!(is_idempotent("UPDATE t SET m = m + {'k': 1} WHERE pk = 1"));
+        assert!(is_idempotent("UPDATE t SET l = l - [1] WHERE pk = 1"));
+        assert!(!is_idempotent("UPDATE t SET col = now() WHERE pk = 1"));
+        assert!(!is_idempotent(
+            "UPDATE t SET col = 1 WHERE pk = 1 IF col = 2"
+        ));
+        assert!(is_idempotent("INSERT INTO t (pk, col) VALUES (1, ?)"));
+        assert!(!is_idempotent("INSERT INTO t (pk, id) VALUES (1, uuid())"));
+        assert!(!is_idempotent(
+            "INSERT INTO t (pk) VALUES (1) IF NOT EXISTS"
+        ));
+        assert!(is_idempotent("DELETE FROM t WHERE pk = 1"));
+        assert!(!is_idempotent("DELETE FROM t WHERE pk = 1 IF EXISTS"));
+        assert!(!is_idempotent("TRUNCATE t"));
+    }
+
+    #[test]
+    fn test_referenced_keyspaces() {
+        let keyspaces = |qry: &str| {
+            let ast = CassandraAST::new(qry);
+            let mut result: Vec<String> = ast.statements[0]
+                .statement
+                .referenced_keyspaces()
+                .into_iter()
+                .collect();
+            result.sort();
+            result
+        };
+        assert!(keyspaces("SELECT * FROM t WHERE col = 5").is_empty());
+        assert_eq!(vec!["ks"], keyspaces("SELECT * FROM ks.t WHERE col = 5"));
+        assert_eq!(
+            vec!["ks", "ks2"],
+            keyspaces("CREATE MATERIALIZED VIEW ks.view AS SELECT col1 FROM ks2.tbl WHERE col1 IS NOT NULL PRIMARY KEY (col1)")
+        );
+        assert_eq!(vec!["ks"], keyspaces("USE ks"));
+        assert_eq!(vec!["ks"], keyspaces("DROP KEYSPACE ks"));
+        assert_eq!(vec!["ks"], keyspaces("GRANT SELECT ON TABLE ks.t TO role"));
+        assert!(keyspaces("LIST ROLES").is_empty());
+        assert_eq!(vec!["ks1", "ks2"], keyspaces("SELECT ks2.f(a) FROM ks1.t"));
+        assert_eq!(
+            vec!["ks3"],
+            keyspaces("UPDATE t SET v = ks3.f(1) WHERE pk = 1")
+        );
+        assert!(keyspaces("SELECT * FROM t WHERE TOKEN(pk) > 5").is_empty());
+    }
+
+    #[test]
+    fn test_bind() {
+        let ast = CassandraAST::new(
+            "UPDATE keyspace.table SET col1 = ?, col2 = col2 + ? WHERE col3 = ? IF col4 = ?",
+        );
+        let mut stmt = ast.statements[0].statement.clone();
+        assert_eq!(
+            Err(BindError::ArityMismatch {
+                expected: 4,
+                actual: 1
+            }),
+            stmt.bind(&[Operand::from("foo")])
+        );
+        stmt.bind(&[
+            Operand::from("foo"),
+            Operand::from(&5),
+            Operand::from(&true),
+            Operand::Null,
+        ])
+        .unwrap();
+        assert_eq!(
+            "UPDATE keyspace.table SET col1 = 'foo', col2 = col2 + 5 WHERE col3 = true IF col4 = NULL",
+            stmt.to_string()
+        );
+    }
+
+    #[test]
+    fn test_prepared_statement_template() {
+        let stmts = [
+            (
+                "SELECT * FROM t WHERE id = 42",
+                "SELECT * FROM t WHERE id = ?",
+            ),
+            (
+                "SELECT a FROM t WHERE id IN (1, 2) AND c > 'x' AND d = :d LIMIT 10",
+                "SELECT a FROM t WHERE id IN (?, ?) AND c > ? AND d = :d LIMIT 10",
+            ),
+            (
+                "INSERT INTO t (a, b, c) VALUES (1, ?, 'x') USING TTL 5 AND TIMESTAMP 1234",
+                "INSERT INTO t (a, b, c) VALUES (?, ?, ?) USING TTL ? AND TIMESTAMP ?",
+            ),
+            (
+                "UPDATE t SET a = 1, c = c + 2, m['k'] = 'v' WHERE pk = 3 IF a = 4",
+                "UPDATE t SET a = ?, c = c + ?, m[?] = ? WHERE pk = ? IF a = ?",
+            ),
+            (
+                "UPDATE t SET m = {'k':'v'}, l = ['a'] WHERE pk = 1",
+                "UPDATE t SET m = ?, l = ? WHERE pk = ?",
+            ),
+            (
+                "DELETE l[1] FROM t WHERE pk = 'a'",
+                "DELETE l[?] FROM t WHERE pk = ?",
+            ),
+            ("DROP TABLE ks.t", "DROP TABLE ks.t"),
+        ];
+        for (qry, expected) in stmts {
+            let ast = CassandraAST::new(qry);
+            assert!(!ast.has_error(), "{}", qry);
+            let template = ast.statements[0].statement.to_prepared_statement_template();
+            assert_eq!(expected, template.to_string());
+            let reparsed = CassandraAST::new(&template.to_string());
+            assert!(!reparsed.has_error(), "{}", expected);
+            assert_eq!(template, reparsed.statements[0].statement);
+        }
+    }
+
+    #[test]
+    fn test_multi_column_clustering_order() {
+        let stmts = [
+            "CREATE TABLE ks.t (pk INT, c1 INT, c2 TEXT, PRIMARY KEY (pk, c1, c2)) WITH CLUSTERING ORDER BY (c1 DESC, c2 ASC)",
+            "CREATE TABLE ks.t (pk INT, c1 INT, c2 TEXT, c3 INT, PRIMARY KEY (pk, c1, c2, c3)) WITH CLUSTERING ORDER BY (c1 ASC, c2 DESC, c3 ASC) AND comment = 'x'",
+            "CREATE MATERIALIZED VIEW ks.v AS SELECT pk, c1, c2 FROM ks.t WHERE c1 IS NOT NULL AND c2 IS NOT NULL PRIMARY KEY (pk, c1, c2) WITH CLUSTERING ORDER BY (c1 DESC, c2 ASC)",
+            "CREATE MATERIALIZED VIEW ks.v AS SELECT pk, c1, c2, c3 FROM ks.t WHERE c1 IS NOT NULL AND c2 IS NOT NULL AND c3 IS NOT NULL PRIMARY KEY (pk, c1, c2, c3) WITH CLUSTERING ORDER BY (c1 DESC, c2 ASC, c3 DESC)",
+        ];
+        for stmt in stmts {
+            let ast = CassandraAST::new(stmt);
+            assert!(!ast.has_error(), "{}", stmt);
+            assert_eq!(stmt, ast.statements[0].statement.to_string());
+        }
+        match &CassandraAST::new(stmts[3]).statements[0].statement {
+            CassandraStatement::CreateMaterializedView(view) => assert_eq!(
+                &[WithItem::ClusterOrder(vec![
+                    OrderClause::desc("c1"),
+                    OrderClause::from_column("c2"),
+                    OrderClause::desc("c3"),
+                ])],
+                view.with_clause.as_slice()
+            ),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_drop_kinds() {
+        let cases = [
+            ("DROP AGGREGATE IF EXISTS ks.agg", DropKind::Aggregate),
+            ("DROP FUNCTION IF EXISTS ks.func", DropKind::Function),
+            ("DROP INDEX IF EXISTS ks.idx", DropKind::Index),
+            ("DROP KEYSPACE IF EXISTS ks", DropKind::Keyspace),
+            (
+                "DROP MATERIALIZED VIEW IF EXISTS ks.view",
+                DropKind::MaterializedView,
+            ),
+            ("DROP ROLE IF EXISTS role", DropKind::Role),
+            ("DROP TABLE IF EXISTS ks.tbl", DropKind::Table),
+            ("DROP TYPE IF EXISTS ks.typ", DropKind::Type),
+            ("DROP USER IF EXISTS usr", DropKind::User),
+        ];
+        for (qry, kind) in cases {
+            let ast = CassandraAST::new(qry);
+            assert!(!ast.has_error(), "{}", qry);
+            let stmt = &ast.statements[0].statement;
+            assert_eq!(qry, stmt.to_string());
+            let (drop_kind, drop) = stmt.as_drop().unwrap();
+            assert_eq!(kind, drop_kind);
+            assert!(drop.if_exists);
+            assert_eq!(qry, format!("DROP {} IF EXISTS {}", kind, drop.name));
+
+            let mut without = drop.clone();
+            without.if_exists = false;
+            let without = CassandraStatement::from_drop(kind, without);
+            assert_eq!(qry.replace(" IF EXISTS", ""), without.to_string());
+            assert_eq!(
+                without,
+                CassandraAST::new(&without.to_string()).statements[0].statement
+            );
+        }
+        assert!(
+            CassandraAST::new("DROP TRIGGER trig ON ks.tbl").statements[0]
+                .statement
+                .as_drop()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_float_literals() {
+        let stmts = [
+            "SELECT * FROM t WHERE x = 1.5e10",
+            "SELECT * FROM t WHERE x = -2E-3",
+            "INSERT INTO t (a, b, c) VALUES (1.5e10, -2E-3, 3.25)",
+            "INSERT INTO t (a, b) VALUES (NaN, Infinity)",
+            "UPDATE t SET a = Infinity WHERE pk = 1",
+        ];
+        for stmt in stmts {
+            let ast = CassandraAST::new(stmt);
+            assert!(!ast.has_error(), "{}", stmt);
+            assert_eq!(stmt, ast.statements[0].statement.to_string());
+        }
+
+        let values = |qry: &str| match &CassandraAST::new(qry).statements[0].statement {
+            CassandraStatement::Insert(insert) => match &insert.values {
+                InsertValues::Values(values) => values.clone(),
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        };
+        assert_eq!(
+            vec![
+                Operand::Const("1.5e10".to_string()),
+                Operand::Const("-2E-3".to_string()),
+            ],
+            values("INSERT INTO t (a, b) VALUES (1.5e10, -2E-3)")
+        );
+        let specials = values("INSERT INTO t (a, b) VALUES (NaN, Infinity)");
+        assert_eq!(
+            vec![
+                Operand::Const("NaN".to_string()),
+                Operand::Const("Infinity".to_string()),
+            ],
+            specials
+        );
+        assert_eq!(Ok(1.5e10), Operand::Const("1.5e10".to_string()).as_f64());
+        assert!(specials[0].as_f64().unwrap().is_nan());
+    }
+
+    #[test]
+    fn test_bind_timestamp() {
+        let mut stmt = CassandraAST::new("DELETE l[?] FROM t USING TIMESTAMP ? WHERE id = ?")
+            .statements[0]
+            .statement
+            .clone();
+        stmt.bind(&[Operand::from(&1), Operand::from(&2), Operand::from(&3)])
+            .unwrap();
+        assert_eq!(
+            "DELETE l[1] FROM t USING TIMESTAMP 2 WHERE id = 3",
+            stmt.to_string()
+        );
+
+        let mut stmt = CassandraAST::new("UPDATE t USING TIMESTAMP ? SET a = ? WHERE pk = ?")
+            .statements[0]
+            .statement
+            .clone();
+        stmt.bind(&[Operand::from(&1), Operand::from(&2), Operand::from(&3)])
+            .unwrap();
+        assert_eq!(
+            "UPDATE t USING TIMESTAMP 1 SET a = 2 WHERE pk = 3",
+            stmt.to_string()
+        );
+
+        let qry = "INSERT INTO t (a) VALUES (?) USING TTL 5 AND TIMESTAMP -10";
+        let ast = CassandraAST::new(qry);
+        assert!(!ast.has_error());
+        assert_eq!(qry, ast.statements[0].statement.to_string());
+    }
+
+    #[test]
+    fn test_bind_named() {
+        let ast =
+            CassandraAST::new("SELECT col FROM table WHERE col1 = :one AND col2 IN (:two, 5)");
+        let mut stmt = ast.statements[0].statement.clone();
+        let mut map = BTreeMap::new();
+        map.insert("one".to_string(), Operand::from("foo"));
+        assert_eq!(
+            Err(BindError::MissingName("two".to_string())),
+            stmt.bind_named(&map)
+        );
+        map.insert("two".to_string(), Operand::from(&4));
+        map.insert("three".to_string(), Operand::from(&3));
+        assert_eq!(
+            Err(BindError::UnknownName("three".to_string())),
+            stmt.bind_named(&map)
+        );
+        map.remove("three");
+        stmt.bind_named(&map).unwrap();
+        assert_eq!(
+            "SELECT col FROM table WHERE col1 = 'foo' AND col2 IN (4, 5)",
+            stmt.to_string()
+        );
+    }
+
     #[test]
     fn test_has_error() {
         let ast = CassandraAST::new("SELECT foo from bar.baz where fu='something'");
@@ -739,6 +2047,10 @@ mod tests {
             "UPDATE keyspace.table SET col1[5] = 'hello' WHERE col2=5 IF col3=7;",
             "UPDATE keyspace.table USING TIMESTAMP 3 SET col1 = 'foo' WHERE col2=5;",
             "UPDATE foo SET c = 'yo', v = 123 WHERE z = 1",
+            "UPDATE t SET col=1 WHERE pk=1 IF col IS NOT NULL",
+            "UPDATE t SET col=1 WHERE pk=1 IF col=0 AND col2 IS NOT NULL",
+            "UPDATE t SET v=1 WHERE id=1 IF v = 3 AND owner IN ('a','b')",
+            "UPDATE t SET v=1 WHERE id=1 IF m['k'] = 3 AND addr.street = 'x'",
     ];
         let expected = [
             "BEGIN LOGGED BATCH USING TIMESTAMP 5 UPDATE keyspace.table SET col1 = 'foo' WHERE col2 = 5",
@@ -758,10 +2070,249 @@ mod tests {
             "UPDATE keyspace.table SET col1[5] = 'hello' WHERE col2 = 5 IF col3 = 7",
             "UPDATE keyspace.table USING TIMESTAMP 3 SET col1 = 'foo' WHERE col2 = 5",
             "UPDATE foo SET c = 'yo', v = 123 WHERE z = 1",
+            "UPDATE t SET col = 1 WHERE pk = 1 IF col IS NOT NULL",
+            "UPDATE t SET col = 1 WHERE pk = 1 IF col = 0 AND col2 IS NOT NULL",
+            "UPDATE t SET v = 1 WHERE id = 1 IF v = 3 AND owner IN ('a', 'b')",
+            "UPDATE t SET v = 1 WHERE id = 1 IF m['k'] = 3 AND addr.street = 'x'",
     ];
         test_parsing(&expected, &stmts);
     }
 
+    #[test]
+    fn test_round_trip_fixtures() {
+        // parse -> display -> parse must produce the same statement.
+        let fixtures = include_str!("../tests/fixtures/round_trip.cql");
+        for qry in fixtures
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with("--"))
+        {
+            let ast = CassandraAST::new(qry);
+            assert!(!ast.has_error(), "AST has error\n{}", qry);
+            let displayed = ast.statements[0].statement.to_string();
+            let reparsed = CassandraAST::new(&displayed);
+            assert!(
+                !reparsed.has_error(),
+                "displayed text has error\n{}\n{}",
+                qry,
+                displayed
+            );
+            assert_eq!(
+                ast.statements[0].statement, reparsed.statements[0].statement,
+                "\n{}\n{}",
+                qry, displayed
+            );
+            assert_eq!(displayed, reparsed.statements[0].statement.to_string());
+        }
+    }
+
+    #[test]
+    fn test_parameters_count() {
+        let stmts = [
+            ("SELECT * FROM t WHERE a = ? AND b IN (?, :b, 3)", 3),
+            ("INSERT INTO t (a, b, c) VALUES (?, :b, 5)", 2),
+            (
+                "UPDATE t SET l[?] = ?, c = c + ? WHERE pk = :pk IF m[?] = ?",
+                6,
+            ),
+            ("DELETE m[?] FROM t WHERE pk = ? IF v = :v", 3),
+            ("DELETE FROM t USING TIMESTAMP ? WHERE id = :id", 2),
+            ("INSERT INTO t (a) VALUES (?) USING TIMESTAMP :ts", 2),
+            (
+                "INSERT INTO t (a) VALUES (1) USING TTL ? AND TIMESTAMP :ts",
+                2,
+            ),
+            (
+                "UPDATE t USING TTL 5 AND TIMESTAMP ? SET a = ? WHERE pk = 1",
+                2,
+            ),
+            ("SELECT * FROM t WHERE a = 1", 0),
+            ("CREATE TABLE t (a INT PRIMARY KEY)", 0),
+        ];
+        for (qry, expected) in stmts {
+            let mut statement = CassandraAST::new(qry).statements[0].statement.clone();
+            assert_eq!(expected, statement.parameters_count(), "{}", qry);
+            assert_eq!(expected, statement.params_mut().len(), "{}", qry);
+        }
+    }
+
+    #[test]
+    fn test_multi_column_relations() {
+        let columns = Operand::Tuple(vec![
+            Operand::Column(Identifier::new("a")),
+            Operand::Column(Identifier::new("b")),
+        ]);
+        let tuple = |x: &str, y: &str| {
+            Operand::Tuple(vec![
+                Operand::Const(x.to_string()),
+                Operand::Const(y.to_string()),
+            ])
+        };
+        let relation = RelationElement {
+            obj: columns.clone(),
+            oper: RelationOperator::In,
+            value: Operand::Tuple(vec![tuple("1", "2"), tuple("3", "4")]),
+        };
+        assert_eq!("(a, b) IN ((1, 2), (3, 4))", relation.to_string());
+
+        let select = parse_one::<Select>;
+        let qry = "SELECT * FROM t WHERE pk = 1 AND (a, b) IN ((1, 2), (3, 4))";
+        let stmt = select(qry);
+        assert_eq!(relation, stmt.where_clause[1]);
+        assert_eq!(qry, stmt.to_string());
+
+        let qry = "SELECT * FROM t WHERE pk = 1 AND (a, b) >= (1, 2)";
+        let stmt = select(qry);
+        assert_eq!(
+            RelationElement {
+                obj: columns,
+                oper: RelationOperator::GreaterThanOrEqual,
+                value: tuple("1", "2"),
+            },
+            stmt.where_clause[1]
+        );
+        assert_eq!(qry, stmt.to_string());
+    }
+
+    #[test]
+    fn test_token_relations() {
+        let qry = "SELECT * FROM t WHERE TOKEN(id) >= ? AND TOKEN(id) < ?";
+        let stmt = parse_one::<Select>(qry);
+        assert_eq!(qry, stmt.to_string());
+        assert_eq!(
+            vec![
+                RelationElement {
+                    obj: Operand::Func("TOKEN(id)".to_string()),
+                    oper: RelationOperator::GreaterThanOrEqual,
+                    value: Operand::Param("?".to_string()),
+                },
+                RelationElement {
+                    obj: Operand::Func("TOKEN(id)".to_string()),
+                    oper: RelationOperator::LessThan,
+                    value: Operand::Param("?".to_string()),
+                },
+            ],
+            stmt.where_clause
+        );
+
+        let qry = "SELECT * FROM t WHERE TOKEN(a, b) > TOKEN(1, 2)";
+        let stmt = parse_one::<Select>(qry);
+        assert_eq!(qry, stmt.to_string());
+        assert_eq!(
+            RelationElement {
+                obj: Operand::Func("TOKEN(a, b)".to_string()),
+                oper: RelationOperator::GreaterThan,
+                value: Operand::Func("TOKEN(1, 2)".to_string()),
+            },
+            stmt.where_clause[0]
+        );
+    }
+
+    #[test]
+    fn test_describe() {
+        let stmts = [
+            "DESCRIBE TABLES",
+            "describe keyspace ks;",
+            "DESC TABLE ks.t",
+        ];
+        let expected = [
+            "DESCRIBE TABLES",
+            "DESCRIBE KEYSPACE ks",
+            "DESCRIBE TABLE ks.t",
+        ];
+        test_parsing(&expected, &stmts);
+        let ast = CassandraAST::new("DESCRIBE KEYSPACE ks");
+        assert!(ast.errors().is_empty());
+        assert_eq!("ks", ast.statements[0].statement.get_keyspace("default"));
+
+        // a `DESCRIBE` command may be one of several statements
+        let qry = "DESCRIBE TABLES; SELECT * FROM t";
+        let ast = CassandraAST::new(qry);
+        assert!(!ast.has_error());
+        assert_eq!(2, ast.statements.len());
+        assert_eq!("DESCRIBE TABLES", ast.statements[0].statement.to_string());
+        assert_eq!("DESCRIBE TABLES", ast.extract_text(&ast.statements[0]));
+        assert_eq!("SELECT * FROM t", ast.statements[1].statement.to_string());
+        assert_eq!("SELECT * FROM t", ast.extract_text(&ast.statements[1]));
+        assert_eq!(
+            vec!["SELECT * FROM t", "DESCRIBE KEYSPACE ks"],
+            CassandraParser::parse_many("SELECT * FROM t; DESC KEYSPACE ks;")
+                .unwrap()
+                .iter()
+                .map(CassandraStatement::to_string)
+                .collect::<Vec<String>>()
+        );
+    }
+
+    #[test]
+    fn test_update_conditions() {
+        let update = parse_one::<Update>;
+        let stmt = update("UPDATE t SET col=1 WHERE pk=1 IF col=0");
+        assert!(stmt.is_lwt());
+        assert_eq!(
+            &[RelationElement {
+                obj: Operand::Column(Identifier::new("col")),
+                oper: RelationOperator::Equal,
+                value: Operand::Const("0".to_string()),
+            }],
+            stmt.conditions()
+        );
+        let stmt = update("UPDATE t SET col=1 WHERE pk=1 IF col IS NOT NULL");
+        assert!(stmt.is_lwt());
+        assert_eq!(
+            &[RelationElement {
+                obj: Operand::Column(Identifier::new("col")),
+                oper: RelationOperator::IsNot,
+                value: Operand::Null,
+            }],
+            stmt.conditions()
+        );
+        let stmt = update("UPDATE t SET v=1 WHERE id=1 IF v = 3 AND owner IN ('a','b')");
+        assert!(stmt.is_lwt());
+        assert_eq!(
+            &[
+                RelationElement {
+                    obj: Operand::Column(Identifier::new("v")),
+                    oper: RelationOperator::Equal,
+                    value: Operand::Const("3".to_string()),
+                },
+                RelationElement {
+                    obj: Operand::Column(Identifier::new("owner")),
+                    oper: RelationOperator::In,
+                    value: Operand::Tuple(vec![
+                        Operand::Const("'a'".to_string()),
+                        Operand::Const("'b'".to_string()),
+                    ]),
+                },
+            ],
+            stmt.conditions()
+        );
+        let stmt = update("UPDATE t SET v=1 WHERE id=1 IF m['k'] = 3");
+        assert_eq!(
+            Operand::IndexedColumn(Box::new(IndexedColumn {
+                column: Identifier::new("m"),
+                idx: Some(Operand::Const("'k'".to_string())),
+                field: None,
+            })),
+            stmt.conditions()[0].obj
+        );
+        let stmt = update("UPDATE t SET v=1 WHERE id=1 IF addr.street = 'x'");
+        assert_eq!(
+            Operand::IndexedColumn(Box::new(IndexedColumn {
+                column: Identifier::new("addr"),
+                idx: None,
+                field: Some(Identifier::new("street")),
+            })),
+            stmt.conditions()[0].obj
+        );
+        let stmt = update("UPDATE t SET v=1 WHERE id=1 IF EXISTS");
+        assert!(stmt.is_lwt());
+        assert!(stmt.conditions().is_empty());
+        let stmt = update("UPDATE t SET col=1 WHERE pk=1");
+        assert!(!stmt.is_lwt());
+        assert!(stmt.conditions().is_empty());
+    }
+
     #[test]
     fn test_create_role() {
         let stmts = [
@@ -802,6 +2353,69 @@ mod tests {
         test_parsing(&expected, &stmts);
     }
 
+    #[test]
+    fn test_redacted() {
+        let statement = |qry: &str| CassandraAST::new(qry).statements[0].statement.clone();
+        assert_eq!(
+            "CREATE ROLE admin WITH PASSWORD = '*****' AND LOGIN = TRUE",
+            statement("CREATE ROLE admin WITH PASSWORD = 'hunter2' AND LOGIN = true")
+                .redacted()
+                .to_string()
+        );
+        assert_eq!(
+            "ALTER ROLE admin WITH OPTIONS = {'api_token':'*****', 'region':'us'}",
+            statement("ALTER ROLE admin WITH OPTIONS = { 'api_token' : 'abc', 'region' : 'us' }")
+                .redacted()
+                .to_string()
+        );
+        assert_eq!(
+            "CREATE USER bob WITH PASSWORD '*****' SUPERUSER",
+            statement("CREATE USER bob WITH PASSWORD 'hunter2' SUPERUSER")
+                .redacted()
+                .to_string()
+        );
+        let select = statement("SELECT password FROM users WHERE name = 'hunter2'");
+        assert_eq!(select, select.redacted());
+    }
+
+    #[test]
+    fn test_role_options() {
+        let stmts = [
+            "CREATE ROLE IF NOT EXISTS r WITH PASSWORD = 'x' AND LOGIN = true AND SUPERUSER = false",
+            "CREATE ROLE r WITH SUPERUSER = true AND OPTIONS = { 'a' : 1 } AND LOGIN = false",
+            "ALTER ROLE r WITH PASSWORD = 'y' AND SUPERUSER = true AND LOGIN = true",
+            "ALTER ROLE r WITH LOGIN = false AND OPTIONS = { 'a' : 1, 'b' : 'c' }",
+        ];
+        let expected = [
+            "CREATE ROLE IF NOT EXISTS r WITH PASSWORD = 'x' AND SUPERUSER = FALSE AND LOGIN = TRUE",
+            "CREATE ROLE r WITH SUPERUSER = TRUE AND LOGIN = FALSE AND OPTIONS = {'a':1}",
+            "ALTER ROLE r WITH PASSWORD = 'y' AND SUPERUSER = TRUE AND LOGIN = TRUE",
+            "ALTER ROLE r WITH LOGIN = FALSE AND OPTIONS = {'a':1, 'b':'c'}",
+        ];
+        test_parsing(&expected, &stmts);
+
+        let mut role = parse_one::<RoleCommon>(stmts[0]);
+        assert_eq!(
+            RoleCommon {
+                name: "r".to_string(),
+                password: Some(Operand::from("x")),
+                superuser: Some(false),
+                login: Some(true),
+                options: vec![],
+                if_not_exists: true,
+            },
+            role
+        );
+        role.redact_password();
+        assert_eq!(
+            "CREATE ROLE IF NOT EXISTS r WITH PASSWORD = '*****' AND SUPERUSER = FALSE AND LOGIN = TRUE",
+            CassandraStatement::CreateRole(role).to_string()
+        );
+
+        let role = parse_one::<RoleCommon>("CREATE ROLE r WITH PASSWORD = :pw");
+        assert_eq!(Some(Operand::Param(":pw".to_string())), role.password);
+    }
+
     #[test]
     fn test_create_user() {
         let stmts = [
@@ -1046,6 +2660,8 @@ mod tests {
             "CREATE INDEX ON table (keys ( key ) );",
             "CREATE INDEX ON table (entries ( spec ) );",
             "CREATE INDEX ON table (full ( spec ) );",
+            "CREATE CUSTOM INDEX ON ks.t (e) USING 'StorageAttachedIndex' WITH OPTIONS = {'similarity_function': 'cosine'};",
+            "CREATE CUSTOM INDEX IF NOT EXISTS idx ON t (v) USING 'org.example.Index';",
         ];
         let expected = [
             "CREATE INDEX index_name ON keyspace.table( column )",
@@ -1054,6 +2670,8 @@ mod tests {
             "CREATE INDEX ON table( KEYS( key ) )",
             "CREATE INDEX ON table( ENTRIES( spec ) )",
             "CREATE INDEX ON table( FULL( spec ) )",
+            "CREATE CUSTOM INDEX ON ks.t( e ) USING 'StorageAttachedIndex' WITH OPTIONS = {'similarity_function':'cosine'}",
+            "CREATE CUSTOM INDEX IF NOT EXISTS idx ON t( v ) USING 'org.example.Index'",
         ];
         test_parsing(&expected, &stmts);
     }
@@ -1067,6 +2685,8 @@ mod tests {
         "CREATE TABLE keyspace.table (col1 text, col2 int, col3 FROZEN<col4>, PRIMARY KEY (col1, col2) ) WITH CLUSTERING ORDER BY ( col2 )",
         "CREATE TABLE keyspace.table (col1 text, col2 int, col3 FROZEN<col4>, PRIMARY KEY (col1, col2) ) WITH option = 'option' AND option2 = 3.5 AND  CLUSTERING ORDER BY ( col2 )",
         "CREATE TABLE keyspace.table (col1 text, col2 int, PRIMARY KEY (col1) ) WITH option1='value' AND CLUSTERING ORDER BY ( col2 ) AND ID='someId' AND COMPACT STORAGE",
+        "CREATE TABLE keyspace.table (col1 text, col2 int, col3 int, PRIMARY KEY (col1, col2, col3) ) WITH CLUSTERING ORDER BY ( col2 DESC, col3 )",
+        "CREATE TABLE keyspace.table (\"my col\" text PRIMARY KEY, col2 int)",
     ];
         let expected = [
         "CREATE TABLE IF NOT EXISTS keyspace.table (col1 TEXT, col2 INT, col3 FROZEN<col4>, PRIMARY KEY (col1, col2))",
@@ -1075,6 +2695,8 @@ mod tests {
         "CREATE TABLE keyspace.table (col1 TEXT, col2 INT, col3 FROZEN<col4>, PRIMARY KEY (col1, col2)) WITH CLUSTERING ORDER BY (col2 ASC)",
         "CREATE TABLE keyspace.table (col1 TEXT, col2 INT, col3 FROZEN<col4>, PRIMARY KEY (col1, col2)) WITH option = 'option' AND option2 = 3.5 AND CLUSTERING ORDER BY (col2 ASC)",
         "CREATE TABLE keyspace.table (col1 TEXT, col2 INT, PRIMARY KEY (col1)) WITH option1 = 'value' AND CLUSTERING ORDER BY (col2 ASC) AND ID = 'someId' AND COMPACT STORAGE",
+        "CREATE TABLE keyspace.table (col1 TEXT, col2 INT, col3 INT, PRIMARY KEY (col1, col2, col3)) WITH CLUSTERING ORDER BY (col2 DESC, col3 ASC)",
+        "CREATE TABLE keyspace.table (\"my col\" TEXT PRIMARY KEY, col2 INT)",
     ];
         test_parsing(&expected, &stmts);
     }
@@ -1306,4 +2928,192 @@ mod tests {
         ];
         test_parsing(&expected, &stmts);
     }
+
+    #[test]
+    fn test_quoted_identifiers() {
+        let stmts = [
+            "INSERT INTO t (\"Order\", \"user-id\") VALUES (?, ?)",
+            "SELECT \"Order\", \"say \"\"hi\"\"\" FROM t WHERE \"user-id\" = 5",
+            "UPDATE t SET \"Order\" = 1 WHERE \"user-id\" = 5",
+            "DELETE \"Order\" FROM t WHERE \"user-id\" = 5",
+            "CREATE TABLE t (\"user-id\" int, \"Order\" int, PRIMARY KEY (\"user-id\", \"Order\"))",
+        ];
+        test_parsing(&stmts, &stmts);
+
+        let select = parse_one::<Select>(stmts[1]);
+        assert_eq!(
+            Operand::Column(Identifier::new("user-id")),
+            select.where_clause[0].obj
+        );
+        assert_eq!(vec!["Order", "say \"hi\""], select.select_alias());
+    }
+
+    #[test]
+    fn test_strip_and_rename_keyspace() {
+        let statement = |qry: &str| CassandraAST::new(qry).statements[0].statement.clone();
+
+        let mut stmt = statement("SELECT * FROM ks.t WHERE pk = 1");
+        stmt.strip_keyspace();
+        assert_eq!("SELECT * FROM t WHERE pk = 1", stmt.to_string());
+        stmt.strip_keyspace();
+        assert_eq!("SELECT * FROM t WHERE pk = 1", stmt.to_string());
+
+        let qry = "CREATE MATERIALIZED VIEW ks.view AS SELECT col1 FROM ks.tbl WHERE col1 IS NOT NULL PRIMARY KEY (col1)";
+        let mut stmt = statement(qry);
+        assert_eq!(2, stmt.rename_keyspace("KS", "other"));
+        assert_eq!(
+            "CREATE MATERIALIZED VIEW other.view AS SELECT col1 FROM other.tbl WHERE col1 IS NOT NULL PRIMARY KEY (col1)",
+            stmt.to_string()
+        );
+        assert_eq!(0, stmt.rename_keyspace("ks", "third"));
+        assert!(stmt
+            .to_string()
+            .starts_with("CREATE MATERIALIZED VIEW other.view"));
+
+        let mut stmt = statement("USE ks");
+        stmt.strip_keyspace();
+        assert_eq!("USE ks", stmt.to_string());
+    }
+
+    #[test]
+    fn test_replace_table() {
+        let statement = |qry: &str| CassandraAST::new(qry).statements[0].statement.clone();
+        let old = FQName::new("ks", "users");
+        let new = FQName::new("ks", "accounts");
+
+        let mut stmt = statement("SELECT * FROM KS.Users WHERE name = 'ks.users'");
+        assert_eq!(1, stmt.replace_table(&old, &new));
+        assert_eq!(
+            "SELECT * FROM ks.accounts WHERE name = 'ks.users'",
+            stmt.to_string()
+        );
+        assert_eq!(0, stmt.replace_table(&old, &new));
+
+        let qry = "CREATE MATERIALIZED VIEW ks.by_name AS SELECT name FROM ks.users WHERE name IS NOT NULL PRIMARY KEY (name)";
+        let mut stmt = statement(qry);
+        assert_eq!(1, stmt.replace_table(&old, &new));
+        assert_eq!(
+            "CREATE MATERIALIZED VIEW ks.by_name AS SELECT name FROM ks.accounts WHERE name IS NOT NULL PRIMARY KEY (name)",
+            stmt.to_string()
+        );
+
+        let mut stmt = statement("SELECT * FROM users");
+        assert_eq!(0, stmt.replace_table(&old, &new));
+    }
+
+    #[test]
+    fn test_fqnames() {
+        let statement = |qry: &str| CassandraAST::new(qry).statements[0].statement.clone();
+
+        let qry = "CREATE MATERIALIZED VIEW ks.by_name AS SELECT name FROM users WHERE name IS NOT NULL PRIMARY KEY (name)";
+        let mut stmt = statement(qry);
+        assert_eq!(
+            vec![&FQName::new("ks", "by_name"), &FQName::simple("users")],
+            stmt.fqnames().collect::<Vec<_>>()
+        );
+        for name in stmt.fqnames_mut() {
+            name.name = name.name.to_uppercase();
+        }
+        assert_eq!(
+            vec![&FQName::from("ks.BY_NAME"), &FQName::from("USERS")],
+            stmt.fqnames().collect::<Vec<_>>()
+        );
+
+        assert_eq!(0, statement("USE ks").fqnames().count());
+        assert_eq!(0, statement("DROP KEYSPACE ks").fqnames().count());
+        assert_eq!(
+            vec![&FQName::new("ks", "t")],
+            statement("GRANT SELECT ON TABLE ks.t TO role")
+                .fqnames()
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_rename_keyspace() {
+        let statement = |qry: &str| CassandraAST::new(qry).statements[0].statement.clone();
+
+        let qry = "CREATE MATERIALIZED VIEW KS.by_name AS SELECT name FROM ks.users WHERE name IS NOT NULL PRIMARY KEY (name)";
+        let mut stmt = statement(qry);
+        assert_eq!(2, stmt.rename_keyspace("ks", "archive"));
+        assert_eq!(
+            "CREATE MATERIALIZED VIEW archive.by_name AS SELECT name FROM archive.users WHERE name IS NOT NULL PRIMARY KEY (name)",
+            stmt.to_string()
+        );
+        assert_eq!(0, stmt.rename_keyspace("ks", "archive"));
+
+        let mut stmt = statement("SELECT * FROM \"KS\".users");
+        assert_eq!(0, stmt.rename_keyspace("ks", "archive"));
+
+        let mut stmt = statement("SELECT * FROM users");
+        assert_eq!(0, stmt.rename_keyspace("ks", "archive"));
+        assert_eq!(1, stmt.rename_keyspace("", "archive"));
+        assert_eq!("SELECT * FROM archive.users", stmt.to_string());
+    }
+
+    #[test]
+    fn test_complexity() {
+        let values = (0..100)
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let qry = format!(
+            "SELECT a, b, c FROM t WHERE pk IN ({}) AND c IN (1, 2) AND d > 5 ALLOW FILTERING",
+            values
+        );
+        let ast = CassandraAST::new(&qry);
+        assert!(!ast.has_error());
+        assert_eq!(
+            Complexity {
+                where_relations: 3,
+                projected_columns: 3,
+                in_list_cardinality: 200,
+                allow_filtering: true,
+            },
+            ast.statements[0].statement.complexity()
+        );
+        assert_eq!(
+            Complexity {
+                where_relations: 1,
+                projected_columns: 1,
+                in_list_cardinality: 0,
+                allow_filtering: false,
+            },
+            CassandraAST::new("SELECT * FROM t WHERE pk = 1").statements[0]
+                .statement
+                .complexity()
+        );
+        assert_eq!(
+            Complexity::default(),
+            CassandraAST::new("INSERT INTO t (a) VALUES (1)").statements[0]
+                .statement
+                .complexity()
+        );
+    }
+
+    #[test]
+    fn test_columns_read_and_written() {
+        let statement = |qry: &str| CassandraAST::new(qry).statements[0].statement.clone();
+        let stmt =
+            statement("SELECT a, count(b), a, now() FROM t WHERE pk = 1 AND (c, d) > (1, 2)");
+        assert_eq!(vec!["a", "b", "pk", "c", "d"], stmt.columns_read());
+        assert!(stmt.columns_written().is_empty());
+
+        let stmt = statement("SELECT * FROM t WHERE pk = 1");
+        assert_eq!(vec!["*", "pk"], stmt.columns_read());
+
+        let stmt = statement(
+            "UPDATE t SET m['k'] = 1, v = 2, m['j'] = 3 WHERE pk = 1 IF owner = 'x' AND pk = 1",
+        );
+        assert_eq!(vec!["m", "v"], stmt.columns_written());
+        assert_eq!(vec!["pk", "owner"], stmt.columns_read());
+
+        let stmt = statement("DELETE l[0], \"Name\" FROM t WHERE pk = 1 IF EXISTS");
+        assert_eq!(vec!["l", "Name"], stmt.columns_written());
+        assert_eq!(vec!["pk"], stmt.columns_read());
+
+        let stmt = statement("INSERT INTO t (pk, v) VALUES (1, 2)");
+        assert_eq!(vec!["pk", "v"], stmt.columns_written());
+        assert!(stmt.columns_read().is_empty());
+    }
 }
@@ -1,12 +1,21 @@
+use crate::alter_table::AlterError;
+use crate::delete::IndexedColumn;
+use crate::keywords::is_reserved_keyword;
+#[cfg(feature = "typed-operands")]
 use bigdecimal::BigDecimal;
+#[cfg(feature = "typed-operands")]
 use bytes::Bytes;
+#[cfg(feature = "typed-operands")]
 use hex;
 use itertools::Itertools;
+#[cfg(feature = "typed-operands")]
 use num::BigInt;
 use serde::Deserialize;
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::fmt::{Display, Formatter};
 use std::net::IpAddr;
+use std::str::FromStr;
+#[cfg(feature = "typed-operands")]
 use uuid::Uuid;
 
 /// A column definition.
@@ -15,20 +24,59 @@ use uuid::Uuid;
 #[derive(PartialEq, Debug, Clone)]
 pub struct ColumnDefinition {
     /// the name of the column
-    pub name: String,
+    pub name: Identifier,
     /// the data type for the column
     pub data_type: DataType,
     /// if set this column is the primary key.
     pub primary_key: bool,
+    /// if set this column is shared by all the rows in a partition (`STATIC`).
+    pub is_static: bool,
+}
+
+impl ColumnDefinition {
+    /// creates the `ALTER TABLE ... ADD` statement that adds this column to the table.
+    /// Returns an error if the column is marked as the primary key.
+    pub fn to_alter_add_cql(
+        &self,
+        table: &FQName,
+        if_not_exists: bool,
+    ) -> Result<String, AlterError> {
+        Ok(format!(
+            "ALTER TABLE {} ADD {}{}",
+            table,
+            if if_not_exists { "IF NOT EXISTS " } else { "" },
+            self.to_alter_add_fragment()?
+        ))
+    }
+
+    /// returns the column definition without the `PRIMARY KEY` marker, e.g. `name TEXT` or
+    /// `name TEXT STATIC`.  Use `Display` to include the marker in a `CREATE TABLE`.
+    pub fn to_create_column_cql(&self) -> String {
+        format!(
+            "{} {}{}",
+            self.name,
+            self.data_type,
+            if self.is_static { " STATIC" } else { "" }
+        )
+    }
+
+    /// returns the text that follows `ALTER TABLE ... ADD`, e.g. `name TEXT STATIC`.  Returns
+    /// an error if the column is marked as the primary key as a primary key column can not
+    /// be added to a table.
+    pub fn to_alter_add_fragment(&self) -> Result<String, AlterError> {
+        if self.primary_key {
+            return Err(AlterError::PrimaryKeyInAlter);
+        }
+        Ok(self.to_create_column_cql())
+    }
 }
 
 impl Display for ColumnDefinition {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "{} {}{}",
-            self.name,
-            self.data_type,
+            "{}{}",
+            self.to_create_column_cql(),
             if self.primary_key { " PRIMARY KEY" } else { "" }
         )
     }
@@ -41,19 +89,282 @@ pub struct DataType {
     pub name: DataTypeName,
     /// the definition of the data type.  Normally this is empty but may contain data types that
     /// comprise the named type. (e.g. `FROZEN<foo>` will have foo in the definition)
-    pub definition: Vec<DataTypeName>,
+    pub definition: Vec<DataType>,
 }
 
 impl Display for DataType {
+    /// the alternate form (`{:#}`) writes the built in type names in lower case.
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        if self.definition.is_empty() {
-            write!(f, "{}", self.name)
+        if f.alternate() {
+            write!(f, "{:#}", self.name)?;
+        } else {
+            write!(f, "{}", self.name)?;
+        }
+        let mut args = if f.alternate() {
+            self.definition
+                .iter()
+                .map(|x| format!("{:#}", x))
+                .collect::<Vec<String>>()
+        } else {
+            self.definition.iter().map(DataType::to_string).collect()
+        };
+        if let DataTypeName::Vector(dimension) = self.name {
+            args.push(dimension.to_string());
+        }
+        if args.is_empty() {
+            Ok(())
         } else {
-            write!(f, "{}<{}>", self.name, self.definition.iter().join(", "))
+            write!(f, "<{}>", args.join(", "))
+        }
+    }
+}
+
+impl DataType {
+    /// parse a standalone data type string such as `FROZEN<MAP<TEXT, LIST<INT>>>`.
+    pub fn try_parse(s: &str) -> Result<DataType, DataTypeParseError> {
+        let tokens = DataType::tokenize(s)?;
+        let mut pos = 0;
+        let result = DataType::parse_tokens(&tokens, &mut pos)?;
+        match tokens.get(pos) {
+            None => Ok(result),
+            Some(token) => Err(DataTypeParseError::UnexpectedToken(token.to_string())),
+        }
+    }
+
+    /// split the type string into names and the `<`, `>` and `,` punctuation.
+    fn tokenize(s: &str) -> Result<Vec<String>, DataTypeParseError> {
+        let mut tokens = vec![];
+        let mut chars = s.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                _ if c.is_whitespace() => {}
+                '<' | '>' | ',' => tokens.push(c.to_string()),
+                '"' => {
+                    // quoted names run to the closing quote, `""` is an escaped quote.
+                    let mut token = String::from(c);
+                    loop {
+                        match chars.next() {
+                            None => return Err(DataTypeParseError::UnexpectedEnd),
+                            Some('"') if chars.peek() == Some(&'"') => {
+                                chars.next();
+                                token.push_str("\"\"");
+                            }
+                            Some('"') => {
+                                token.push('"');
+                                break;
+                            }
+                            Some(x) => token.push(x),
+                        }
+                    }
+                    tokens.push(token);
+                }
+                _ => {
+                    let mut token = String::from(c);
+                    while let Some(x) = chars.peek() {
+                        if x.is_whitespace() || "<>,\"".contains(*x) {
+                            break;
+                        }
+                        token.push(*x);
+                        chars.next();
+                    }
+                    tokens.push(token);
+                }
+            }
+        }
+        Ok(tokens)
+    }
+
+    /// parse a single, possibly generic, data type starting at `pos`.
+    fn parse_tokens(tokens: &[String], pos: &mut usize) -> Result<DataType, DataTypeParseError> {
+        let mut name = match tokens.get(*pos) {
+            None => return Err(DataTypeParseError::UnexpectedEnd),
+            Some(token) if token == "<" || token == ">" || token == "," => {
+                return Err(DataTypeParseError::UnexpectedToken(token.to_string()))
+            }
+            Some(token) => DataTypeName::from(token),
+        };
+        *pos += 1;
+        let mut definition = vec![];
+        if tokens.get(*pos).map_or(false, |t| t == "<") {
+            *pos += 1;
+            loop {
+                match (&mut name, tokens.get(*pos).map(|t| t.parse())) {
+                    (DataTypeName::Vector(dimension), Some(Ok(value))) => {
+                        *dimension = value;
+                        *pos += 1;
+                    }
+                    _ => definition.push(DataType::parse_tokens(tokens, pos)?),
+                }
+                match tokens.get(*pos).map(|t| t.as_str()) {
+                    None => return Err(DataTypeParseError::UnexpectedEnd),
+                    Some(",") => *pos += 1,
+                    Some(">") => {
+                        *pos += 1;
+                        break;
+                    }
+                    Some(token) => {
+                        return Err(DataTypeParseError::UnexpectedToken(token.to_string()))
+                    }
+                }
+            }
+        }
+        Ok(DataType { name, definition })
+    }
+
+    /// returns the type with the built in type names in lower case, e.g.
+    /// `map<text, frozen<list<int>>>`.
+    pub fn to_lowercase_string(&self) -> String {
+        format!("{:#}", self)
+    }
+
+    /// creates the `VECTOR<element, dimension>` type.
+    pub fn vector(element: DataType, dimension: usize) -> DataType {
+        DataType {
+            name: DataTypeName::Vector(dimension),
+            definition: vec![element],
+        }
+    }
+
+    /// returns the element type and dimension of a `VECTOR`, `None` for other types.
+    pub fn as_vector(&self) -> Option<(&DataType, usize)> {
+        match (&self.name, self.definition.as_slice()) {
+            (DataTypeName::Vector(dimension), [element]) => Some((element, *dimension)),
+            _ => None,
+        }
+    }
+
+    /// returns a copy of the data type with `FROZEN` added where Cassandra requires it.  The
+    /// rules applied are:
+    ///  * a `LIST`, `SET`, `MAP` or user defined type that is an element, key or value of a
+    ///    `LIST`, `SET` or `MAP` is wrapped in `FROZEN`.
+    ///  * types within `FROZEN` or `TUPLE` are not changed as they are implicitly frozen.
+    ///  * the outermost type is not changed, top level collections may be updated in place.
+    pub fn with_required_frozen(&self) -> DataType {
+        match self.name {
+            DataTypeName::List | DataTypeName::Set | DataTypeName::Map => DataType {
+                name: self.name.clone(),
+                definition: self
+                    .definition
+                    .iter()
+                    .map(|element| match element.name {
+                        DataTypeName::List
+                        | DataTypeName::Set
+                        | DataTypeName::Map
+                        | DataTypeName::Custom(_) => DataType {
+                            name: DataTypeName::Frozen,
+                            definition: vec![element.clone()],
+                        },
+                        _ => element.clone(),
+                    })
+                    .collect(),
+            },
+            _ => self.clone(),
+        }
+    }
+
+    /// returns true if values of this type can be read as `other`, that is if a column of
+    /// this type can be changed to `other` without rewriting the data.  The rules follow the
+    /// CQL data types section of the Cassandra documentation and the value compatibility
+    /// checks in Cassandra's `AbstractType` implementations:
+    ///  * `TEXT` and `VARCHAR` are aliases, as are `BLOB` and its internal name `BYTES`
+    ///    (native types table).
+    ///  * `ASCII` may be read as `TEXT` as ASCII is a subset of UTF-8 (`UTF8Type`).
+    ///  * integers may be widened, `TINYINT` → `SMALLINT` → `INT` → `BIGINT` → `VARINT`, and
+    ///    `FLOAT` may be widened to `DOUBLE` (native types table).
+    ///  * `BIGINT` and `TIMESTAMP` share a representation, milliseconds since the epoch
+    ///    (`LongType`, `TimestampType`).
+    ///  * `TIMEUUID` may be read as `UUID` (`UUIDType`).
+    ///  * any type other than a collection or counter may be read as `BLOB` (`BytesType`).
+    ///  * `COUNTER` is only compatible with itself (counters section).
+    ///  * `LIST`, `SET`, `MAP` and `FROZEN` require the same kind of type with compatible
+    ///    elements, keys and values (collections section).
+    ///  * a `TUPLE` may gain fields at the end, the existing fields must be compatible (tuples
+    ///    section).
+    ///  * user defined types must have the same name (user-defined types section).
+    ///  * a `VECTOR` requires the same element type and dimension (vectors section).
+    pub fn compatible_with(&self, other: &DataType) -> bool {
+        match (&self.name, &other.name) {
+            (DataTypeName::Vector(_), DataTypeName::Vector(_)) => self
+                .as_vector()
+                .map_or(false, |vector| Some(vector) == other.as_vector()),
+            (DataTypeName::List, DataTypeName::List)
+            | (DataTypeName::Set, DataTypeName::Set)
+            | (DataTypeName::Map, DataTypeName::Map)
+            | (DataTypeName::Frozen, DataTypeName::Frozen) => {
+                self.definition.len() == other.definition.len() && self.elements_compatible(other)
+            }
+            (DataTypeName::Tuple, DataTypeName::Tuple) => {
+                self.definition.len() <= other.definition.len() && self.elements_compatible(other)
+            }
+            (DataTypeName::List, _)
+            | (DataTypeName::Set, _)
+            | (DataTypeName::Map, _)
+            | (DataTypeName::Frozen, _)
+            | (DataTypeName::Tuple, _)
+            | (DataTypeName::Vector(_), _)
+            | (_, DataTypeName::List)
+            | (_, DataTypeName::Set)
+            | (_, DataTypeName::Map)
+            | (_, DataTypeName::Frozen)
+            | (_, DataTypeName::Tuple)
+            | (_, DataTypeName::Vector(_)) => false,
+            (DataTypeName::Counter, name) | (name, DataTypeName::Counter) => {
+                *name == DataTypeName::Counter
+            }
+            (from, to) => {
+                let from = from.canonical();
+                let to = to.canonical();
+                match (from.integer_width(), to.integer_width()) {
+                    (Some(from_width), Some(to_width)) => from_width <= to_width,
+                    _ => {
+                        from == to
+                            || to == DataTypeName::Blob
+                            || matches!(
+                                (from, to),
+                                (DataTypeName::Ascii, DataTypeName::Text)
+                                    | (DataTypeName::Float, DataTypeName::Double)
+                                    | (DataTypeName::Timestamp, DataTypeName::BigInt)
+                                    | (DataTypeName::BigInt, DataTypeName::Timestamp)
+                                    | (DataTypeName::TimeUuid, DataTypeName::Uuid)
+                            )
+                    }
+                }
+            }
+        }
+    }
+
+    /// true if each of the elements of this type is compatible with the element of `other`
+    /// at the same position.
+    fn elements_compatible(&self, other: &DataType) -> bool {
+        self.definition
+            .iter()
+            .zip(other.definition.iter())
+            .all(|(from, to)| from.compatible_with(to))
+    }
+}
+
+/// the error returned when a data type string can not be parsed.
+#[derive(PartialEq, Debug, Clone)]
+pub enum DataTypeParseError {
+    /// the string ended before the data type was complete.
+    UnexpectedEnd,
+    /// the token was not expected at its position.
+    UnexpectedToken(String),
+}
+
+impl Display for DataTypeParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DataTypeParseError::UnexpectedEnd => write!(f, "unexpected end of data type"),
+            DataTypeParseError::UnexpectedToken(token) => {
+                write!(f, "unexpected token in data type: '{}'", token)
+            }
         }
     }
 }
 
+impl std::error::Error for DataTypeParseError {}
+
 /// An enumeration of data types.
 #[derive(PartialEq, Debug, Clone)]
 pub enum DataTypeName {
@@ -82,44 +393,84 @@ pub enum DataTypeName {
     VarChar,
     VarInt,
     Uuid,
+    /// a fixed length vector with the dimension, `VECTOR<FLOAT, 384>`.  The definition holds
+    /// the element type, see `DataType::vector()`.  `DataTypeName::from()` returns a dimension
+    /// of 0 as the dimension is part of the type definition.
+    Vector(usize),
     /// defines a custom type.  Where the name is the name of the type.
     Custom(String),
 }
 
 impl Display for DataTypeName {
+    /// the alternate form (`{:#}`) writes the built in type names in lower case.
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        match self {
-            DataTypeName::Timestamp => write!(f, "TIMESTAMP"),
-            DataTypeName::Set => write!(f, "SET"),
-            DataTypeName::Ascii => write!(f, "ASCII"),
-            DataTypeName::BigInt => write!(f, "BIGINT"),
-            DataTypeName::Blob => write!(f, "BLOB"),
-            DataTypeName::Boolean => write!(f, "BOOLEAN"),
-            DataTypeName::Counter => write!(f, "COUNTER"),
-            DataTypeName::Date => write!(f, "DATE"),
-            DataTypeName::Decimal => write!(f, "DECIMAL"),
-            DataTypeName::Double => write!(f, "DOUBLE"),
-            DataTypeName::Float => write!(f, "FLOAT"),
-            DataTypeName::Frozen => write!(f, "FROZEN"),
-            DataTypeName::Inet => write!(f, "INET"),
-            DataTypeName::Int => write!(f, "INT"),
-            DataTypeName::List => write!(f, "LIST"),
-            DataTypeName::Map => write!(f, "MAP"),
-            DataTypeName::SmallInt => write!(f, "SMALLINT"),
-            DataTypeName::Text => write!(f, "TEXT"),
-            DataTypeName::Time => write!(f, "TIME"),
-            DataTypeName::TimeUuid => write!(f, "TIMEUUID"),
-            DataTypeName::TinyInt => write!(f, "TINYINT"),
-            DataTypeName::Tuple => write!(f, "TUPLE"),
-            DataTypeName::VarChar => write!(f, "VARCHAR"),
-            DataTypeName::VarInt => write!(f, "VARINT"),
-            DataTypeName::Uuid => write!(f, "UUID"),
-            DataTypeName::Custom(name) => write!(f, "{}", name),
+        let name = match self {
+            DataTypeName::Timestamp => "TIMESTAMP",
+            DataTypeName::Set => "SET",
+            DataTypeName::Ascii => "ASCII",
+            DataTypeName::BigInt => "BIGINT",
+            DataTypeName::Blob => "BLOB",
+            DataTypeName::Boolean => "BOOLEAN",
+            DataTypeName::Counter => "COUNTER",
+            DataTypeName::Date => "DATE",
+            DataTypeName::Decimal => "DECIMAL",
+            DataTypeName::Double => "DOUBLE",
+            DataTypeName::Float => "FLOAT",
+            DataTypeName::Frozen => "FROZEN",
+            DataTypeName::Inet => "INET",
+            DataTypeName::Int => "INT",
+            DataTypeName::List => "LIST",
+            DataTypeName::Map => "MAP",
+            DataTypeName::SmallInt => "SMALLINT",
+            DataTypeName::Text => "TEXT",
+            DataTypeName::Time => "TIME",
+            DataTypeName::TimeUuid => "TIMEUUID",
+            DataTypeName::TinyInt => "TINYINT",
+            DataTypeName::Tuple => "TUPLE",
+            DataTypeName::VarChar => "VARCHAR",
+            DataTypeName::VarInt => "VARINT",
+            DataTypeName::Uuid => "UUID",
+            DataTypeName::Vector(_) => "VECTOR",
+            DataTypeName::Custom(name) => return write!(f, "{}", name),
+        };
+        if f.alternate() {
+            write!(f, "{}", name.to_lowercase())
+        } else {
+            write!(f, "{}", name)
         }
     }
 }
 
 impl DataTypeName {
+    /// returns the name in lower case (e.g. `timestamp`) as expected by some drivers and
+    /// schema registries.  User defined type names are returned as written.
+    pub fn to_lowercase_string(&self) -> String {
+        format!("{:#}", self)
+    }
+
+    /// returns the name with aliases resolved: `VARCHAR` is `TEXT`, `BYTES` is `BLOB` and
+    /// user defined type names are normalized.
+    fn canonical(&self) -> DataTypeName {
+        match self {
+            DataTypeName::VarChar => DataTypeName::Text,
+            DataTypeName::Custom(name) if name.eq_ignore_ascii_case("BYTES") => DataTypeName::Blob,
+            DataTypeName::Custom(name) => DataTypeName::Custom(normalize_name(name)),
+            _ => self.clone(),
+        }
+    }
+
+    /// the relative width of the integer types, `None` for other types.
+    fn integer_width(&self) -> Option<u8> {
+        match self {
+            DataTypeName::TinyInt => Some(1),
+            DataTypeName::SmallInt => Some(2),
+            DataTypeName::Int => Some(3),
+            DataTypeName::BigInt => Some(4),
+            DataTypeName::VarInt => Some(5),
+            _ => None,
+        }
+    }
+
     pub fn from(name: &str) -> DataTypeName {
         match name.to_uppercase().as_str() {
             "ASCII" => DataTypeName::Ascii,
@@ -147,6 +498,7 @@ impl DataTypeName {
             "UUID" => DataTypeName::Uuid,
             "VARCHAR" => DataTypeName::VarChar,
             "VARINT" => DataTypeName::VarInt,
+            "VECTOR" => DataTypeName::Vector(0),
             _ => DataTypeName::Custom(name.to_string()),
         }
     }
@@ -166,7 +518,9 @@ pub enum Operand {
     /// a tuple of values.  Displays as `{ Operand, Operand, ... }`
     Tuple(Vec<Operand>),
     /// A column name
-    Column(String),
+    Column(Identifier),
+    /// A collection element or user defined type field, `column[idx]` or `column.field`
+    IndexedColumn(Box<IndexedColumn>),
     /// A function name
     Func(String),
     /// A parameter.  The string will either be '?' or ':name'
@@ -185,6 +539,7 @@ impl From<&str> for Operand {
     }
 }
 
+#[cfg(feature = "typed-operands")]
 impl From<&Bytes> for Operand {
     fn from(b: &Bytes) -> Self {
         Operand::from_hex(&hex::encode(b))
@@ -268,12 +623,14 @@ impl From<&f32> for Operand {
     }
 }
 
+#[cfg(feature = "typed-operands")]
 impl From<&BigInt> for Operand {
     fn from(b: &BigInt) -> Self {
         Operand::Const(b.to_string())
     }
 }
 
+#[cfg(feature = "typed-operands")]
 impl From<&BigDecimal> for Operand {
     fn from(b: &BigDecimal) -> Self {
         Operand::Const(b.to_string())
@@ -286,18 +643,108 @@ impl From<&IpAddr> for Operand {
     }
 }
 
+#[cfg(feature = "typed-operands")]
 impl From<&Uuid> for Operand {
     fn from(uuid: &Uuid) -> Self {
         Operand::from(uuid.to_string().as_str())
     }
 }
 
+/// creates an `Operand::Map` from the key and value pairs.  The keys and values are the CQL
+/// text of the entries so strings must already be quoted.
+/// ```
+/// use cql3_parser::common::Operand;
+/// let map = Operand::from(vec![("'a'".to_string(), "1".to_string())]);
+/// assert_eq!("{'a':1}", map.to_string());
+/// ```
+impl From<Vec<(String, String)>> for Operand {
+    fn from(entries: Vec<(String, String)>) -> Self {
+        Operand::Map(entries)
+    }
+}
+
+/// creates an `Operand::List` from the values.  The values are the CQL text of the entries
+/// so strings must already be quoted.
+/// ```
+/// use cql3_parser::common::Operand;
+/// let list = Operand::from(vec!["1".to_string(), "2".to_string()]);
+/// assert_eq!("[1, 2]", list.to_string());
+/// ```
+impl From<Vec<String>> for Operand {
+    fn from(values: Vec<String>) -> Self {
+        Operand::List(values)
+    }
+}
+
+/// creates an `Operand::Set` from the values.  The values are the CQL text of the entries
+/// so strings must already be quoted.
+/// ```
+/// use cql3_parser::common::Operand;
+/// use std::collections::BTreeSet;
+/// let set = Operand::from(BTreeSet::from(["'b'".to_string(), "'a'".to_string()]));
+/// assert_eq!("{'a', 'b'}", set.to_string());
+/// ```
+impl From<BTreeSet<String>> for Operand {
+    fn from(values: BTreeSet<String>) -> Self {
+        Operand::Set(values.into_iter().collect())
+    }
+}
+
+/// creates an `Operand::Set` from the values.  The values are sorted so that the set always
+/// displays the same way.  The values are the CQL text of the entries so strings must already
+/// be quoted.
+/// ```
+/// use cql3_parser::common::Operand;
+/// use std::collections::HashSet;
+/// let set = Operand::from(HashSet::from(["2".to_string(), "1".to_string()]));
+/// assert_eq!("{1, 2}", set.to_string());
+/// ```
+impl From<HashSet<String>> for Operand {
+    fn from(values: HashSet<String>) -> Self {
+        Operand::Set(values.into_iter().sorted().collect())
+    }
+}
+
 impl Operand {
     /// creates creates a properly formated Operand::Const for a hex string.
+    #[cfg(feature = "typed-operands")]
     fn from_hex(hex_str: &str) -> Operand {
         Operand::Const(format!("0x{}", hex_str))
     }
 
+    /// returns true if the operand always yields the same value.  Function calls (e.g. `now()`)
+    /// may not, including those within a tuple.
+    pub fn is_fixed_value(&self) -> bool {
+        match self {
+            Operand::Func(_) => false,
+            Operand::Tuple(operands) | Operand::Collection(operands) => {
+                operands.iter().all(Operand::is_fixed_value)
+            }
+            _ => true,
+        }
+    }
+
+    /// creates an `Operand::List` from the operands.  Each operand is stored as its CQL text.
+    pub fn list_from<I: IntoIterator<Item = Operand>>(values: I) -> Operand {
+        Operand::List(values.into_iter().map(|x| x.to_string()).collect())
+    }
+
+    /// creates an `Operand::Set` from the operands.  Each operand is stored as its CQL text.
+    pub fn set_from<I: IntoIterator<Item = Operand>>(values: I) -> Operand {
+        Operand::Set(values.into_iter().map(|x| x.to_string()).collect())
+    }
+
+    /// creates an `Operand::Map` from the key and value operands.  Each operand is stored as
+    /// its CQL text.
+    pub fn map_from<I: IntoIterator<Item = (Operand, Operand)>>(entries: I) -> Operand {
+        Operand::Map(
+            entries
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        )
+    }
+
     /// unescapes a CQL string
     /// Specifically converts `''` to `'` and removes the leading and
     /// trailing delimiters.  For all other strings this is method returns
@@ -323,6 +770,143 @@ impl Operand {
         }
     }
 
+    /// returns the text of a constant operand.
+    fn const_text(&self) -> Result<&str, OperandConversionError> {
+        match self {
+            Operand::Const(text) => Ok(text),
+            _ => Err(OperandConversionError::NotConst(self.to_string())),
+        }
+    }
+
+    /// parses the unescaped constant text as the type `T`.
+    fn parse_const<T: FromStr>(
+        &self,
+        type_name: &'static str,
+    ) -> Result<T, OperandConversionError> {
+        let text = Operand::unescape(self.const_text()?);
+        text.parse::<T>()
+            .map_err(|_| OperandConversionError::InvalidValue { text, type_name })
+    }
+
+    /// parses an unquoted constant as the numeric type `T`.  Quoted constants such as `'1'`
+    /// are strings and are rejected.
+    fn parse_number<T: FromStr>(
+        &self,
+        type_name: &'static str,
+    ) -> Result<T, OperandConversionError> {
+        let text = self.const_text()?;
+        if text.starts_with('\'') || text.starts_with("$$") {
+            return Err(OperandConversionError::InvalidValue {
+                text: text.to_string(),
+                type_name,
+            });
+        }
+        self.parse_const(type_name)
+    }
+
+    /// converts an unquoted constant to an `i64`.
+    pub fn as_i64(&self) -> Result<i64, OperandConversionError> {
+        self.parse_number("i64")
+    }
+
+    /// converts an unquoted constant to an `f64`.
+    pub fn as_f64(&self) -> Result<f64, OperandConversionError> {
+        self.parse_number("f64")
+    }
+
+    /// converts an unquoted constant to a `bool`, ignoring case.
+    pub fn as_bool(&self) -> Result<bool, OperandConversionError> {
+        let text = self.const_text()?;
+        match text.to_uppercase().as_str() {
+            "TRUE" => Ok(true),
+            "FALSE" => Ok(false),
+            _ => Err(OperandConversionError::InvalidValue {
+                text: text.to_string(),
+                type_name: "bool",
+            }),
+        }
+    }
+
+    /// converts a quoted constant to the unescaped `String`.
+    pub fn as_string(&self) -> Result<String, OperandConversionError> {
+        let text = self.const_text()?;
+        if text.starts_with('\'') || text.starts_with("$$") {
+            Ok(Operand::unescape(text))
+        } else {
+            Err(OperandConversionError::InvalidValue {
+                text: text.to_string(),
+                type_name: "String",
+            })
+        }
+    }
+
+    /// converts an unquoted constant to a `BigInt`.
+    #[cfg(feature = "typed-operands")]
+    pub fn as_bigint(&self) -> Result<BigInt, OperandConversionError> {
+        self.parse_number("BigInt")
+    }
+
+    /// converts an unquoted constant to a `BigDecimal`.
+    #[cfg(feature = "typed-operands")]
+    pub fn as_bigdecimal(&self) -> Result<BigDecimal, OperandConversionError> {
+        self.parse_number("BigDecimal")
+    }
+
+    /// converts a constant, quoted or not, to a `Uuid`.
+    #[cfg(feature = "typed-operands")]
+    pub fn as_uuid(&self) -> Result<Uuid, OperandConversionError> {
+        self.parse_const("Uuid")
+    }
+
+    /// converts a constant, quoted or not, to an `IpAddr`.
+    pub fn as_ipaddr(&self) -> Result<IpAddr, OperandConversionError> {
+        self.parse_const("IpAddr")
+    }
+
+    /// converts a `0x` hex constant to `Bytes`.
+    #[cfg(feature = "typed-operands")]
+    pub fn as_bytes(&self) -> Result<Bytes, OperandConversionError> {
+        let text = self.const_text()?;
+        let invalid = || OperandConversionError::InvalidValue {
+            text: text.to_string(),
+            type_name: "Bytes",
+        };
+        if !text.to_lowercase().starts_with("0x") {
+            return Err(invalid());
+        }
+        hex::decode(&text[2..])
+            .map(Bytes::from)
+            .map_err(|_| invalid())
+    }
+
+    /// converts an unquoted constant to an `i64`, `None` if the operand is not an integer
+    /// constant.
+    pub fn try_as_i64(&self) -> Option<i64> {
+        self.as_i64().ok()
+    }
+
+    /// converts a constant to an `f64`, `None` if the operand is not a numeric constant.
+    pub fn try_as_f64(&self) -> Option<f64> {
+        self.as_f64().ok()
+    }
+
+    /// converts a constant to a `bool`, `None` if the operand is not `true` or `false`.
+    pub fn try_as_bool(&self) -> Option<bool> {
+        self.as_bool().ok()
+    }
+
+    /// converts a quoted constant to the unescaped `String`, `None` if the operand is not a
+    /// quoted constant.
+    pub fn try_as_str(&self) -> Option<String> {
+        self.as_string().ok()
+    }
+
+    /// converts a constant to a `Uuid`, `None` if the operand is not a UUID constant.
+    #[cfg(feature = "typed-operands")]
+    pub fn try_as_uuid(&self) -> Option<Uuid> {
+        self.as_uuid().ok()
+    }
+
     /// creates an Operand::Const from an unquoted string.
     /// if the string contains a "'" it will be quoted by the "$$" pattern.  if it contains "$$" and "'"
     /// it will be quoted by the "'" pattern and all existing "'" will be replaced with "''"
@@ -337,15 +921,112 @@ impl Operand {
             Operand::Const(txt.to_string())
         }
     }
+
+    /// puts the operand into a deterministic form so that equal maps and sets compare equal.
+    /// Map entries are sorted by key and set elements are sorted, tuples are canonicalized
+    /// element by element.  List order is significant and is not changed.  The derived `Ord`
+    /// still compares the structure as written.
+    pub fn canonicalize(&mut self) {
+        match self {
+            Operand::Map(entries) => entries.sort_by(|a, b| a.0.cmp(&b.0)),
+            Operand::Set(values) => values.sort(),
+            Operand::Tuple(operands) | Operand::Collection(operands) => {
+                operands.iter_mut().for_each(Operand::canonicalize)
+            }
+            _ => {}
+        }
+    }
+
+    /// returns the lexical kind of a constant based on the shape of its text.  Returns `None`
+    /// for operands that are not constants and for constants of any other shape (e.g. UUIDs
+    /// and durations).
+    pub fn const_kind(&self) -> Option<ConstKind> {
+        let text = match self {
+            Operand::Const(text) => text.as_str(),
+            _ => return None,
+        };
+        if text.starts_with('\'') {
+            return Some(ConstKind::QuotedString);
+        }
+        if text.starts_with("$$") {
+            return Some(ConstKind::DollarString);
+        }
+        if text.len() > 2
+            && text[..2].eq_ignore_ascii_case("0x")
+            && text[2..].chars().all(|c| c.is_ascii_hexdigit())
+        {
+            return Some(ConstKind::Hex);
+        }
+        if text.eq_ignore_ascii_case("true") || text.eq_ignore_ascii_case("false") {
+            return Some(ConstKind::Boolean);
+        }
+        let unsigned = text.strip_prefix('-').unwrap_or(text);
+        if unsigned.eq_ignore_ascii_case("NaN") || unsigned.eq_ignore_ascii_case("Infinity") {
+            return Some(ConstKind::Float);
+        }
+        if unsigned.is_empty() || !unsigned.starts_with(|c: char| c.is_ascii_digit()) {
+            return None;
+        }
+        if unsigned.chars().all(|c| c.is_ascii_digit()) {
+            Some(ConstKind::Integer)
+        } else if unsigned.parse::<f64>().is_ok() {
+            Some(ConstKind::Float)
+        } else {
+            None
+        }
+    }
+}
+
+/// the lexical kind of an `Operand::Const`.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum ConstKind {
+    /// an integer, e.g. `55` or `-3`
+    Integer,
+    /// a floating point number, e.g. `3.5`, `1e10` or `NaN`
+    Float,
+    /// a string delimited by `'`
+    QuotedString,
+    /// a string delimited by `$$`
+    DollarString,
+    /// a `0x` hex blob
+    Hex,
+    /// `true` or `false`
+    Boolean,
+}
+
+/// the error returned when an `Operand` can not be converted to a Rust type.
+#[derive(PartialEq, Debug, Clone)]
+pub enum OperandConversionError {
+    /// the operand is not a constant.  Contains the display text of the operand.
+    NotConst(String),
+    /// the constant text can not be parsed as the type.
+    InvalidValue {
+        text: String,
+        type_name: &'static str,
+    },
+}
+
+impl Display for OperandConversionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OperandConversionError::NotConst(operand) => {
+                write!(f, "{} is not a constant", operand)
+            }
+            OperandConversionError::InvalidValue { text, type_name } => {
+                write!(f, "{} is not a valid {}", text, type_name)
+            }
+        }
+    }
 }
 
+impl std::error::Error for OperandConversionError {}
+
 impl Display for Operand {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            Operand::Column(text)
-            | Operand::Func(text)
-            | Operand::Const(text)
-            | Operand::Param(text) => {
+            Operand::Column(name) => write!(f, "{}", name),
+            Operand::IndexedColumn(column) => write!(f, "{}", column),
+            Operand::Func(text) | Operand::Const(text) | Operand::Param(text) => {
                 write!(f, "{}", text)
             }
             Operand::Map(entries) => {
@@ -409,6 +1090,56 @@ pub enum PrivilegeType {
     Select,
 }
 
+impl PrivilegeType {
+    /// all the privilege types in display order.
+    pub const fn all_variants() -> &'static [PrivilegeType] {
+        &[
+            PrivilegeType::All,
+            PrivilegeType::Alter,
+            PrivilegeType::Authorize,
+            PrivilegeType::Describe,
+            PrivilegeType::Execute,
+            PrivilegeType::Create,
+            PrivilegeType::Drop,
+            PrivilegeType::Modify,
+            PrivilegeType::Select,
+        ]
+    }
+}
+
+/// the error returned when a string does not name a known privilege.
+#[derive(PartialEq, Debug, Clone)]
+pub struct UnknownPrivilege(pub String);
+
+impl Display for UnknownPrivilege {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown privilege: '{}'", self.0)
+    }
+}
+
+impl std::error::Error for UnknownPrivilege {}
+
+/// parses the privilege name ignoring case.  Both `ALL` and `ALL PERMISSIONS` yield
+/// `PrivilegeType::All`.
+impl FromStr for PrivilegeType {
+    type Err = UnknownPrivilege;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_whitespace().join(" ").to_uppercase().as_str() {
+            "ALL" | "ALL PERMISSIONS" => Ok(PrivilegeType::All),
+            "ALTER" => Ok(PrivilegeType::Alter),
+            "AUTHORIZE" => Ok(PrivilegeType::Authorize),
+            "DESCRIBE" => Ok(PrivilegeType::Describe),
+            "EXECUTE" => Ok(PrivilegeType::Execute),
+            "CREATE" => Ok(PrivilegeType::Create),
+            "DROP" => Ok(PrivilegeType::Drop),
+            "MODIFY" => Ok(PrivilegeType::Modify),
+            "SELECT" => Ok(PrivilegeType::Select),
+            _ => Err(UnknownPrivilege(s.to_string())),
+        }
+    }
+}
+
 impl Display for PrivilegeType {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -441,7 +1172,84 @@ impl Display for RelationElement {
     }
 }
 
+impl RelationElement {
+    /// creates `col = value`
+    pub const fn from_column_eq(col: Identifier, value: Operand) -> RelationElement {
+        RelationElement::from_column(col, RelationOperator::Equal, value)
+    }
+
+    /// creates `col < value`
+    pub const fn from_column_lt(col: Identifier, value: Operand) -> RelationElement {
+        RelationElement::from_column(col, RelationOperator::LessThan, value)
+    }
+
+    /// creates `col > value`
+    pub const fn from_column_gt(col: Identifier, value: Operand) -> RelationElement {
+        RelationElement::from_column(col, RelationOperator::GreaterThan, value)
+    }
+
+    /// creates `col IN (value, value, ...)`
+    pub const fn from_column_in(col: Identifier, values: Vec<Operand>) -> RelationElement {
+        RelationElement::from_column(col, RelationOperator::In, Operand::Tuple(values))
+    }
+
+    /// creates `col CONTAINS value`
+    pub const fn from_column_contains(col: Identifier, value: Operand) -> RelationElement {
+        RelationElement::from_column(col, RelationOperator::Contains, value)
+    }
+
+    /// returns the logical inverse of the relation, e.g. `col >= value` for `col < value`.
+    /// Returns `None` if the operator has no inverse, see `RelationOperator::negate()`.
+    pub fn negate(&self) -> Option<RelationElement> {
+        Some(RelationElement {
+            obj: self.obj.clone(),
+            oper: self.oper.negate()?,
+            value: self.value.clone(),
+        })
+    }
+
+    const fn from_column(
+        col: Identifier,
+        oper: RelationOperator,
+        value: Operand,
+    ) -> RelationElement {
+        RelationElement {
+            obj: Operand::Column(col),
+            oper,
+            value,
+        }
+    }
+}
+
 impl RelationOperator {
+    /// returns true for the range operators `<`, `<=`, `>` and `>=`.
+    pub fn is_range(&self) -> bool {
+        matches!(
+            self,
+            RelationOperator::LessThan
+                | RelationOperator::LessThanOrEqual
+                | RelationOperator::GreaterThan
+                | RelationOperator::GreaterThanOrEqual
+        )
+    }
+
+    /// returns the operator that selects the values this operator rejects.  `IN`,
+    /// `CONTAINS`, `CONTAINS KEY` and `IS NOT` have no inverse in CQL and return `None`.
+    pub fn negate(&self) -> Option<RelationOperator> {
+        match self {
+            RelationOperator::LessThan => Some(RelationOperator::GreaterThanOrEqual),
+            RelationOperator::LessThanOrEqual => Some(RelationOperator::GreaterThan),
+            RelationOperator::Equal => Some(RelationOperator::NotEqual),
+            RelationOperator::NotEqual => Some(RelationOperator::Equal),
+            RelationOperator::GreaterThanOrEqual => Some(RelationOperator::LessThan),
+            RelationOperator::GreaterThan => Some(RelationOperator::LessThanOrEqual),
+            RelationOperator::In
+            | RelationOperator::Contains
+            | RelationOperator::ContainsKey
+            | RelationOperator::IsNot => None,
+        }
+    }
+
     /// evaluates the expression for any PartialOrd implementation
     pub fn eval<T>(&self, left: &T, right: &T) -> bool
     where
@@ -499,29 +1307,19 @@ impl Display for RelationOperator {
 /// the structure of the TTL / Timestamp option.
 #[derive(PartialEq, Debug, Clone)]
 pub struct TtlTimestamp {
-    /// the optional time-to-live value
-    pub ttl: Option<u64>,
-    /// the optional timestamp value
-    pub timestamp: Option<u64>,
+    /// the optional time-to-live value, either a literal or a bind marker
+    pub ttl: Option<Operand>,
+    /// the optional timestamp value, either a literal (which may be negative) or a bind marker
+    pub timestamp: Option<Operand>,
 }
 
 impl Display for TtlTimestamp {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let tl = match self.ttl {
-            Some(t) => format!("TTL {}", t),
-            _ => "".to_string(),
-        };
-
-        let tm = match self.timestamp {
-            Some(t) => format!("TIMESTAMP {}", t),
-            _ => "".to_string(),
-        };
-
-        if self.ttl.is_some() && self.timestamp.is_some() {
-            write!(f, " USING {} AND {}", tl, tm)
-        } else {
-            write!(f, " USING {}", if self.ttl.is_some() { tl } else { tm })
-        }
+        let items = [
+            self.ttl.as_ref().map(|t| format!("TTL {}", t)),
+            self.timestamp.as_ref().map(|t| format!("TIMESTAMP {}", t)),
+        ];
+        write!(f, " USING {}", items.iter().flatten().join(" AND "))
     }
 }
 
@@ -531,7 +1329,7 @@ pub enum WithItem {
     /// an option comprising the key (name) and the value for the option.
     Option { key: String, value: OptionValue },
     /// A clustering order clause.
-    ClusterOrder(OrderClause),
+    ClusterOrder(Vec<OrderClause>),
     /// the ID the ID for the table/view.
     ID(String),
     /// use compact storage.
@@ -542,13 +1340,64 @@ impl Display for WithItem {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             WithItem::Option { key, value } => write!(f, "{} = {}", key, value),
-            WithItem::ClusterOrder(order) => write!(f, "CLUSTERING ORDER BY ({})", order),
+            WithItem::ClusterOrder(order) => {
+                write!(f, "CLUSTERING ORDER BY ({})", order.iter().join(", "))
+            }
             WithItem::ID(txt) => write!(f, "ID = {}", txt),
             WithItem::CompactStorage => write!(f, "COMPACT STORAGE"),
         }
     }
 }
 
+/// the items of a `WITH` clause.  Displays as `WITH item1 AND item2`, or as nothing when
+/// there are no items.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct WithClause(pub Vec<WithItem>);
+
+impl WithClause {
+    /// returns true if there are no items.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// returns an iterator over the items.
+    pub fn iter(&self) -> std::slice::Iter<'_, WithItem> {
+        self.0.iter()
+    }
+
+    /// adds an item to the end of the clause.
+    pub fn push(&mut self, item: WithItem) {
+        self.0.push(item)
+    }
+
+    /// returns the items as a slice.
+    pub fn as_slice(&self) -> &[WithItem] {
+        &self.0
+    }
+}
+
+impl Display for WithClause {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            write!(f, "WITH {}", self.0.iter().join(" AND "))
+        }
+    }
+}
+
+impl From<Vec<WithItem>> for WithClause {
+    fn from(items: Vec<WithItem>) -> Self {
+        WithClause(items)
+    }
+}
+
+impl From<WithClause> for Vec<WithItem> {
+    fn from(clause: WithClause) -> Self {
+        clause.0
+    }
+}
+
 /// the order clause
 #[derive(PartialEq, Debug, Clone)]
 pub struct OrderClause {
@@ -558,9 +1407,40 @@ pub struct OrderClause {
     pub desc: bool,
 }
 
-impl Display for OrderClause {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(
+impl OrderClause {
+    /// creates an ascending order clause for the column.
+    pub fn from_column(name: &str) -> OrderClause {
+        OrderClause {
+            name: name.to_string(),
+            desc: false,
+        }
+    }
+
+    /// creates a descending order clause for the column.
+    pub fn desc(name: &str) -> OrderClause {
+        OrderClause {
+            name: name.to_string(),
+            desc: true,
+        }
+    }
+
+    /// returns a copy of the clause with the direction reversed.
+    pub fn toggle_direction(&self) -> OrderClause {
+        OrderClause {
+            name: self.name.clone(),
+            desc: !self.desc,
+        }
+    }
+
+    /// returns the clause as it appears in a statement, e.g. `name DESC`.
+    pub fn to_cql_fragment(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl Display for OrderClause {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
             f,
             "{} {}",
             self.name,
@@ -576,6 +1456,135 @@ pub enum OptionValue {
     Map(Vec<(String, String)>),
 }
 
+impl OptionValue {
+    /// returns the value for the map key.  Keys match case-insensitively and without regard to
+    /// enclosing single quotes, the value is returned without enclosing single quotes.  Returns
+    /// `None` if the key is not present or the option is a literal.
+    pub fn as_map_entry(&self, key: &str) -> Option<&str> {
+        match self {
+            OptionValue::Map(items) => items
+                .iter()
+                .find(|(k, _)| OptionValue::key_matches(k, key))
+                .map(|(_, v)| OptionValue::unquote(v)),
+            OptionValue::Literal(_) => None,
+        }
+    }
+
+    /// returns the text of a literal option.
+    pub fn as_literal(&self) -> Option<&str> {
+        match self {
+            OptionValue::Literal(txt) => Some(txt),
+            OptionValue::Map(_) => None,
+        }
+    }
+
+    /// returns the value of a numeric literal option, e.g. `gc_grace_seconds = 864000`.
+    /// Returns `None` for maps and for literals that are not numbers, including quoted ones.
+    pub fn as_number(&self) -> Option<f64> {
+        self.as_literal()
+            .filter(|txt| !txt.starts_with('\''))
+            .and_then(|txt| txt.parse().ok())
+    }
+
+    /// returns the unescaped value of a quoted string literal option, e.g. `comment = 'x'`.
+    /// Returns `None` for maps and unquoted literals.
+    pub fn as_string(&self) -> Option<String> {
+        self.as_literal()
+            .filter(|txt| txt.len() > 1 && txt.starts_with('\'') && txt.ends_with('\''))
+            .map(Operand::unescape)
+    }
+
+    /// returns the value of a boolean literal option, ignoring case.  The value may be quoted
+    /// as some options (e.g. `cdc`) are commonly written as `'true'`.  Returns `None` for maps
+    /// and other literals.
+    pub fn as_bool(&self) -> Option<bool> {
+        let txt = OptionValue::unquote(self.as_literal()?);
+        if txt.eq_ignore_ascii_case("true") {
+            Some(true)
+        } else if txt.eq_ignore_ascii_case("false") {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    /// sets the value for the map key, replacing any entry with a matching key.  The key and
+    /// value are the CQL text so strings must be quoted.  A literal option is replaced by a map
+    /// containing only the entry.
+    pub fn insert_map_entry(&mut self, key: String, value: String) {
+        match self {
+            OptionValue::Map(items) => {
+                match items
+                    .iter_mut()
+                    .find(|(k, _)| OptionValue::key_matches(k, &key))
+                {
+                    Some(entry) => *entry = (key, value),
+                    None => items.push((key, value)),
+                }
+            }
+            OptionValue::Literal(_) => *self = OptionValue::Map(vec![(key, value)]),
+        }
+    }
+
+    /// removes the entry for the map key.  Returns true if an entry was removed.
+    pub fn remove_map_entry(&mut self, key: &str) -> bool {
+        match self {
+            OptionValue::Map(items) => {
+                let len = items.len();
+                items.retain(|(k, _)| !OptionValue::key_matches(k, key));
+                items.len() != len
+            }
+            OptionValue::Literal(_) => false,
+        }
+    }
+
+    /// merges the other value into this one.  For two maps the entries of `other` with keys
+    /// missing from this map are added; a key present in both with different values is a
+    /// conflict.  Two literals merge if they are equal.  On error this value is unchanged.
+    pub fn merge(&mut self, other: &OptionValue) -> Result<(), MergeError> {
+        match (self, other) {
+            (OptionValue::Map(items), OptionValue::Map(other_items)) => {
+                let mut added = vec![];
+                for (key, value) in other_items {
+                    match items.iter().find(|(k, _)| OptionValue::key_matches(k, key)) {
+                        Some((_, existing)) if existing != value => {
+                            return Err(MergeError::Conflict {
+                                key: OptionValue::unquote(key).to_string(),
+                            })
+                        }
+                        Some(_) => {}
+                        None => added.push((key.clone(), value.clone())),
+                    }
+                }
+                items.extend(added);
+                Ok(())
+            }
+            (OptionValue::Literal(txt), OptionValue::Literal(other_txt)) => {
+                if txt == other_txt {
+                    Ok(())
+                } else {
+                    Err(MergeError::LiteralConflict {
+                        value: txt.clone(),
+                        other: other_txt.clone(),
+                    })
+                }
+            }
+            _ => Err(MergeError::KindMismatch),
+        }
+    }
+
+    fn key_matches(map_key: &str, key: &str) -> bool {
+        OptionValue::unquote(map_key).eq_ignore_ascii_case(OptionValue::unquote(key))
+    }
+
+    /// removes the enclosing single quotes from a map key or value.
+    pub(crate) fn unquote(txt: &str) -> &str {
+        txt.strip_prefix('\'')
+            .and_then(|t| t.strip_suffix('\''))
+            .unwrap_or(txt)
+    }
+}
+
 impl Display for OptionValue {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -589,12 +1598,75 @@ impl Display for OptionValue {
     }
 }
 
+/// the errors returned by `OptionValue::merge()`.
+#[derive(PartialEq, Debug, Clone)]
+pub enum MergeError {
+    /// the key is in both maps with different values.  Contains the key without quotes.
+    Conflict { key: String },
+    /// the literals differ.
+    LiteralConflict { value: String, other: String },
+    /// a map can not be merged with a literal.
+    KindMismatch,
+}
+
+impl Display for MergeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MergeError::Conflict { key } => write!(f, "conflicting values for {}", key),
+            MergeError::LiteralConflict { value, other } => {
+                write!(f, "conflicting values {} and {}", value, other)
+            }
+            MergeError::KindMismatch => write!(f, "a map can not be merged with a literal"),
+        }
+    }
+}
+
+impl std::error::Error for MergeError {}
+
 /// The definition of a primary key.
 /// There must be at least one column specified in the partition.
 #[derive(PartialEq, Debug, Clone)]
 pub struct PrimaryKey {
-    pub partition: Vec<String>,
-    pub clustering: Vec<String>,
+    pub partition: Vec<Identifier>,
+    pub clustering: Vec<Identifier>,
+}
+
+impl PrimaryKey {
+    /// true if the column is part of the partition or clustering key.  The comparison is
+    /// case-insensitive.
+    pub fn contains_column(&self, name: &str) -> bool {
+        self.column_index(name).is_some()
+    }
+
+    /// true if the column is part of the partition key.  The comparison is case-insensitive.
+    pub fn is_partition_key(&self, name: &str) -> bool {
+        matches!(self.column_index(name), Some((KeyPart::Partition, _)))
+    }
+
+    /// true if the column is part of the clustering key.  The comparison is case-insensitive.
+    pub fn is_clustering_key(&self, name: &str) -> bool {
+        matches!(self.column_index(name), Some((KeyPart::Clustering, _)))
+    }
+
+    /// returns the part of the key containing the column and the position of the column
+    /// within that part.  The comparison is case-insensitive.
+    pub fn column_index(&self, name: &str) -> Option<(KeyPart, usize)> {
+        let find = |columns: &[Identifier]| {
+            columns
+                .iter()
+                .position(|column| column.as_str().eq_ignore_ascii_case(name))
+        };
+        find(&self.partition)
+            .map(|idx| (KeyPart::Partition, idx))
+            .or_else(|| find(&self.clustering).map(|idx| (KeyPart::Clustering, idx)))
+    }
+}
+
+/// the parts of a primary key.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum KeyPart {
+    Partition,
+    Clustering,
 }
 
 impl Display for PrimaryKey {
@@ -609,15 +1681,17 @@ impl Display for PrimaryKey {
                     f,
                     "PRIMARY KEY ({}, {})",
                     self.partition[0],
-                    self.clustering.join(", ")
+                    self.clustering.iter().join(", ")
                 )
             }
+        } else if self.clustering.is_empty() {
+            write!(f, "PRIMARY KEY (({}))", self.partition.iter().join(", "))
         } else {
             write!(
                 f,
                 "PRIMARY KEY (({}), {})",
-                self.partition.join(", "),
-                self.clustering.join(", ")
+                self.partition.iter().join(", "),
+                self.clustering.iter().join(", ")
             )
         }
     }
@@ -664,7 +1738,8 @@ impl Display for Resource {
 
 pub struct WhereClause {}
 impl WhereClause {
-    /// return a map of column names to relation elements
+    /// return a map of column names to relation elements.  `CONTAINS` and `CONTAINS KEY`
+    /// relations are grouped under their collection column.
     pub fn get_column_relation_element_map(
         where_clause: &[RelationElement],
     ) -> BTreeMap<String, Vec<RelationElement>> {
@@ -672,10 +1747,11 @@ impl WhereClause {
 
         for relation_element in where_clause {
             if let Operand::Column(key) = &relation_element.obj {
-                if let Some(value) = result.get_mut(key) {
+                let key = key.to_string();
+                if let Some(value) = result.get_mut(&key) {
                     value.push(relation_element.clone());
                 } else {
-                    result.insert(key.clone(), vec![relation_element.clone()]);
+                    result.insert(key, vec![relation_element.clone()]);
                 }
             }
         }
@@ -688,11 +1764,176 @@ impl WhereClause {
         where_clause
             .into_iter()
             .filter_map(|relation_element| match relation_element.obj {
-                Operand::Column(name) => Some(name),
+                Operand::Column(name) => Some(name.to_string()),
                 _ => None,
             })
             .collect()
     }
+
+    /// returns the relations that use the operator.
+    pub fn filter_by_operator(
+        elements: &[RelationElement],
+        op: RelationOperator,
+    ) -> Vec<&RelationElement> {
+        elements.iter().filter(|e| e.oper == op).collect()
+    }
+
+    /// returns the `=` relations.
+    pub fn equalities(elements: &[RelationElement]) -> Vec<&RelationElement> {
+        WhereClause::filter_by_operator(elements, RelationOperator::Equal)
+    }
+
+    /// returns the `<`, `<=`, `>` and `>=` relations.
+    pub fn ranges(elements: &[RelationElement]) -> Vec<&RelationElement> {
+        elements.iter().filter(|e| e.oper.is_range()).collect()
+    }
+
+    /// reports which primary key columns the relations restrict and whether the clustering
+    /// restrictions form a valid prefix.  A partition key column is only restricted by an `=`
+    /// or `IN` relation as the server can not route a range on it.  A clustering column is
+    /// restricted by any relation on it, including the columns of a multi-column relation
+    /// such as `(c1, c2) > (1, 2)`.  The
+    /// columns of a multi-column relation are a single restriction so a range on the tuple
+    /// does not end the prefix for the later columns of the tuple.  Column names match
+    /// case-insensitively.
+    pub fn restricts_primary_key(elements: &[RelationElement], pk: &PrimaryKey) -> KeyCoverage {
+        // the index and operator of the relations on each key column, `None` if the column is
+        // not restricted.
+        let operators = |column: &Identifier| -> Option<Vec<(usize, &RelationOperator)>> {
+            let is_column = |operand: &Operand| matches!(operand, Operand::Column(name) if name.as_str().eq_ignore_ascii_case(column.as_str()));
+            let opers: Vec<(usize, &RelationOperator)> = elements
+                .iter()
+                .enumerate()
+                .filter(|(_, e)| match &e.obj {
+                    Operand::Tuple(names) => names.iter().any(is_column),
+                    obj => is_column(obj),
+                })
+                .map(|(idx, e)| (idx, &e.oper))
+                .collect();
+            if opers.is_empty() {
+                None
+            } else {
+                Some(opers)
+            }
+        };
+
+        let mut result = KeyCoverage::default();
+        for column in &pk.partition {
+            let is_eq = operators(column).map_or(false, |opers| {
+                opers
+                    .iter()
+                    .any(|(_, oper)| matches!(oper, RelationOperator::Equal | RelationOperator::In))
+            });
+            if is_eq {
+                result.partition_restricted.push(column.clone());
+            } else {
+                result.partition_unrestricted.push(column.clone());
+            }
+        }
+        // the preceding clustering column that ended the valid prefix, whether it was restricted
+        // and the indices of the relations on it.
+        let mut previous: Option<(&Identifier, bool, Vec<usize>)> = None;
+        for column in &pk.clustering {
+            match operators(column) {
+                Some(opers) => {
+                    result.clustering_restricted.push(column.clone());
+                    match &previous {
+                        // the column is restricted by the same multi-column relations
+                        Some((_, true, relations))
+                            if opers.iter().all(|(idx, _)| relations.contains(idx)) => {}
+                        Some((prev, true, _)) => result
+                            .clustering_after_non_eq
+                            .push((column.clone(), (*prev).clone())),
+                        Some((prev, false, _)) => result
+                            .clustering_gaps
+                            .push((column.clone(), (*prev).clone())),
+                        None => {
+                            if !opers.iter().all(|(_, oper)| {
+                                matches!(oper, RelationOperator::Equal | RelationOperator::In)
+                            }) {
+                                previous = Some((
+                                    column,
+                                    true,
+                                    opers.iter().map(|(idx, _)| *idx).collect(),
+                                ));
+                            }
+                        }
+                    }
+                }
+                None => {
+                    if previous.is_none() {
+                        previous = Some((column, false, vec![]));
+                    }
+                }
+            }
+        }
+        result
+    }
+}
+
+/// the primary key columns restricted by a `WHERE` clause, see
+/// `WhereClause::restricts_primary_key()`.  Columns are listed in key order.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct KeyCoverage {
+    /// the partition key columns that are restricted by `=` or `IN`.
+    pub partition_restricted: Vec<Identifier>,
+    /// the partition key columns that are not restricted by `=` or `IN`.
+    pub partition_unrestricted: Vec<Identifier>,
+    /// the clustering columns that are restricted.
+    pub clustering_restricted: Vec<Identifier>,
+    /// the restricted clustering columns following an unrestricted one, each with the first
+    /// unrestricted clustering column.
+    pub clustering_gaps: Vec<(Identifier, Identifier)>,
+    /// the restricted clustering columns following one restricted by an operator other than
+    /// `=` or `IN`, each with that column.
+    pub clustering_after_non_eq: Vec<(Identifier, Identifier)>,
+}
+
+impl KeyCoverage {
+    /// true if every partition key column is restricted by `=` or `IN`.
+    pub fn is_partition_complete(&self) -> bool {
+        self.partition_unrestricted.is_empty()
+    }
+
+    /// true if the restricted clustering columns are a prefix of the clustering key and only
+    /// the last of them is restricted by an operator other than `=` or `IN`.
+    pub fn is_clustering_prefix(&self) -> bool {
+        self.clustering_gaps.is_empty() && self.clustering_after_non_eq.is_empty()
+    }
+
+    /// true if the server accepts the restrictions without `ALLOW FILTERING`.  Clustering
+    /// columns may only be restricted when the whole partition key is.
+    pub fn is_valid(&self) -> bool {
+        self.is_clustering_prefix()
+            && (self.is_partition_complete() || self.clustering_restricted.is_empty())
+    }
+
+    /// describes why the restrictions are not valid, empty if they are.
+    pub fn problems(&self) -> Vec<String> {
+        let mut result: Vec<String> = self
+            .clustering_gaps
+            .iter()
+            .map(|(column, missing)| {
+                format!(
+                    "clustering column {} can not be restricted as preceding column {} is not restricted",
+                    column, missing
+                )
+            })
+            .collect();
+        result.extend(self.clustering_after_non_eq.iter().map(|(column, previous)| {
+            format!(
+                "clustering column {} can not be restricted as preceding column {} is restricted by a non-EQ relation",
+                column, previous
+            )
+        }));
+        if !self.is_partition_complete() && !self.clustering_restricted.is_empty() {
+            result.push(format!(
+                "clustering columns can not be restricted as partition key columns {} are not restricted by EQ or IN relations",
+                self.partition_unrestricted.iter().join(", ")
+            ));
+        }
+        result
+    }
 }
 
 #[derive(PartialEq, Debug, Clone, Hash, Eq, Deserialize)]
@@ -702,17 +1943,22 @@ pub struct FQName {
 }
 
 impl FQName {
+    /// creates the name of an object without a keyspace.  The name is case sensitive, as
+    /// for `Identifier::new()`, so it is quoted if needed (see `quote_identifier()`).  Use
+    /// `FQName::from()` to create the name from statement text.
     pub fn simple(name: &str) -> FQName {
         FQName {
             keyspace: None,
-            name: name.into(),
+            name: quote_identifier(name),
         }
     }
 
+    /// creates the name of an object in the keyspace.  The names are case sensitive, so
+    /// `FQName::new("ks", "myTable")` displays as `ks."myTable"`.
     pub fn new(keyspace: &str, name: &str) -> FQName {
         FQName {
-            keyspace: Some(keyspace.into()),
-            name: name.into(),
+            keyspace: Some(quote_identifier(keyspace)),
+            name: quote_identifier(name),
         }
     }
 
@@ -724,18 +1970,169 @@ impl FQName {
             default
         }
     }
+
+    /// returns true if the names refer to the same object.  Unquoted names are compared
+    /// without regard to case, quoted names are case sensitive.  A name without a keyspace
+    /// only matches another name without a keyspace.
+    pub fn eq_ignoring_case(&self, other: &FQName) -> bool {
+        self.keyspace.as_deref().map(normalize_name)
+            == other.keyspace.as_deref().map(normalize_name)
+            && normalize_name(&self.name) == normalize_name(&other.name)
+    }
+}
+
+/// returns the name as the server stores it: unquoted names are lower case, quoted names
+/// have the quotes removed.
+pub(crate) fn normalize_name(name: &str) -> String {
+    let identifier = Identifier::parse(name);
+    if identifier.quoted {
+        identifier.name
+    } else {
+        identifier.name.to_lowercase()
+    }
+}
+
+impl From<&str> for FQName {
+    /// creates the name from the statement text `name` or `keyspace.name`.  The names are
+    /// held as written so the case of unquoted names is not significant.
+    fn from(text: &str) -> Self {
+        match text.split_once('.') {
+            Some((keyspace, name)) => FQName {
+                keyspace: Some(keyspace.to_string()),
+                name: name.to_string(),
+            },
+            None => FQName {
+                keyspace: None,
+                name: text.to_string(),
+            },
+        }
+    }
 }
 
+/// the names are held as written in the statement, where the case of unquoted names is not
+/// significant, so only the names that need quotes (see `Identifier::from_cql()`) are quoted.
+/// `FQName::new()` and `FQName::simple()` hold case sensitive names already quoted.
 impl Display for FQName {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let quote = |name: &str| {
+            if needs_quotes(name) {
+                format!("\"{}\"", name.replace('"', "\"\""))
+            } else {
+                name.to_string()
+            }
+        };
         if let Some(keyspace) = &self.keyspace {
-            write!(f, "{}.{}", keyspace, self.name)
+            write!(f, "{}.{}", quote(keyspace), quote(&self.name))
+        } else {
+            write!(f, "{}", quote(&self.name))
+        }
+    }
+}
+
+/// returns the identifier wrapped in double quotes, with inner quotes doubled, if it contains
+/// characters that are not valid in a bare identifier, including upper case characters, or
+/// is a reserved keyword.  Identifiers that are already quoted are returned unchanged.
+pub fn quote_identifier(name: &str) -> String {
+    if name.is_empty() || name.starts_with('"') || name.starts_with('\'') || !must_quote(name) {
+        name.to_string()
+    } else {
+        format!("\"{}\"", name.replace('"', "\"\""))
+    }
+}
+
+/// true if the name can be written without quotes.  Unquoted names are folded to lower case
+/// so a name containing upper case characters must be quoted.
+fn is_bare(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().map_or(false, |c| c.is_ascii_lowercase())
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// true if the case sensitive name must be enclosed in double quotes: it is not a bare
+/// identifier or it is a reserved keyword.
+fn must_quote(name: &str) -> bool {
+    !is_bare(name) || is_reserved_keyword(name)
+}
+
+/// true if the name, as written in a statement, must be enclosed in double quotes: it is not
+/// a valid bare identifier in any case or it is a reserved keyword.  Names that are already
+/// quoted do not need quotes.
+fn needs_quotes(name: &str) -> bool {
+    !(name.is_empty() || name.starts_with('"') || name.starts_with('\''))
+        && (!is_bare(&name.to_lowercase()) || is_reserved_keyword(name))
+}
+
+/// An identifier such as a column name.  Quoted identifiers are case sensitive and keep their
+/// quotes when displayed.
+#[derive(PartialEq, Debug, Clone, Eq, Ord, PartialOrd, Hash)]
+pub struct Identifier {
+    /// the name without the enclosing quotes.
+    pub name: String,
+    /// if true the name is enclosed in double quotes.
+    pub quoted: bool,
+}
+
+impl Identifier {
+    /// creates an identifier for the name.  The identifier is quoted if the name can not be
+    /// written without quotes or is a reserved keyword.
+    pub fn new(name: &str) -> Identifier {
+        Identifier {
+            name: name.to_string(),
+            quoted: must_quote(name),
+        }
+    }
+
+    /// creates an identifier from the statement text.  Text enclosed in double quotes is
+    /// quoted and any `""` within it is unescaped.
+    pub fn parse(text: &str) -> Identifier {
+        if text.len() > 1 && text.starts_with('"') && text.ends_with('"') {
+            Identifier {
+                name: text[1..text.len() - 1].replace("\"\"", "\""),
+                quoted: true,
+            }
+        } else {
+            Identifier {
+                name: text.to_string(),
+                quoted: false,
+            }
+        }
+    }
+
+    /// creates an identifier from the name as it would be written in a statement, the rule
+    /// the builders use for both table and column names.  Text enclosed in double quotes is
+    /// case sensitive, names that need quotes (e.g. `my col` or `order`) are quoted, and the
+    /// case of any other name is not significant, so `MyCol` refers to the column `mycol`.
+    pub fn from_cql(text: &str) -> Identifier {
+        let identifier = Identifier::parse(text);
+        Identifier {
+            quoted: identifier.quoted || needs_quotes(&identifier.name),
+            name: identifier.name,
+        }
+    }
+
+    /// the name without quotes.
+    pub fn as_str(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Display for Identifier {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.quoted {
+            write!(f, "\"{}\"", self.name.replace('"', "\"\""))
         } else {
             write!(f, "{}", self.name)
         }
     }
 }
 
+/// parses the identifier from the statement text, see `Identifier::parse`.
+impl From<&str> for Identifier {
+    fn from(text: &str) -> Self {
+        Identifier::parse(text)
+    }
+}
+
 impl From<&FQName> for std::string::String {
     fn from(fqname: &FQName) -> Self {
         fqname.to_string()
@@ -750,7 +2147,25 @@ impl From<FQName> for std::string::String {
 
 #[cfg(test)]
 mod tests {
-    use crate::common::Operand;
+    use crate::alter_table::AlterError;
+    use crate::cassandra_ast::CassandraAST;
+    use crate::cassandra_statement::CassandraStatement;
+    use crate::common::{
+        quote_identifier, ColumnDefinition, ConstKind, DataType, DataTypeName, DataTypeParseError,
+        FQName, Identifier, KeyPart, MergeError, Operand, OperandConversionError, OptionValue,
+        OrderClause, PrimaryKey, PrivilegeType, RelationElement, RelationOperator, TtlTimestamp,
+        UnknownPrivilege, WhereClause, WithClause, WithItem,
+    };
+    #[cfg(feature = "typed-operands")]
+    use bigdecimal::BigDecimal;
+    #[cfg(feature = "typed-operands")]
+    use bytes::Bytes;
+    #[cfg(feature = "typed-operands")]
+    use num::BigInt;
+    use std::net::IpAddr;
+    use std::str::FromStr;
+    #[cfg(feature = "typed-operands")]
+    use uuid::Uuid;
 
     #[test]
     pub fn test_operand_unescape() {
@@ -795,4 +2210,943 @@ mod tests {
             assert_eq!(Operand::Const(expected.to_string()), Operand::escape(arg));
         }
     }
+
+    #[test]
+    pub fn test_privilege_type_from_str() {
+        assert_eq!(Ok(PrivilegeType::Select), "SELECT".parse::<PrivilegeType>());
+        assert_eq!(Ok(PrivilegeType::Select), "select".parse::<PrivilegeType>());
+        assert_eq!(Ok(PrivilegeType::All), "ALL".parse::<PrivilegeType>());
+        assert_eq!(
+            Ok(PrivilegeType::All),
+            "all  Permissions".parse::<PrivilegeType>()
+        );
+        assert_eq!(
+            Err(UnknownPrivilege("TRUNCATE".to_string())),
+            "TRUNCATE".parse::<PrivilegeType>()
+        );
+        for privilege in PrivilegeType::all_variants() {
+            assert_eq!(
+                Ok(privilege.clone()),
+                privilege.to_string().parse::<PrivilegeType>()
+            );
+        }
+    }
+
+    #[test]
+    pub fn test_data_type_try_parse() {
+        let data_type = DataType::try_parse("FROZEN<MAP<TEXT, LIST<INT>>>").unwrap();
+        assert_eq!(
+            DataType {
+                name: DataTypeName::Frozen,
+                definition: vec![DataType {
+                    name: DataTypeName::Map,
+                    definition: vec![
+                        DataType {
+                            name: DataTypeName::Text,
+                            definition: vec![],
+                        },
+                        DataType {
+                            name: DataTypeName::List,
+                            definition: vec![DataType {
+                                name: DataTypeName::Int,
+                                definition: vec![],
+                            }],
+                        },
+                    ],
+                }],
+            },
+            data_type
+        );
+        assert_eq!("FROZEN<MAP<TEXT, LIST<INT>>>", data_type.to_string());
+
+        assert_eq!(
+            "TUPLE<INT, FROZEN<SET<ks.my_type>>, \"Quoted\">",
+            DataType::try_parse("tuple< int,frozen<set<ks.my_type>>, \"Quoted\" >")
+                .unwrap()
+                .to_string()
+        );
+        assert_eq!(
+            DataTypeName::Custom("address".to_string()),
+            DataType::try_parse("address").unwrap().name
+        );
+
+        assert_eq!(
+            Err(DataTypeParseError::UnexpectedEnd),
+            DataType::try_parse("MAP<TEXT, INT")
+        );
+        assert_eq!(
+            Err(DataTypeParseError::UnexpectedEnd),
+            DataType::try_parse("")
+        );
+        assert_eq!(
+            Err(DataTypeParseError::UnexpectedToken(">".to_string())),
+            DataType::try_parse("LIST<INT>>")
+        );
+        assert_eq!(
+            Err(DataTypeParseError::UnexpectedToken(">".to_string())),
+            DataType::try_parse("LIST<>")
+        );
+    }
+
+    #[test]
+    pub fn test_data_type_compatible_with() {
+        let compatible = |from: &str, to: &str| {
+            DataType::try_parse(from)
+                .unwrap()
+                .compatible_with(&DataType::try_parse(to).unwrap())
+        };
+        assert!(compatible("int", "bigint"));
+        assert!(compatible("tinyint", "varint"));
+        assert!(!compatible("bigint", "int"));
+        assert!(compatible("text", "varchar"));
+        assert!(compatible("varchar", "text"));
+        assert!(compatible("ascii", "text"));
+        assert!(!compatible("text", "ascii"));
+        assert!(compatible("blob", "bytes"));
+        assert!(compatible("bytes", "blob"));
+        assert!(compatible("int", "blob"));
+        assert!(!compatible("blob", "int"));
+        assert!(compatible("bigint", "timestamp"));
+        assert!(compatible("timestamp", "bigint"));
+        assert!(!compatible("timestamp", "int"));
+        assert!(compatible("timeuuid", "uuid"));
+        assert!(!compatible("uuid", "timeuuid"));
+        assert!(compatible("float", "double"));
+        assert!(!compatible("int", "text"));
+        assert!(compatible("counter", "counter"));
+        assert!(!compatible("counter", "bigint"));
+        assert!(!compatible("bigint", "counter"));
+        assert!(compatible("list<int>", "list<bigint>"));
+        assert!(compatible("map<text, int>", "map<varchar, varint>"));
+        assert!(!compatible("map<text, bigint>", "map<text, int>"));
+        assert!(!compatible("list<int>", "set<int>"));
+        assert!(!compatible("list<int>", "blob"));
+        assert!(compatible("frozen<address>", "FROZEN<Address>"));
+        assert!(!compatible("frozen<address>", "address"));
+        assert!(!compatible("address", "location"));
+        assert!(compatible("tuple<int, text>", "tuple<bigint, text, uuid>"));
+        assert!(!compatible("tuple<int, text>", "tuple<int>"));
+    }
+
+    #[test]
+    pub fn test_with_required_frozen() {
+        let frozen = |s: &str| {
+            DataType::try_parse(s)
+                .unwrap()
+                .with_required_frozen()
+                .to_string()
+        };
+        assert_eq!("LIST<FROZEN<SET<INT>>>", frozen("LIST<SET<INT>>"));
+        assert_eq!(
+            "MAP<TEXT, FROZEN<LIST<address>>>",
+            frozen("MAP<TEXT, LIST<address>>")
+        );
+        assert_eq!("SET<FROZEN<address>>", frozen("SET<address>"));
+        assert_eq!("LIST<INT>", frozen("LIST<INT>"));
+        assert_eq!("MAP<TEXT, INT>", frozen("MAP<TEXT, INT>"));
+        assert_eq!("FROZEN<LIST<SET<INT>>>", frozen("FROZEN<LIST<SET<INT>>>"));
+        assert_eq!(
+            "LIST<TUPLE<INT, LIST<INT>>>",
+            frozen("LIST<TUPLE<INT, LIST<INT>>>")
+        );
+    }
+
+    #[test]
+    pub fn test_column_definition_to_alter_add_cql() {
+        let mut column = ColumnDefinition {
+            name: Identifier::new("col_name"),
+            data_type: DataType::try_parse("MAP<TEXT, INT>").unwrap(),
+            primary_key: false,
+            is_static: false,
+        };
+        let table = FQName::new("ks", "t");
+        assert_eq!(
+            Ok("ALTER TABLE ks.t ADD col_name MAP<TEXT, INT>".to_string()),
+            column.to_alter_add_cql(&table, false)
+        );
+        assert_eq!(
+            Ok("ALTER TABLE ks.t ADD IF NOT EXISTS col_name MAP<TEXT, INT>".to_string()),
+            column.to_alter_add_cql(&table, true)
+        );
+        column.is_static = true;
+        assert_eq!(
+            Ok("ALTER TABLE ks.t ADD col_name MAP<TEXT, INT> STATIC".to_string()),
+            column.to_alter_add_cql(&table, false)
+        );
+        column.is_static = false;
+        column.primary_key = true;
+        assert_eq!(
+            Err(AlterError::PrimaryKeyInAlter),
+            column.to_alter_add_cql(&table, false)
+        );
+    }
+
+    #[test]
+    pub fn test_column_definition_fragments() {
+        let mut column = ColumnDefinition {
+            name: Identifier::new("id"),
+            data_type: DataType::try_parse("UUID").unwrap(),
+            primary_key: true,
+            is_static: false,
+        };
+        assert_eq!("id UUID PRIMARY KEY", column.to_string());
+        assert_eq!("id UUID", column.to_create_column_cql());
+        assert_eq!(
+            Err(AlterError::PrimaryKeyInAlter),
+            column.to_alter_add_fragment()
+        );
+        column.primary_key = false;
+        column.is_static = true;
+        assert_eq!(
+            Ok("id UUID STATIC".to_string()),
+            column.to_alter_add_fragment()
+        );
+    }
+
+    #[test]
+    pub fn test_identifier() {
+        let quoted = Identifier::parse("\"Order\"");
+        assert_eq!("Order", quoted.as_str());
+        assert!(quoted.quoted);
+        assert_eq!("\"Order\"", quoted.to_string());
+
+        let embedded = Identifier::parse("\"say \"\"hi\"\"\"");
+        assert_eq!("say \"hi\"", embedded.as_str());
+        assert_eq!("\"say \"\"hi\"\"\"", embedded.to_string());
+
+        let bare = Identifier::parse("col_1");
+        assert!(!bare.quoted);
+        assert_eq!("col_1", bare.to_string());
+
+        assert_eq!(Identifier::parse("\"user-id\""), Identifier::new("user-id"));
+        assert_eq!(Identifier::parse("col"), Identifier::new("col"));
+        assert_eq!("\"order\"", Identifier::new("order").to_string());
+    }
+
+    #[test]
+    pub fn test_quote_identifier() {
+        assert_eq!("col_1", quote_identifier("col_1"));
+        assert_eq!("\"my col\"", quote_identifier("my col"));
+        assert_eq!("\"1col\"", quote_identifier("1col"));
+        assert_eq!("\"a\"\"b\"", quote_identifier("a\"b"));
+        assert_eq!("\"Quoted\"", quote_identifier("\"Quoted\""));
+        assert_eq!("'keyspace'", quote_identifier("'keyspace'"));
+        assert_eq!("\"MyCol\"", quote_identifier("MyCol"));
+        assert_eq!("\"COL\"", quote_identifier("COL"));
+        assert_eq!("\"order\"", quote_identifier("order"));
+        assert_eq!("\"MyCol\"", Identifier::new("MyCol").to_string());
+        assert_eq!("ks.\"myTable\"", FQName::new("ks", "myTable").to_string());
+        assert_eq!("\"MyKs\".t", FQName::new("MyKs", "t").to_string());
+        assert_eq!("ks.\"order\"", FQName::new("ks", "order").to_string());
+        assert!(!FQName::new("ks", "myTable").eq_ignoring_case(&FQName::from("ks.mytable")));
+        // unquoted names in the statement text are not case sensitive
+        assert_eq!("ks.myTable", FQName::from("ks.myTable").to_string());
+        assert!(FQName::from("ks.myTable").eq_ignoring_case(&FQName::new("ks", "mytable")));
+        assert_eq!(FQName::new("ks", "myTable"), FQName::from("ks.\"myTable\""));
+        assert_eq!("MyCol", Identifier::from_cql("MyCol").to_string());
+        assert_eq!("\"MyCol\"", Identifier::from_cql("\"MyCol\"").to_string());
+        assert_eq!("\"my col\"", Identifier::from_cql("my col").to_string());
+        assert_eq!("\"order\"", Identifier::from_cql("order").to_string());
+
+        assert_eq!(
+            "\"my ks\".\"my col\"",
+            FQName::new("my ks", "my col").to_string()
+        );
+        assert_eq!("\"my col\"", FQName::simple("my col").to_string());
+        assert_eq!(FQName::new("ks", "t"), FQName::from("ks.t"));
+        assert_eq!(FQName::simple("t"), FQName::from("t"));
+        assert!(FQName::from("KS.Tbl").eq_ignoring_case(&FQName::from("ks.tbl")));
+        assert!(FQName::from("ks.\"tbl\"").eq_ignoring_case(&FQName::from("ks.TBL")));
+        assert!(!FQName::from("ks.\"Tbl\"").eq_ignoring_case(&FQName::from("ks.Tbl")));
+        assert!(!FQName::from("tbl").eq_ignoring_case(&FQName::from("ks.tbl")));
+        assert_eq!(
+            "\"my col\" TEXT",
+            ColumnDefinition {
+                name: Identifier::new("my col"),
+                data_type: DataType::try_parse("TEXT").unwrap(),
+                primary_key: false,
+                is_static: false,
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    pub fn test_option_value_map() {
+        let mut option = OptionValue::Map(vec![
+            (
+                "'class'".to_string(),
+                "'SizeTieredCompactionStrategy'".to_string(),
+            ),
+            ("'max_threshold'".to_string(), "32".to_string()),
+        ]);
+        assert_eq!(
+            Some("SizeTieredCompactionStrategy"),
+            option.as_map_entry("CLASS")
+        );
+        assert_eq!(Some("32"), option.as_map_entry("'max_threshold'"));
+        assert_eq!(None, option.as_map_entry("min_threshold"));
+        assert_eq!(None, option.as_literal());
+
+        option.insert_map_entry("'MAX_THRESHOLD'".to_string(), "64".to_string());
+        option.insert_map_entry("'min_threshold'".to_string(), "4".to_string());
+        assert_eq!(
+            "{'class':'SizeTieredCompactionStrategy', 'MAX_THRESHOLD':64, 'min_threshold':4}",
+            option.to_string()
+        );
+        assert!(option.remove_map_entry("max_threshold"));
+        assert!(!option.remove_map_entry("max_threshold"));
+        assert_eq!(None, option.as_map_entry("max_threshold"));
+
+        let literal = OptionValue::Literal("'comment'".to_string());
+        assert_eq!(Some("'comment'"), literal.as_literal());
+        assert_eq!(None, literal.as_map_entry("comment"));
+        assert_eq!(None, option.as_number());
+        assert_eq!(None, option.as_string());
+        assert_eq!(None, option.as_bool());
+        assert_eq!(Some("comment".to_string()), literal.as_string());
+        assert_eq!(
+            Some(32.0),
+            OptionValue::Literal("32".to_string()).as_number()
+        );
+        assert_eq!(None, OptionValue::Literal("'32'".to_string()).as_number());
+        assert_eq!(
+            Some(false),
+            OptionValue::Literal("'FALSE'".to_string()).as_bool()
+        );
+    }
+
+    #[test]
+    pub fn test_option_value_merge() {
+        let map = |entries: &[(&str, &str)]| {
+            OptionValue::Map(
+                entries
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+            )
+        };
+        let mut option = map(&[("'class'", "'LZ4Compressor'"), ("'enabled'", "'true'")]);
+        option
+            .merge(&map(&[
+                ("'ENABLED'", "'true'"),
+                ("'chunk_length_in_kb'", "'64'"),
+            ]))
+            .unwrap();
+        assert_eq!(
+            "{'class':'LZ4Compressor', 'enabled':'true', 'chunk_length_in_kb':'64'}",
+            option.to_string()
+        );
+
+        let before = option.clone();
+        assert_eq!(
+            Err(MergeError::Conflict {
+                key: "class".to_string()
+            }),
+            option.merge(&map(&[
+                ("'crc_check_chance'", "0.5"),
+                ("'class'", "'ZstdCompressor'")
+            ]))
+        );
+        assert_eq!(before, option);
+        assert_eq!(
+            Err(MergeError::KindMismatch),
+            option.merge(&OptionValue::Literal("'x'".to_string()))
+        );
+
+        let mut literal = OptionValue::Literal("'a'".to_string());
+        assert_eq!(
+            Ok(()),
+            literal.merge(&OptionValue::Literal("'a'".to_string()))
+        );
+        assert_eq!(
+            Err(MergeError::LiteralConflict {
+                value: "'a'".to_string(),
+                other: "'b'".to_string()
+            }),
+            literal.merge(&OptionValue::Literal("'b'".to_string()))
+        );
+    }
+
+    #[test]
+    pub fn test_relation_element_constructors() {
+        assert_eq!(
+            "id = 5",
+            RelationElement::from_column_eq(Identifier::new("id"), Operand::from(&5)).to_string()
+        );
+        assert_eq!(
+            "ts < ?",
+            RelationElement::from_column_lt(Identifier::new("ts"), Operand::Param("?".to_string()))
+                .to_string()
+        );
+        assert_eq!(
+            "ts > 10",
+            RelationElement::from_column_gt(Identifier::new("ts"), Operand::from(&10)).to_string()
+        );
+        assert_eq!(
+            "id IN (1, 2, 3)",
+            RelationElement::from_column_in(
+                Identifier::new("id"),
+                vec![Operand::from(&1), Operand::from(&2), Operand::from(&3)]
+            )
+            .to_string()
+        );
+        assert_eq!(
+            "\"my tags\" CONTAINS 'x'",
+            RelationElement::from_column_contains(Identifier::new("my tags"), Operand::from("x"))
+                .to_string()
+        );
+    }
+
+    #[test]
+    pub fn test_order_clause_constructors() {
+        let asc = OrderClause::from_column("ts");
+        assert_eq!(
+            OrderClause {
+                name: "ts".to_string(),
+                desc: false
+            },
+            asc
+        );
+        assert_eq!("ts ASC", asc.to_cql_fragment());
+        let desc = OrderClause::desc("ts");
+        assert_eq!("ts DESC", desc.to_cql_fragment());
+        assert_eq!(desc, asc.toggle_direction());
+        assert_eq!(asc, desc.toggle_direction());
+    }
+
+    #[test]
+    pub fn test_where_clause_filters() {
+        let elements = vec![
+            RelationElement::from_column_eq(Identifier::new("pk"), Operand::from(&1)),
+            RelationElement::from_column_gt(Identifier::new("ck"), Operand::from(&5)),
+            RelationElement::from_column_in(
+                Identifier::new("c"),
+                vec![Operand::from(&1), Operand::from(&2)],
+            ),
+            RelationElement::from_column_eq(
+                Identifier::new("ck2"),
+                Operand::Param("?".to_string()),
+            ),
+            RelationElement::from_column_lt(Identifier::new("ck"), Operand::from(&10)),
+        ];
+        assert_eq!(
+            vec![&elements[0], &elements[3]],
+            WhereClause::equalities(&elements)
+        );
+        assert_eq!(
+            vec![&elements[1], &elements[4]],
+            WhereClause::ranges(&elements)
+        );
+        assert_eq!(
+            vec![&elements[2]],
+            WhereClause::filter_by_operator(&elements, RelationOperator::In)
+        );
+        assert!(WhereClause::filter_by_operator(&elements, RelationOperator::Contains).is_empty());
+    }
+
+    #[test]
+    pub fn test_restricts_primary_key() {
+        let pk = PrimaryKey {
+            partition: vec![Identifier::new("pk1"), Identifier::new("pk2")],
+            clustering: vec![
+                Identifier::new("c1"),
+                Identifier::new("c2"),
+                Identifier::new("c3"),
+            ],
+        };
+        let valid = WhereClause::restricts_primary_key(
+            &[
+                RelationElement::from_column_eq(Identifier::from_cql("PK1"), Operand::from(&1)),
+                RelationElement::from_column_in(
+                    Identifier::new("pk2"),
+                    vec![Operand::from(&1), Operand::from(&2)],
+                ),
+                RelationElement::from_column_eq(Identifier::new("c1"), Operand::from(&3)),
+                RelationElement::from_column_gt(Identifier::new("c2"), Operand::from(&4)),
+                RelationElement::from_column_lt(Identifier::new("c2"), Operand::from(&9)),
+            ],
+            &pk,
+        );
+        assert!(valid.is_partition_complete());
+        assert!(valid.is_clustering_prefix());
+        assert!(valid.is_valid());
+        assert!(valid.problems().is_empty());
+        assert_eq!(
+            vec![Identifier::new("c1"), Identifier::new("c2")],
+            valid.clustering_restricted
+        );
+
+        let gapped = WhereClause::restricts_primary_key(
+            &[
+                RelationElement::from_column_eq(Identifier::new("pk1"), Operand::from(&1)),
+                RelationElement::from_column_eq(Identifier::new("c2"), Operand::from(&4)),
+            ],
+            &pk,
+        );
+        assert!(!gapped.is_partition_complete());
+        assert_eq!(vec![Identifier::new("pk2")], gapped.partition_unrestricted);
+        assert!(!gapped.is_clustering_prefix());
+        assert!(!gapped.is_valid());
+        assert_eq!(
+            vec![(Identifier::new("c2"), Identifier::new("c1"))],
+            gapped.clustering_gaps
+        );
+        assert_eq!(
+            vec![
+                "clustering column c2 can not be restricted as preceding column c1 is not restricted",
+                "clustering columns can not be restricted as partition key columns pk2 are not restricted by EQ or IN relations",
+            ],
+            gapped.problems()
+        );
+
+        let after_range = WhereClause::restricts_primary_key(
+            &[
+                RelationElement::from_column_eq(Identifier::new("pk1"), Operand::from(&1)),
+                RelationElement::from_column_eq(Identifier::new("pk2"), Operand::from(&1)),
+                RelationElement::from_column_gt(Identifier::new("c1"), Operand::from(&4)),
+                RelationElement::from_column_eq(Identifier::new("c2"), Operand::from(&4)),
+            ],
+            &pk,
+        );
+        assert!(!after_range.is_valid());
+        assert_eq!(
+            vec![(Identifier::new("c2"), Identifier::new("c1"))],
+            after_range.clustering_after_non_eq
+        );
+
+        let multi_column = WhereClause::restricts_primary_key(
+            &[
+                RelationElement::from_column_eq(Identifier::new("pk1"), Operand::from(&1)),
+                RelationElement::from_column_eq(Identifier::new("pk2"), Operand::from(&1)),
+                RelationElement {
+                    obj: Operand::Tuple(vec![
+                        Operand::Column(Identifier::new("c1")),
+                        Operand::Column(Identifier::new("c2")),
+                    ]),
+                    oper: RelationOperator::Equal,
+                    value: Operand::Tuple(vec![Operand::from(&1), Operand::from(&2)]),
+                },
+            ],
+            &pk,
+        );
+        assert!(multi_column.is_valid());
+        assert_eq!(2, multi_column.clustering_restricted.len());
+
+        let tuple = |oper| RelationElement {
+            obj: Operand::Tuple(vec![
+                Operand::Column(Identifier::new("c1")),
+                Operand::Column(Identifier::new("c2")),
+            ]),
+            oper,
+            value: Operand::Tuple(vec![Operand::from(&1), Operand::from(&2)]),
+        };
+        let tuple_range = WhereClause::restricts_primary_key(
+            &[
+                RelationElement::from_column_eq(Identifier::new("pk1"), Operand::from(&1)),
+                RelationElement::from_column_eq(Identifier::new("pk2"), Operand::from(&1)),
+                tuple(RelationOperator::GreaterThan),
+                tuple(RelationOperator::LessThanOrEqual),
+            ],
+            &pk,
+        );
+        assert!(tuple_range.is_valid());
+        assert!(tuple_range.clustering_after_non_eq.is_empty());
+        assert_eq!(
+            vec![Identifier::new("c1"), Identifier::new("c2")],
+            tuple_range.clustering_restricted
+        );
+
+        let after_tuple_range = WhereClause::restricts_primary_key(
+            &[
+                RelationElement::from_column_eq(Identifier::new("pk1"), Operand::from(&1)),
+                RelationElement::from_column_eq(Identifier::new("pk2"), Operand::from(&1)),
+                tuple(RelationOperator::GreaterThan),
+                RelationElement::from_column_eq(Identifier::new("c3"), Operand::from(&3)),
+            ],
+            &pk,
+        );
+        assert!(!after_tuple_range.is_valid());
+        assert_eq!(
+            vec![(Identifier::new("c3"), Identifier::new("c1"))],
+            after_tuple_range.clustering_after_non_eq
+        );
+        assert!(WhereClause::restricts_primary_key(&[], &pk).is_valid());
+
+        let partition_range = WhereClause::restricts_primary_key(
+            &[
+                RelationElement::from_column_eq(Identifier::new("pk1"), Operand::from(&1)),
+                RelationElement::from_column_gt(Identifier::new("pk2"), Operand::from(&5)),
+                RelationElement::from_column_eq(Identifier::new("c1"), Operand::from(&3)),
+            ],
+            &pk,
+        );
+        assert!(!partition_range.is_partition_complete());
+        assert_eq!(
+            vec![Identifier::new("pk1")],
+            partition_range.partition_restricted
+        );
+        assert_eq!(
+            vec![Identifier::new("pk2")],
+            partition_range.partition_unrestricted
+        );
+        assert!(!partition_range.is_valid());
+    }
+
+    #[test]
+    pub fn test_column_relation_element_map_contains() {
+        let ast = CassandraAST::new(
+            "SELECT * FROM t WHERE m CONTAINS 'x' AND m CONTAINS KEY 'y' AND pk = 1",
+        );
+        assert!(!ast.has_error());
+        let where_clause = match &ast.statements[0].statement {
+            CassandraStatement::Select(select) => select.where_clause.clone(),
+            _ => unreachable!(),
+        };
+        let map = WhereClause::get_column_relation_element_map(&where_clause);
+        assert_eq!(2, map.len());
+        let m = &map["m"];
+        assert_eq!(2, m.len());
+        assert_eq!(RelationOperator::Contains, m[0].oper);
+        assert_eq!(Operand::Const("'x'".to_string()), m[0].value);
+        assert_eq!(RelationOperator::ContainsKey, m[1].oper);
+        assert_eq!(Operand::Const("'y'".to_string()), m[1].value);
+        assert_eq!(1, map["pk"].len());
+    }
+
+    #[test]
+    pub fn test_primary_key_columns() {
+        let key = PrimaryKey {
+            partition: vec![Identifier::new("pk1"), Identifier::new("PK2")],
+            clustering: vec![Identifier::new("ck")],
+        };
+        assert!(key.contains_column("pk1"));
+        assert!(key.contains_column("CK"));
+        assert!(!key.contains_column("other"));
+        assert!(key.is_partition_key("pk2"));
+        assert!(!key.is_partition_key("ck"));
+        assert!(key.is_clustering_key("Ck"));
+        assert!(!key.is_clustering_key("pk1"));
+        assert_eq!(Some((KeyPart::Partition, 1)), key.column_index("pk2"));
+        assert_eq!(Some((KeyPart::Clustering, 0)), key.column_index("ck"));
+        assert_eq!(None, key.column_index("other"));
+    }
+
+    #[test]
+    pub fn test_operand_collection_builders() {
+        let list = Operand::list_from([1, 2, 3].iter().map(Operand::from));
+        assert_eq!(
+            Operand::List(vec!["1".to_string(), "2".to_string(), "3".to_string()]),
+            list
+        );
+        assert_eq!("[1, 2, 3]", list.to_string());
+        assert_eq!(
+            "{'a', 'b'}",
+            Operand::set_from(vec![Operand::from("a"), Operand::from("b")]).to_string()
+        );
+        assert_eq!(
+            "{'a':1, 'b':?}",
+            Operand::map_from(vec![
+                (Operand::from("a"), Operand::from(&1)),
+                (Operand::from("b"), Operand::Param("?".to_string())),
+            ])
+            .to_string()
+        );
+    }
+
+    #[test]
+    pub fn test_operand_canonicalize() {
+        let mut a = Operand::Map(vec![
+            ("'b'".to_string(), "2".to_string()),
+            ("'a'".to_string(), "1".to_string()),
+        ]);
+        let mut b = Operand::Map(vec![
+            ("'a'".to_string(), "1".to_string()),
+            ("'b'".to_string(), "2".to_string()),
+        ]);
+        assert_ne!(a, b);
+        a.canonicalize();
+        b.canonicalize();
+        assert_eq!(a, b);
+        assert_eq!("{'a':1, 'b':2}", a.to_string());
+
+        let mut tuple = Operand::Tuple(vec![
+            Operand::Set(vec!["3".to_string(), "1".to_string()]),
+            Operand::List(vec!["3".to_string(), "1".to_string()]),
+        ]);
+        tuple.canonicalize();
+        assert_eq!(
+            Operand::Tuple(vec![
+                Operand::Set(vec!["1".to_string(), "3".to_string()]),
+                Operand::List(vec!["3".to_string(), "1".to_string()]),
+            ]),
+            tuple
+        );
+    }
+
+    #[test]
+    pub fn test_ttl_timestamp_display() {
+        let mut using = TtlTimestamp {
+            ttl: Some(Operand::from(&60)),
+            timestamp: None,
+        };
+        assert_eq!(" USING TTL 60", using.to_string());
+        using.timestamp = Some(Operand::from(&3));
+        assert_eq!(" USING TTL 60 AND TIMESTAMP 3", using.to_string());
+        using.ttl = None;
+        using.timestamp = Some(Operand::Param(":ts".to_string()));
+        assert_eq!(" USING TIMESTAMP :ts", using.to_string());
+    }
+
+    #[cfg(feature = "typed-operands")]
+    #[test]
+    pub fn test_typed_operand_conversion() {
+        assert_eq!(
+            Ok(BigInt::from(12345)),
+            Operand::from(&BigInt::from(12345)).as_bigint()
+        );
+        assert_eq!(
+            Ok(BigDecimal::from_str("12.345").unwrap()),
+            Operand::Const("12.345".to_string()).as_bigdecimal()
+        );
+        let uuid = Uuid::parse_str("5b6962dd-3f90-4c93-8f61-eabfa4a803e2").unwrap();
+        assert_eq!(Ok(uuid), Operand::from(&uuid).as_uuid());
+        assert_eq!(
+            Ok(uuid),
+            Operand::Const("5b6962dd-3f90-4c93-8f61-eabfa4a803e2".to_string()).as_uuid()
+        );
+        let bytes = Bytes::from(vec![0xde, 0xad]);
+        assert_eq!(Ok(bytes.clone()), Operand::from(&bytes).as_bytes());
+
+        assert!(Operand::from("not a uuid").as_uuid().is_err());
+        assert!(Operand::from(&5).as_bytes().is_err());
+    }
+
+    #[test]
+    pub fn test_operand_conversion() {
+        assert_eq!(Ok(-5), Operand::from(&-5i64).as_i64());
+        assert_eq!(Ok(3.5), Operand::from(&3.5f64).as_f64());
+        assert_eq!(Ok(true), Operand::from(&true).as_bool());
+        assert_eq!(Ok(false), Operand::Const("false".to_string()).as_bool());
+        assert_eq!(Ok("it's".to_string()), Operand::escape("it's").as_string());
+        let addr = IpAddr::from_str("127.0.0.1").unwrap();
+        assert_eq!(Ok(addr), Operand::from(&addr).as_ipaddr());
+
+        assert_eq!(
+            Err(OperandConversionError::NotConst("{'a':'b'}".to_string())),
+            Operand::Map(vec![("'a'".to_string(), "'b'".to_string())]).as_i64()
+        );
+        assert_eq!(
+            Err(OperandConversionError::NotConst("col".to_string())),
+            Operand::Column(Identifier::new("col")).as_bool()
+        );
+        assert_eq!(
+            Err(OperandConversionError::InvalidValue {
+                text: "'foo'".to_string(),
+                type_name: "i64"
+            }),
+            Operand::from("foo").as_i64()
+        );
+        assert_eq!(
+            Err(OperandConversionError::InvalidValue {
+                text: "'1'".to_string(),
+                type_name: "i64"
+            }),
+            Operand::Const("'1'".to_string()).as_i64()
+        );
+        assert!(Operand::Const("'2.5'".to_string()).as_f64().is_err());
+        assert!(Operand::Const("$$3$$".to_string()).as_i64().is_err());
+        assert!(Operand::Const("'true'".to_string()).as_bool().is_err());
+        assert_eq!(
+            Err(OperandConversionError::InvalidValue {
+                text: "5".to_string(),
+                type_name: "bool"
+            }),
+            Operand::from(&5).as_bool()
+        );
+        assert_eq!(
+            Err(OperandConversionError::InvalidValue {
+                text: "5".to_string(),
+                type_name: "String"
+            }),
+            Operand::from(&5).as_string()
+        );
+        assert!(Operand::from("not an address").as_ipaddr().is_err());
+    }
+
+    #[test]
+    pub fn test_operand_try_as() {
+        assert_eq!(Some(-5), Operand::from(&-5i64).try_as_i64());
+        assert_eq!(Some(2.5), Operand::Const("2.5".to_string()).try_as_f64());
+        assert_eq!(Some(true), Operand::Const("TRUE".to_string()).try_as_bool());
+        assert_eq!(
+            Some("it's".to_string()),
+            Operand::escape("it's").try_as_str()
+        );
+        assert_eq!(None, Operand::from("x").try_as_i64());
+        assert_eq!(None, Operand::from("5").try_as_i64());
+        assert_eq!(None, Operand::from("2.5").try_as_f64());
+        assert_eq!(None, Operand::from("true").try_as_bool());
+        assert_eq!(None, Operand::from(&5).try_as_str());
+        assert_eq!(None, Operand::from(&5).try_as_bool());
+        for operand in [Operand::Null, Operand::Param("?".to_string())] {
+            assert_eq!(None, operand.try_as_i64());
+            assert_eq!(None, operand.try_as_f64());
+            assert_eq!(None, operand.try_as_bool());
+            assert_eq!(None, operand.try_as_str());
+        }
+    }
+
+    #[cfg(feature = "typed-operands")]
+    #[test]
+    pub fn test_operand_try_as_uuid() {
+        let uuid = Uuid::parse_str("5d3bf0b6-4a4e-11ed-b878-0242ac120002").unwrap();
+        assert_eq!(Some(uuid), Operand::from(&uuid).try_as_uuid());
+        assert_eq!(None, Operand::from(&5).try_as_uuid());
+        assert_eq!(None, Operand::Null.try_as_uuid());
+        assert_eq!(None, Operand::Param(":id".to_string()).try_as_uuid());
+    }
+
+    #[test]
+    pub fn test_operand_const_kind() {
+        let kind = |text: &str| Operand::Const(text.to_string()).const_kind();
+        assert_eq!(Some(ConstKind::Hex), kind("0x1f"));
+        assert_eq!(Some(ConstKind::Hex), kind("0X1F"));
+        assert_eq!(Some(ConstKind::Integer), kind("55"));
+        assert_eq!(Some(ConstKind::Integer), kind("-55"));
+        assert_eq!(Some(ConstKind::QuotedString), kind("'x'"));
+        assert_eq!(Some(ConstKind::QuotedString), kind("'55'"));
+        assert_eq!(Some(ConstKind::DollarString), kind("$$x$$"));
+        assert_eq!(Some(ConstKind::Float), kind("5.5"));
+        assert_eq!(Some(ConstKind::Float), kind("-1e10"));
+        assert_eq!(Some(ConstKind::Float), kind("NaN"));
+        assert_eq!(Some(ConstKind::Boolean), kind("TRUE"));
+        assert_eq!(Some(ConstKind::Boolean), Operand::from(&false).const_kind());
+        assert_eq!(None, kind("5d3bf0b6-4a4e-11ed-b878-0242ac120002"));
+        assert_eq!(None, kind("1h30m"));
+        assert_eq!(None, Operand::Null.const_kind());
+        assert_eq!(None, Operand::Param("?".to_string()).const_kind());
+        assert_eq!(None, Operand::Column(Identifier::new("col")).const_kind());
+    }
+
+    #[test]
+    fn test_with_clause() {
+        assert_eq!("", WithClause::default().to_string());
+        let items = vec![
+            WithItem::Option {
+                key: "comment".to_string(),
+                value: OptionValue::Literal("'c'".to_string()),
+            },
+            WithItem::CompactStorage,
+        ];
+        let clause = WithClause::from(items.clone());
+        assert_eq!("WITH comment = 'c' AND COMPACT STORAGE", clause.to_string());
+        assert_eq!(items, Vec::<WithItem>::from(clause));
+    }
+
+    #[test]
+    fn test_negate() {
+        let value = Operand::Const("5".to_string());
+        let pairs = [
+            (RelationOperator::Equal, RelationOperator::NotEqual),
+            (RelationOperator::NotEqual, RelationOperator::Equal),
+            (
+                RelationOperator::LessThan,
+                RelationOperator::GreaterThanOrEqual,
+            ),
+            (
+                RelationOperator::LessThanOrEqual,
+                RelationOperator::GreaterThan,
+            ),
+            (
+                RelationOperator::GreaterThan,
+                RelationOperator::LessThanOrEqual,
+            ),
+            (
+                RelationOperator::GreaterThanOrEqual,
+                RelationOperator::LessThan,
+            ),
+        ];
+        for (oper, inverse) in pairs {
+            let relation = RelationElement {
+                obj: Operand::Column(Identifier::new("col")),
+                oper,
+                value: value.clone(),
+            };
+            let negated = relation.negate().unwrap();
+            assert_eq!(inverse, negated.oper);
+            assert_eq!(relation.obj, negated.obj);
+            assert_eq!(relation.value, negated.value);
+            assert_eq!(Some(relation), negated.negate());
+        }
+        assert_eq!(
+            "col >= 5",
+            RelationElement::from_column_lt(Identifier::new("col"), value.clone())
+                .negate()
+                .unwrap()
+                .to_string()
+        );
+        assert_eq!(
+            None,
+            RelationElement::from_column_in(Identifier::new("col"), vec![value.clone()]).negate()
+        );
+        assert_eq!(
+            None,
+            RelationElement::from_column_contains(Identifier::new("col"), value).negate()
+        );
+        assert_eq!(None, RelationOperator::ContainsKey.negate());
+        assert_eq!(None, RelationOperator::IsNot.negate());
+    }
+
+    #[test]
+    fn test_vector_type() {
+        let vector = DataType::try_parse("vector<float, 384>").unwrap();
+        assert_eq!(
+            DataType::vector(
+                DataType {
+                    name: DataTypeName::Float,
+                    definition: vec![],
+                },
+                384
+            ),
+            vector
+        );
+        assert_eq!(DataTypeName::Vector(384), vector.name);
+        assert_eq!(1, vector.definition.len());
+        assert_eq!("VECTOR<FLOAT, 384>", vector.to_string());
+        assert_eq!(
+            Some(384),
+            vector.as_vector().map(|(_, dimension)| dimension)
+        );
+        assert!(vector.compatible_with(&vector));
+        assert!(!vector.compatible_with(&DataType::try_parse("VECTOR<FLOAT, 3>").unwrap()));
+        assert!(!vector.compatible_with(&DataType::try_parse("LIST<FLOAT>").unwrap()));
+        assert_eq!(
+            None,
+            DataType::try_parse("LIST<FLOAT>").unwrap().as_vector()
+        );
+    }
+
+    #[test]
+    fn test_lowercase_data_type() {
+        assert_eq!("TIMESTAMP", DataTypeName::Timestamp.to_string());
+        assert_eq!("timestamp", DataTypeName::Timestamp.to_lowercase_string());
+        assert_eq!("bigint", format!("{:#}", DataTypeName::BigInt));
+        assert_eq!(
+            "MyType",
+            DataTypeName::Custom("MyType".to_string()).to_lowercase_string()
+        );
+        let data_type = DataType::try_parse("MAP<TEXT, FROZEN<LIST<MyType>>>").unwrap();
+        assert_eq!("MAP<TEXT, FROZEN<LIST<MyType>>>", data_type.to_string());
+        assert_eq!(
+            "map<text, frozen<list<MyType>>>",
+            data_type.to_lowercase_string()
+        );
+        assert_eq!(
+            "vector<float, 3>",
+            DataType::try_parse("VECTOR<FLOAT, 3>")
+                .unwrap()
+                .to_lowercase_string()
+        );
+    }
 }
@@ -1,4 +1,5 @@
 use crate::common::FQName;
+use std::fmt::{Display, Formatter};
 
 /// the data for many `Drop` commands
 #[derive(PartialEq, Debug, Clone)]
@@ -19,3 +20,34 @@ impl CommonDrop {
         )
     }
 }
+
+/// the kinds of object dropped with a `CommonDrop`.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum DropKind {
+    Aggregate,
+    Function,
+    Index,
+    Keyspace,
+    MaterializedView,
+    Role,
+    Table,
+    Type,
+    User,
+}
+
+impl Display for DropKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            DropKind::Aggregate => "AGGREGATE",
+            DropKind::Function => "FUNCTION",
+            DropKind::Index => "INDEX",
+            DropKind::Keyspace => "KEYSPACE",
+            DropKind::MaterializedView => "MATERIALIZED VIEW",
+            DropKind::Role => "ROLE",
+            DropKind::Table => "TABLE",
+            DropKind::Type => "TYPE",
+            DropKind::User => "USER",
+        };
+        write!(f, "{}", text)
+    }
+}
@@ -1,4 +1,4 @@
-use crate::common::FQName;
+use crate::common::{FQName, OptionValue};
 use std::fmt::{Display, Formatter};
 
 /// data to for the create index statement.
@@ -12,6 +12,25 @@ pub struct CreateIndex {
     pub table: FQName,
     /// the index column type.
     pub column: IndexColumnType,
+    /// the index class of a `CREATE CUSTOM INDEX` as written, including the quotes
+    /// (e.g. `'StorageAttachedIndex'`).
+    pub using: Option<String>,
+    /// the `WITH OPTIONS` map.
+    pub options: Option<OptionValue>,
+}
+
+impl CreateIndex {
+    /// returns true for a `CREATE CUSTOM INDEX`.
+    pub fn is_custom(&self) -> bool {
+        self.using.is_some()
+    }
+
+    /// returns the `similarity_function` option of a vector (ANN) index, e.g. `cosine`.
+    pub fn similarity_function(&self) -> Option<&str> {
+        self.options
+            .as_ref()
+            .and_then(|options| options.as_map_entry("similarity_function"))
+    }
 }
 
 impl Display for CreateIndex {
@@ -29,9 +48,20 @@ impl Display for CreateIndex {
 
         write!(
             f,
-            "CREATE INDEX {}{}ON {}( {} )",
-            exists, name, self.table, self.column
-        )
+            "CREATE {}INDEX {}{}ON {}( {} )",
+            if self.is_custom() { "CUSTOM " } else { "" },
+            exists,
+            name,
+            self.table,
+            self.column
+        )?;
+        if let Some(using) = &self.using {
+            write!(f, " USING {}", using)?;
+        }
+        if let Some(options) = &self.options {
+            write!(f, " WITH OPTIONS = {}", options)?;
+        }
+        Ok(())
     }
 }
 
@@ -58,3 +88,47 @@ impl Display for IndexColumnType {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::cassandra_ast::CassandraAST;
+    use crate::common::{DataType, DataTypeName};
+    use crate::create_index::CreateIndex;
+    use crate::create_table::CreateTable;
+    use crate::test_helpers::parse_one;
+
+    #[test]
+    fn test_vector_index() {
+        let table = "CREATE TABLE t (id int PRIMARY KEY, e vector<float, 384>)";
+        let index = "CREATE CUSTOM INDEX ON t(e) USING 'StorageAttachedIndex' WITH OPTIONS = {'similarity_function': 'cosine'}";
+        let ast = CassandraAST::new(&format!("{};{}", table, index));
+        assert!(!ast.has_error());
+        // the statements are emitted in the canonical form, which parses to the same statements
+        let emitted = [
+            "CREATE TABLE t (id INT PRIMARY KEY, e VECTOR<FLOAT, 384>)",
+            "CREATE CUSTOM INDEX ON t( e ) USING 'StorageAttachedIndex' WITH OPTIONS = {'similarity_function':'cosine'}",
+        ];
+        for (parsed, emitted) in ast.statements.iter().zip(emitted) {
+            assert_eq!(emitted, parsed.statement.to_string());
+            let reparsed = CassandraAST::new(emitted);
+            assert!(!reparsed.has_error());
+            assert_eq!(parsed.statement, reparsed.statements[0].statement);
+            assert_eq!(emitted, reparsed.statements[0].statement.to_string());
+        }
+        assert_eq!(
+            Some((
+                &DataType {
+                    name: DataTypeName::Float,
+                    definition: vec![],
+                },
+                384
+            )),
+            parse_one::<CreateTable>(table).columns[1]
+                .data_type
+                .as_vector()
+        );
+        let create = parse_one::<CreateIndex>(index);
+        assert!(create.is_custom());
+        assert_eq!(Some("cosine"), create.similarity_function());
+    }
+}
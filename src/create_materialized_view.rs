@@ -1,5 +1,5 @@
-use crate::common::{FQName, PrimaryKey};
-use crate::common::{RelationElement, WithItem};
+use crate::common::{FQName, Identifier, PrimaryKey};
+use crate::common::{RelationElement, WithClause};
 use itertools::Itertools;
 use std::fmt::{Display, Formatter};
 
@@ -11,7 +11,7 @@ pub struct CreateMaterializedView {
     /// the name of the materialized view.
     pub name: FQName,
     /// the columns in the view.
-    pub columns: Vec<String>,
+    pub columns: Vec<Identifier>,
     /// the table to extract the view from.
     pub table: FQName,
     /// the where clause to select.  Note: all elements of the primary key must be listed
@@ -20,7 +20,7 @@ pub struct CreateMaterializedView {
     /// the primary key for the view
     pub key: PrimaryKey,
     /// the with options.
-    pub with_clause: Vec<WithItem>,
+    pub with_clause: WithClause,
 }
 
 impl Display for CreateMaterializedView {
@@ -34,17 +34,14 @@ impl Display for CreateMaterializedView {
                 ""
             },
             self.name,
-            self.columns.join(", "),
+            self.columns.iter().join(", "),
             self.table,
             self.where_clause.iter().join(" AND "),
             self.key,
             if self.with_clause.is_empty() {
                 "".to_string()
             } else {
-                format!(
-                    " WITH {}",
-                    self.with_clause.iter().map(|x| x.to_string()).join(" AND ")
-                )
+                format!(" {}", self.with_clause)
             }
         )
     }
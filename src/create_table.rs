@@ -1,5 +1,11 @@
-use crate::common::{ColumnDefinition, FQName, PrimaryKey, WithItem};
-use itertools::Itertools;
+use crate::common::{
+    normalize_name, ColumnDefinition, DataType, DataTypeName, FQName, Identifier, OptionValue,
+    PrimaryKey, WithClause, WithItem,
+};
+use crate::table_options::{
+    default_ttl, find_option, integer_option, validate_options, Caching, Compaction, Compression,
+    OptionIssue,
+};
 use std::fmt::{Display, Formatter};
 
 /// The data for a `Create table` statement
@@ -14,9 +20,350 @@ pub struct CreateTable {
     /// the primary key if not specified in the column definitions.
     pub key: Option<PrimaryKey>,
     /// the list of `WITH` options.
-    pub with_clause: Vec<WithItem>,
+    pub with_clause: WithClause,
 }
 
+impl CreateTable {
+    /// creates a table definition with no columns, primary key clause, or options.
+    pub fn new(name: FQName) -> CreateTable {
+        CreateTable {
+            if_not_exists: false,
+            name,
+            columns: vec![],
+            key: None,
+            with_clause: WithClause::default(),
+        }
+    }
+
+    /// adds the column to the table.
+    pub fn with_column(mut self, column: ColumnDefinition) -> CreateTable {
+        self.columns.push(column);
+        self
+    }
+
+    /// sets the `PRIMARY KEY (...)` clause.
+    pub fn with_primary_key(mut self, key: PrimaryKey) -> CreateTable {
+        self.key = Some(key);
+        self
+    }
+
+    /// adds the `WITH` option.
+    pub fn with_option(mut self, item: WithItem) -> CreateTable {
+        self.with_clause.push(item);
+        self
+    }
+
+    /// returns the effective primary key.  This is the `PRIMARY KEY (...)` clause if present,
+    /// otherwise the column declared inline with `PRIMARY KEY` as the single partition column.
+    /// Specifying both is rejected by the server; in that case the clause is returned.
+    pub fn primary_key(&self) -> PrimaryKey {
+        match &self.key {
+            Some(key) => key.clone(),
+            None => PrimaryKey {
+                partition: self
+                    .columns
+                    .iter()
+                    .filter(|c| c.primary_key)
+                    .map(|c| c.name.clone())
+                    .collect(),
+                clustering: vec![],
+            },
+        }
+    }
+
+    /// returns the `STATIC` columns, those shared by all the rows in a partition.
+    pub fn static_columns(&self) -> Vec<&ColumnDefinition> {
+        self.columns.iter().filter(|c| c.is_static).collect()
+    }
+
+    /// returns the value of the `WITH` option.  The key matches case-insensitively.
+    pub fn option(&self, key: &str) -> Option<&OptionValue> {
+        find_option(self.with_clause.as_slice(), key)
+    }
+
+    /// returns the `cdc` option.
+    pub fn cdc(&self) -> Option<bool> {
+        self.option("cdc").and_then(OptionValue::as_bool)
+    }
+
+    /// returns the `default_time_to_live` option in seconds.  Returns `None` if the value is
+    /// not an integer the server accepts.
+    pub fn default_ttl(&self) -> Option<u32> {
+        default_ttl(self.with_clause.as_slice())
+    }
+
+    /// returns the `default_time_to_live` option in seconds, the same as `default_ttl()`.
+    pub fn default_time_to_live(&self) -> Option<u32> {
+        self.default_ttl()
+    }
+
+    /// checks the TTL related options and `speculative_retry`, see
+    /// `table_options::validate_options()`.
+    pub fn validate_options(&self) -> Vec<OptionIssue> {
+        validate_options(self.with_clause.as_slice())
+    }
+
+    /// returns the `gc_grace_seconds` option.
+    pub fn gc_grace_seconds(&self) -> Option<u64> {
+        self.integer_option("gc_grace_seconds")
+    }
+
+    /// returns the `memtable_flush_period_in_ms` option.
+    pub fn memtable_flush_period_in_ms(&self) -> Option<u64> {
+        self.integer_option("memtable_flush_period_in_ms")
+    }
+
+    fn integer_option(&self, key: &str) -> Option<u64> {
+        integer_option(self.with_clause.as_slice(), key)
+    }
+
+    /// returns a typed view of the `compaction` option.  Returns `None` if the option is not
+    /// present or is not a map with a `class` entry.
+    pub fn compaction(&self) -> Option<Compaction> {
+        find_option(self.with_clause.as_slice(), "compaction").and_then(Compaction::from_option)
+    }
+
+    /// returns a typed view of the `compression` option.  Returns `None` if the option is not
+    /// present or is not a map.
+    pub fn compression(&self) -> Option<Compression> {
+        find_option(self.with_clause.as_slice(), "compression").and_then(Compression::from_option)
+    }
+
+    /// returns a typed view of the `caching` option.  Returns `None` if the option is not
+    /// present or is not a map.
+    pub fn caching(&self) -> Option<Caching> {
+        find_option(self.with_clause.as_slice(), "caching").and_then(Caching::from_option)
+    }
+
+    /// checks the table definition.  Returns all the problems found rather than just the
+    /// first.  Frozen collections are allowed in the primary key.
+    pub fn validate(&self) -> Result<(), Vec<SchemaError>> {
+        let mut errors = vec![];
+        let name = |identifier: &Identifier| normalize_name(&identifier.to_string());
+
+        let mut names = vec![];
+        for column in &self.columns {
+            let column_name = name(&column.name);
+            if names.contains(&column_name) {
+                errors.push(SchemaError::DuplicateColumn(column.name.clone()));
+            } else {
+                names.push(column_name);
+            }
+        }
+
+        let key = self.primary_key();
+        let mut key_names = vec![];
+        for key_column in key.partition.iter().chain(key.clustering.iter()) {
+            key_names.push(name(key_column));
+            match self
+                .columns
+                .iter()
+                .find(|c| name(&c.name) == name(key_column))
+            {
+                None => errors.push(SchemaError::UndefinedKeyColumn(key_column.clone())),
+                Some(column) => {
+                    if matches!(
+                        column.data_type.name,
+                        DataTypeName::List | DataTypeName::Set | DataTypeName::Map
+                    ) {
+                        errors.push(SchemaError::CollectionKeyColumn(column.name.clone()));
+                    }
+                }
+            }
+        }
+
+        let (counters, others): (Vec<&ColumnDefinition>, Vec<&ColumnDefinition>) = self
+            .columns
+            .iter()
+            .filter(|c| !key_names.contains(&name(&c.name)))
+            .partition(|c| c.data_type.name == DataTypeName::Counter);
+        if !counters.is_empty() && !others.is_empty() {
+            errors.push(SchemaError::MixedCounterColumns);
+        }
+
+        if key.clustering.is_empty() {
+            for column in self.columns.iter().filter(|c| c.is_static) {
+                errors.push(SchemaError::StaticWithoutClustering(column.name.clone()));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// the problems found by `CreateTable::validate()`.
+#[derive(PartialEq, Debug, Clone)]
+pub enum SchemaError {
+    /// a primary key column is not defined in the column list.
+    UndefinedKeyColumn(Identifier),
+    /// the column is defined more than once.
+    DuplicateColumn(Identifier),
+    /// counter columns are mixed with non-counter columns.
+    MixedCounterColumns,
+    /// a non-frozen collection is used as a primary key column.
+    CollectionKeyColumn(Identifier),
+    /// a `STATIC` column is defined in a table without clustering columns.
+    StaticWithoutClustering(Identifier),
+}
+
+impl Display for SchemaError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SchemaError::UndefinedKeyColumn(name) => {
+                write!(f, "primary key column {} is not defined", name)
+            }
+            SchemaError::DuplicateColumn(name) => write!(f, "column {} is defined twice", name),
+            SchemaError::MixedCounterColumns => write!(
+                f,
+                "counter columns can not be mixed with non-counter columns"
+            ),
+            SchemaError::CollectionKeyColumn(name) => write!(
+                f,
+                "collection column {} can not be part of the primary key",
+                name
+            ),
+            SchemaError::StaticWithoutClustering(name) => write!(
+                f,
+                "static column {} requires a table with clustering columns",
+                name
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+/// builds `CreateTable` statements.  The primary key is always written as a
+/// `PRIMARY KEY (...)` clause.  Column names are written as in a statement, see
+/// `Identifier::from_cql()`.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct CreateTableBuilder {
+    name: Option<FQName>,
+    if_not_exists: bool,
+    columns: Vec<ColumnDefinition>,
+    partition: Vec<Identifier>,
+    clustering: Vec<Identifier>,
+    with_clause: WithClause,
+}
+
+impl CreateTableBuilder {
+    /// start building a table definition.
+    pub fn new() -> CreateTableBuilder {
+        CreateTableBuilder::default()
+    }
+
+    /// set the name of the table.
+    pub fn name(mut self, name: FQName) -> CreateTableBuilder {
+        self.name = Some(name);
+        self
+    }
+
+    /// add a column to the table.
+    pub fn column(mut self, name: &str, data_type: DataType) -> CreateTableBuilder {
+        self.columns.push(ColumnDefinition {
+            name: Identifier::from_cql(name),
+            data_type,
+            primary_key: false,
+            is_static: false,
+        });
+        self
+    }
+
+    /// add a `STATIC` column to the table.
+    pub fn static_column(mut self, name: &str, data_type: DataType) -> CreateTableBuilder {
+        self = self.column(name, data_type);
+        if let Some(column) = self.columns.last_mut() {
+            column.is_static = true;
+        }
+        self
+    }
+
+    /// set the partition key columns, more than one column creates a composite partition key.
+    pub fn partition_key<I, S>(mut self, columns: I) -> CreateTableBuilder
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.partition = columns
+            .into_iter()
+            .map(|c| Identifier::from_cql(c.as_ref()))
+            .collect();
+        self
+    }
+
+    /// set the clustering key columns.
+    pub fn clustering_key<I, S>(mut self, columns: I) -> CreateTableBuilder
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.clustering = columns
+            .into_iter()
+            .map(|c| Identifier::from_cql(c.as_ref()))
+            .collect();
+        self
+    }
+
+    /// add a `WITH` option.
+    pub fn with_option(mut self, key: &str, value: OptionValue) -> CreateTableBuilder {
+        self.with_clause.push(WithItem::Option {
+            key: key.to_string(),
+            value,
+        });
+        self
+    }
+
+    /// add `IF NOT EXISTS` to the statement.
+    pub fn if_not_exists(mut self) -> CreateTableBuilder {
+        self.if_not_exists = true;
+        self
+    }
+
+    /// create the `CreateTable`.
+    pub fn build(self) -> Result<CreateTable, CreateTableBuildError> {
+        let name = self.name.ok_or(CreateTableBuildError::NoName)?;
+        if self.partition.is_empty() {
+            return Err(CreateTableBuildError::NoPartitionKey);
+        }
+        Ok(CreateTable {
+            if_not_exists: self.if_not_exists,
+            name,
+            columns: self.columns,
+            key: Some(PrimaryKey {
+                partition: self.partition,
+                clustering: self.clustering,
+            }),
+            with_clause: self.with_clause,
+        })
+    }
+}
+
+/// the errors returned by `CreateTableBuilder::build()`.
+#[derive(PartialEq, Debug, Clone)]
+pub enum CreateTableBuildError {
+    /// the table name was not specified.
+    NoName,
+    /// no partition key columns were specified.
+    NoPartitionKey,
+}
+
+impl Display for CreateTableBuildError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CreateTableBuildError::NoName => write!(f, "a create table requires a table name"),
+            CreateTableBuildError::NoPartitionKey => {
+                write!(f, "a create table requires a partition key")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CreateTableBuildError {}
+
 impl Display for CreateTable {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let mut v: Vec<String> = self.columns.iter().map(|x| x.to_string()).collect();
@@ -34,13 +381,322 @@ impl Display for CreateTable {
             self.name,
             v.join(", "),
             if !self.with_clause.is_empty() {
-                format!(
-                    " WITH {}",
-                    self.with_clause.iter().map(|x| x.to_string()).join(" AND ")
-                )
+                format!(" {}", self.with_clause)
             } else {
                 "".to_string()
             }
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::cassandra_ast::CassandraAST;
+    use crate::cassandra_statement::CassandraStatement;
+    use crate::common::{
+        ColumnDefinition, DataType, FQName, Identifier, OptionValue, PrimaryKey, WithClause,
+        WithItem,
+    };
+    use crate::create_table::{
+        CreateTable, CreateTableBuildError, CreateTableBuilder, SchemaError,
+    };
+    use crate::insert::InsertBuilder;
+    use crate::test_helpers::parse_one;
+
+    #[test]
+    fn test_primary_key() {
+        assert_eq!(
+            PrimaryKey {
+                partition: vec![Identifier::new("pk")],
+                clustering: vec![],
+            },
+            parse_one::<CreateTable>("CREATE TABLE t (pk int PRIMARY KEY, col text)").primary_key()
+        );
+        assert_eq!(
+            PrimaryKey {
+                partition: vec![Identifier::new("pk")],
+                clustering: vec![Identifier::new("c1"), Identifier::new("c2")],
+            },
+            parse_one::<CreateTable>(
+                "CREATE TABLE t (pk int, c1 int, c2 int, PRIMARY KEY (pk, c1, c2))"
+            )
+            .primary_key()
+        );
+        assert_eq!(
+            PrimaryKey {
+                partition: vec![Identifier::new("pk1"), Identifier::new("pk2")],
+                clustering: vec![Identifier::new("c1")],
+            },
+            parse_one::<CreateTable>(
+                "CREATE TABLE t (pk1 int, pk2 int, c1 int, PRIMARY KEY ((pk1, pk2), c1))"
+            )
+            .primary_key()
+        );
+        assert_eq!(
+            PrimaryKey {
+                partition: vec![Identifier::new("pk1"), Identifier::new("pk2")],
+                clustering: vec![],
+            },
+            parse_one::<CreateTable>("CREATE TABLE t (pk1 int, pk2 int, PRIMARY KEY ((pk1, pk2)))")
+                .primary_key()
+        );
+        assert_eq!(
+            PrimaryKey {
+                partition: vec![Identifier::new("Pk")],
+                clustering: vec![Identifier::new("c-1")],
+            },
+            parse_one::<CreateTable>(
+                "CREATE TABLE t (\"Pk\" int, \"c-1\" int, PRIMARY KEY (\"Pk\", \"c-1\"))"
+            )
+            .primary_key()
+        );
+    }
+
+    #[test]
+    fn test_builder() {
+        let column = |name: &str, data_type: &str| ColumnDefinition {
+            name: Identifier::new(name),
+            data_type: DataType::try_parse(data_type).unwrap(),
+            primary_key: false,
+            is_static: false,
+        };
+        let create = CreateTable::new(FQName::new("ks", "t"))
+            .with_column(column("pk", "INT"))
+            .with_column(column("c", "TEXT"))
+            .with_column(column("m", "MAP<TEXT, INT>"))
+            .with_primary_key(PrimaryKey {
+                partition: vec![Identifier::new("pk")],
+                clustering: vec![Identifier::new("c")],
+            })
+            .with_option(WithItem::Option {
+                key: "comment".to_string(),
+                value: OptionValue::Literal("'test'".to_string()),
+            });
+        let qry = "CREATE TABLE ks.t (pk INT, c TEXT, m MAP<TEXT, INT>, PRIMARY KEY (pk, c)) WITH comment = 'test'";
+        assert_eq!(
+            qry,
+            CassandraStatement::CreateTable(create.clone()).to_string()
+        );
+        let ast = CassandraAST::new(qry);
+        assert!(!ast.has_error());
+        assert_eq!(
+            CassandraStatement::CreateTable(create),
+            ast.statements[0].statement
+        );
+    }
+
+    #[test]
+    fn test_create_table_builder() {
+        let data_type = |txt: &str| DataType::try_parse(txt).unwrap();
+        let create = CreateTableBuilder::new()
+            .name(FQName::new("ks", "t"))
+            .column("id", data_type("int"))
+            .column("ts", data_type("timestamp"))
+            .static_column("s", data_type("text"))
+            .column("v", data_type("list<text>"))
+            .partition_key(["id"])
+            .clustering_key(["ts"])
+            .with_option("cdc", OptionValue::Literal("true".into()))
+            .if_not_exists()
+            .build()
+            .unwrap();
+        let qry = "CREATE TABLE IF NOT EXISTS ks.t (id INT, ts TIMESTAMP, s TEXT STATIC, v LIST<TEXT>, PRIMARY KEY (id, ts)) WITH cdc = true";
+        assert_eq!(
+            qry,
+            CassandraStatement::CreateTable(create.clone()).to_string()
+        );
+        assert_eq!(
+            CassandraStatement::CreateTable(create),
+            CassandraAST::new(qry).statements[0].statement
+        );
+
+        let create = CreateTableBuilder::new()
+            .name(FQName::simple("t"))
+            .column("a", data_type("int"))
+            .column("b", data_type("int"))
+            .partition_key(vec!["a".to_string(), "b".to_string()])
+            .build()
+            .unwrap();
+        let qry = "CREATE TABLE t (a INT, b INT, PRIMARY KEY ((a, b)))";
+        assert_eq!(
+            CassandraStatement::CreateTable(create),
+            CassandraAST::new(qry).statements[0].statement
+        );
+
+        assert_eq!(
+            Err(CreateTableBuildError::NoPartitionKey),
+            CreateTableBuilder::new()
+                .name(FQName::simple("t"))
+                .column("a", data_type("int"))
+                .build()
+        );
+        assert_eq!(
+            Err(CreateTableBuildError::NoName),
+            CreateTableBuilder::new().partition_key(["a"]).build()
+        );
+    }
+
+    #[test]
+    fn test_builder_names() {
+        let create = CreateTableBuilder::new()
+            .name(FQName::simple("t"))
+            .column("MyCol", DataType::try_parse("int").unwrap())
+            .partition_key(["MyCol"])
+            .build()
+            .unwrap();
+        let insert = InsertBuilder::into("t")
+            .value("MyCol", &1i64)
+            .build()
+            .unwrap();
+        // both builders name the same column
+        assert_eq!(create.columns[0].name, insert.columns[0]);
+        assert_eq!(
+            "CREATE TABLE t (MyCol INT, PRIMARY KEY (MyCol))",
+            CassandraStatement::CreateTable(create).to_string()
+        );
+        assert_eq!(
+            "INSERT INTO t (MyCol) VALUES (1)",
+            CassandraStatement::Insert(insert).to_string()
+        );
+    }
+
+    #[test]
+    fn test_typed_option_values() {
+        let create = parse_one::<CreateTable>(
+            "CREATE TABLE t (pk int PRIMARY KEY) WITH gc_grace_seconds = 864000 AND comment = 'it''s' AND crc_check_chance = 0.5 AND cdc = true",
+        );
+        let gc_grace = create.option("GC_GRACE_SECONDS").unwrap();
+        assert_eq!(Some(864000.0), gc_grace.as_number());
+        assert_eq!(None, gc_grace.as_string());
+        assert_eq!(None, gc_grace.as_bool());
+        let comment = create.option("comment").unwrap();
+        assert_eq!(Some("it's".to_string()), comment.as_string());
+        assert_eq!(None, comment.as_number());
+        assert_eq!(
+            Some(0.5),
+            create.option("crc_check_chance").unwrap().as_number()
+        );
+        assert_eq!(Some(true), create.option("cdc").unwrap().as_bool());
+        assert_eq!(None, create.option("compaction"));
+    }
+
+    #[test]
+    fn test_numeric_and_boolean_options() {
+        let qry = "CREATE TABLE ks.t (pk INT PRIMARY KEY) WITH cdc = true AND default_time_to_live = 86400 AND gc_grace_seconds = 864000 AND memtable_flush_period_in_ms = 3600000";
+        let option = |key: &str, value: &str| WithItem::Option {
+            key: key.to_string(),
+            value: OptionValue::Literal(value.to_string()),
+        };
+        let expected = CreateTable {
+            if_not_exists: false,
+            name: FQName {
+                keyspace: Some("ks".to_string()),
+                name: "t".to_string(),
+            },
+            columns: vec![ColumnDefinition {
+                name: Identifier {
+                    name: "pk".to_string(),
+                    quoted: false,
+                },
+                data_type: DataType::try_parse("INT").unwrap(),
+                primary_key: true,
+                is_static: false,
+            }],
+            key: None,
+            with_clause: WithClause(vec![
+                option("cdc", "true"),
+                option("default_time_to_live", "86400"),
+                option("gc_grace_seconds", "864000"),
+                option("memtable_flush_period_in_ms", "3600000"),
+            ]),
+        };
+        let create = parse_one::<CreateTable>(qry);
+        assert_eq!(expected, create);
+        assert_eq!(
+            qry,
+            CassandraStatement::CreateTable(create.clone()).to_string()
+        );
+        assert_eq!(Some(true), create.cdc());
+        assert_eq!(Some(86400), create.default_ttl());
+        assert_eq!(Some(86400), create.default_time_to_live());
+        assert_eq!(Some(864000), create.gc_grace_seconds());
+        assert_eq!(Some(3600000), create.memtable_flush_period_in_ms());
+
+        let create = CreateTable::new(FQName::simple("t"));
+        assert_eq!(None, create.cdc());
+        assert_eq!(None, create.gc_grace_seconds());
+    }
+
+    #[test]
+    fn test_static_column() {
+        let qry = "CREATE TABLE t (pk int, c int, s text STATIC, PRIMARY KEY (pk, c))";
+        let create = parse_one::<CreateTable>(qry);
+        assert!(create.columns[2].is_static);
+        assert!(!create.columns[2].primary_key);
+        assert!(!create.columns[0].is_static);
+        assert_eq!(vec![&create.columns[2]], create.static_columns());
+        assert_eq!(
+            Err(vec![SchemaError::StaticWithoutClustering(Identifier::new(
+                "s"
+            ))]),
+            create
+                .clone()
+                .with_primary_key(PrimaryKey {
+                    partition: vec![Identifier::new("pk"), Identifier::new("c")],
+                    clustering: vec![],
+                })
+                .validate()
+        );
+        assert_eq!(
+            "t (pk INT, c INT, s TEXT STATIC, PRIMARY KEY (pk, c))",
+            create.to_string()
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let create = parse_one::<CreateTable>;
+        assert_eq!(
+            Ok(()),
+            create("CREATE TABLE t (pk int, c int, s text STATIC, v text, PRIMARY KEY (pk, c))")
+                .validate()
+        );
+        assert_eq!(
+            Ok(()),
+            create(
+                "CREATE TABLE t (pk frozen<list<int>>, c int, s text STATIC, PRIMARY KEY (pk, c))"
+            )
+            .validate()
+        );
+        assert_eq!(
+            Ok(()),
+            create("CREATE TABLE t (pk int PRIMARY KEY, v1 counter, v2 counter)").validate()
+        );
+        assert_eq!(
+            Err(vec![
+                SchemaError::DuplicateColumn(Identifier::parse("PK")),
+                SchemaError::CollectionKeyColumn(Identifier::new("l")),
+                SchemaError::UndefinedKeyColumn(Identifier::new("c")),
+                SchemaError::MixedCounterColumns,
+            ]),
+            create("CREATE TABLE t (pk int, PK text, l list<int>, s text STATIC, v counter, w text, PRIMARY KEY ((pk, l), c))")
+                .validate()
+        );
+        assert_eq!(
+            Err(vec![SchemaError::StaticWithoutClustering(Identifier::new(
+                "s"
+            ))]),
+            create("CREATE TABLE t (pk int PRIMARY KEY, s text STATIC)").validate()
+        );
+    }
+
+    #[test]
+    fn test_masked_columns_unsupported() {
+        // column masks are not in the grammar.
+        assert!(CassandraAST::new(
+            "CREATE TABLE t (pk int PRIMARY KEY, v text MASKED WITH DEFAULT)"
+        )
+        .has_error());
+        assert!(CassandraAST::new("ALTER TABLE t ALTER v DROP MASKED").has_error());
+    }
+}
@@ -15,6 +15,15 @@ pub struct CreateUser {
     pub if_not_exists: bool,
 }
 
+impl CreateUser {
+    /// replaces the password, if any, with `'*****'` so that the statement can be logged.
+    pub fn redact_password(&mut self) {
+        if let Some(password) = &mut self.password {
+            *password = "'*****'".to_string();
+        }
+    }
+}
+
 impl Display for CreateUser {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let mut with = String::new();
@@ -1,5 +1,5 @@
 use crate::begin_batch::BeginBatch;
-use crate::common::{FQName, RelationElement};
+use crate::common::{FQName, Identifier, Operand, RelationElement};
 use itertools::Itertools;
 use std::fmt::{Display, Formatter};
 
@@ -12,8 +12,8 @@ pub struct Delete {
     pub columns: Vec<IndexedColumn>,
     /// the table to delete from
     pub table_name: FQName,
-    /// an optional timestamp to use for the deletion.
-    pub timestamp: Option<u64>,
+    /// an optional timestamp to use for the deletion, either a literal or a bind marker.
+    pub timestamp: Option<Operand>,
     /// the were clause for the delete.
     pub where_clause: Vec<RelationElement>,
     /// if present a list of key,values for the `IF` clause
@@ -22,6 +22,49 @@ pub struct Delete {
     pub if_exists: bool,
 }
 
+impl Delete {
+    /// returns the `IF` conditions.  Empty unless the delete is conditional on column values.
+    /// `IF EXISTS` is reported by `if_exists` and is never combined with conditions.
+    pub fn conditions(&self) -> &[RelationElement] {
+        &self.if_clause
+    }
+
+    /// returns true if the statement is a lightweight transaction (`IF EXISTS` or `IF condition`).
+    pub fn is_lwt(&self) -> bool {
+        self.if_exists || !self.if_clause.is_empty()
+    }
+
+    /// return the names of the columns deleted by the statement.  Element and field targets
+    /// (e.g. `m['key']`) report the base column.  Empty when the whole row is deleted.
+    pub fn columns_written(&self) -> Vec<&str> {
+        self.columns.iter().map(|c| c.column.as_str()).collect()
+    }
+
+    /// returns true if the statement deletes a range of rows, that is if any of the
+    /// `clustering_cols` is constrained by an inequality (`<`, `<=`, `>`, `>=`) in the where
+    /// clause.  Range deletes create range tombstones.  Multi-column relations such as
+    /// `(ck1, ck2) >= (1, 2)` are a range if any of the columns is a clustering column.
+    pub fn is_range_delete(&self, clustering_cols: &[String]) -> bool {
+        let is_clustering = |operand: &Operand| match operand {
+            Operand::Column(column) => clustering_cols.iter().any(|name| {
+                if column.quoted {
+                    column.as_str() == name
+                } else {
+                    column.as_str().eq_ignore_ascii_case(name)
+                }
+            }),
+            _ => false,
+        };
+        self.where_clause.iter().any(|relation| {
+            relation.oper.is_range()
+                && match &relation.obj {
+                    Operand::Tuple(columns) => columns.iter().any(is_clustering),
+                    obj => is_clustering(obj),
+                }
+        })
+    }
+}
+
 impl Display for Delete {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -54,21 +97,342 @@ impl Display for Delete {
     }
 }
 
-/// Defines an indexed column.  Indexed columns comprise a column name and an optional index into
-/// the column.  This is expressed as `column[idx]`
+/// builds a `Delete` statement.
 #[derive(PartialEq, Debug, Clone)]
+pub struct DeleteBuilder {
+    table_name: FQName,
+    columns: Vec<IndexedColumn>,
+    timestamp: Option<Operand>,
+    where_clause: Vec<RelationElement>,
+    if_clause: Vec<RelationElement>,
+    if_exists: bool,
+}
+
+impl DeleteBuilder {
+    /// start building a delete from the table, either a `FQName` or `table` or
+    /// `keyspace.table`.  Without columns the whole row is deleted.  Table and column names
+    /// are written as in a statement, see `Identifier::from_cql()`.
+    pub fn table<T: Into<FQName>>(table_name: T) -> DeleteBuilder {
+        DeleteBuilder {
+            table_name: table_name.into(),
+            columns: vec![],
+            timestamp: None,
+            where_clause: vec![],
+            if_clause: vec![],
+            if_exists: false,
+        }
+    }
+
+    /// delete the column.
+    pub fn column(mut self, column: &str) -> DeleteBuilder {
+        self.columns.push(IndexedColumn {
+            column: Identifier::from_cql(column),
+            idx: None,
+            field: None,
+        });
+        self
+    }
+
+    /// delete an element of a collection column, `column[idx]`.
+    pub fn element<T: Into<Operand>>(mut self, column: &str, idx: T) -> DeleteBuilder {
+        self.columns.push(IndexedColumn {
+            column: Identifier::from_cql(column),
+            idx: Some(idx.into()),
+            field: None,
+        });
+        self
+    }
+
+    /// set the `USING TIMESTAMP` value.
+    pub fn timestamp(mut self, timestamp: u64) -> DeleteBuilder {
+        self.timestamp = Some(Operand::from(&timestamp));
+        self
+    }
+
+    /// add the relation to the `WHERE` clause.
+    pub fn where_(mut self, relation: RelationElement) -> DeleteBuilder {
+        self.where_clause.push(relation);
+        self
+    }
+
+    /// add `column = value` to the `WHERE` clause.
+    pub fn where_eq<T: Into<Operand>>(self, column: &str, value: T) -> DeleteBuilder {
+        self.where_(RelationElement::from_column_eq(
+            Identifier::from_cql(column),
+            value.into(),
+        ))
+    }
+
+    /// add the condition to the `IF` clause.
+    pub fn if_condition(mut self, condition: RelationElement) -> DeleteBuilder {
+        self.if_clause.push(condition);
+        self
+    }
+
+    /// add `column = value` to the `IF` clause.
+    pub fn if_eq<T: Into<Operand>>(self, column: &str, value: T) -> DeleteBuilder {
+        self.if_condition(RelationElement::from_column_eq(
+            Identifier::from_cql(column),
+            value.into(),
+        ))
+    }
+
+    /// add `IF EXISTS`.
+    pub fn if_exists(mut self) -> DeleteBuilder {
+        self.if_exists = true;
+        self
+    }
+
+    /// create the `Delete`.
+    pub fn build(self) -> Result<Delete, DeleteBuildError> {
+        if self.where_clause.is_empty() {
+            return Err(DeleteBuildError::NoWhereClause);
+        }
+        if self.if_exists && !self.if_clause.is_empty() {
+            return Err(DeleteBuildError::IfExistsWithConditions);
+        }
+        Ok(Delete {
+            begin_batch: None,
+            columns: self.columns,
+            table_name: self.table_name,
+            timestamp: self.timestamp,
+            where_clause: self.where_clause,
+            if_clause: self.if_clause,
+            if_exists: self.if_exists,
+        })
+    }
+}
+
+/// the errors returned by `DeleteBuilder::build()`.
+#[derive(PartialEq, Debug, Clone)]
+pub enum DeleteBuildError {
+    /// no `WHERE` relations were specified.
+    NoWhereClause,
+    /// both `IF EXISTS` and `IF` conditions were specified.
+    IfExistsWithConditions,
+}
+
+impl Display for DeleteBuildError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeleteBuildError::NoWhereClause => {
+                write!(f, "a delete requires at least one WHERE relation")
+            }
+            DeleteBuildError::IfExistsWithConditions => {
+                write!(f, "IF EXISTS can not be combined with IF conditions")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DeleteBuildError {}
+
+/// Defines an indexed column.  Indexed columns comprise a column name and either an optional
+/// index into the column or an optional field of a user defined type.  This is expressed as
+/// `column[idx]` or `column.field`
+#[derive(PartialEq, Debug, Clone, Eq, Ord, PartialOrd)]
 pub struct IndexedColumn {
     /// the column name
-    pub column: String,
-    /// the optional index in to the column
-    pub idx: Option<String>,
+    pub column: Identifier,
+    /// the optional index in to the column.  May be a constant, bind marker or function call.
+    pub idx: Option<Operand>,
+    /// the optional user defined type field.
+    pub field: Option<Identifier>,
 }
 
 impl Display for IndexedColumn {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        match &self.idx {
-            Some(x) => write!(f, "{}[{}]", self.column, x),
-            None => write!(f, "{}", self.column),
+        write!(f, "{}", self.column)?;
+        if let Some(x) = &self.idx {
+            write!(f, "[{}]", x)?;
+        }
+        match &self.field {
+            Some(field) => write!(f, ".{}", field),
+            None => Ok(()),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{FQName, Identifier, Operand, RelationElement, RelationOperator};
+    use crate::delete::{Delete, DeleteBuildError, DeleteBuilder, IndexedColumn};
+    use crate::test_helpers::parse_one;
+
+    #[test]
+    fn test_delete_targets() {
+        let qry = "DELETE col1, m['key'], l[2], l[?] FROM t WHERE id = ?";
+        let delete = parse_one::<Delete>(qry);
+        assert_eq!(qry, delete.to_string());
+        assert_eq!(
+            vec![
+                IndexedColumn {
+                    column: Identifier::new("col1"),
+                    idx: None,
+                    field: None,
+                },
+                IndexedColumn {
+                    column: Identifier::new("m"),
+                    idx: Some(Operand::from("key")),
+                    field: None,
+                },
+                IndexedColumn {
+                    column: Identifier::new("l"),
+                    idx: Some(Operand::Const("2".to_string())),
+                    field: None,
+                },
+                IndexedColumn {
+                    column: Identifier::new("l"),
+                    idx: Some(Operand::Param("?".to_string())),
+                    field: None,
+                },
+            ],
+            delete.columns
+        );
+        assert_eq!(vec!["col1", "m", "l", "l"], delete.columns_written());
+        assert!(parse_one::<Delete>("DELETE FROM t WHERE id = 1")
+            .columns_written()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_delete_conditions_with_timestamp() {
+        let qry = "DELETE FROM t USING TIMESTAMP 5 WHERE id = 1 IF EXISTS";
+        let delete = parse_one::<Delete>(qry);
+        assert_eq!(qry, delete.to_string());
+        assert_eq!(Some(Operand::from(&5)), delete.timestamp);
+        assert!(delete.if_exists);
+        assert!(delete.conditions().is_empty());
+
+        let qry = "DELETE v FROM t USING TIMESTAMP 5 WHERE id = 1 IF version = ?";
+        let delete = parse_one::<Delete>(qry);
+        assert_eq!(qry, delete.to_string());
+        assert!(!delete.if_exists);
+        assert_eq!(
+            vec![RelationElement::from_column_eq(
+                Identifier::new("version"),
+                Operand::Param("?".to_string())
+            )],
+            delete.conditions()
+        );
+        assert!(delete.is_lwt());
+    }
+
+    #[test]
+    fn test_range_delete() {
+        let clustering = vec!["ck".to_string(), "ck2".to_string()];
+
+        let qry = "DELETE FROM t WHERE pk = 1 AND ck >= 5 AND ck < 10";
+        let delete = parse_one::<Delete>(qry);
+        assert_eq!(qry, delete.to_string());
+        assert_eq!(
+            vec![
+                RelationOperator::Equal,
+                RelationOperator::GreaterThanOrEqual,
+                RelationOperator::LessThan
+            ],
+            delete
+                .where_clause
+                .iter()
+                .map(|r| r.oper.clone())
+                .collect::<Vec<RelationOperator>>()
+        );
+        assert!(delete.is_range_delete(&clustering));
+
+        let qry = "DELETE FROM t WHERE pk = 1 AND ck = 2 AND ck2 <= 5";
+        let delete = parse_one::<Delete>(qry);
+        assert_eq!(qry, delete.to_string());
+        assert!(delete.is_range_delete(&clustering));
+
+        let qry = "DELETE FROM t WHERE pk = 1 AND ck > 5";
+        assert!(parse_one::<Delete>(qry).is_range_delete(&clustering));
+        assert!(!parse_one::<Delete>(qry).is_range_delete(&["other".to_string()]));
+
+        let qry = "DELETE FROM t WHERE pk = 1 AND (ck, ck2) > (1, 2)";
+        let delete = parse_one::<Delete>(qry);
+        assert_eq!(qry, delete.to_string());
+        assert!(delete.is_range_delete(&clustering));
+
+        let qry = "DELETE FROM t WHERE pk = 1 AND (ck, ck2) = (1, 2)";
+        assert!(!parse_one::<Delete>(qry).is_range_delete(&clustering));
+
+        let qry = "DELETE FROM t WHERE pk = 1 AND ck IN (1, 2)";
+        assert!(!parse_one::<Delete>(qry).is_range_delete(&clustering));
+
+        // an inequality on a column that is not a clustering column is not a range delete.
+        let qry = "DELETE FROM t WHERE pk >= 1";
+        assert!(!parse_one::<Delete>(qry).is_range_delete(&clustering));
+    }
+
+    #[test]
+    fn test_delete_builder() {
+        assert_eq!(
+            parse_one::<Delete>("DELETE FROM ks.t WHERE id = 1"),
+            DeleteBuilder::table(FQName::new("ks", "t"))
+                .where_eq("id", &1i64)
+                .build()
+                .unwrap()
+        );
+        assert_eq!(
+            parse_one::<Delete>("DELETE m, m['k'] FROM ks.t WHERE id = 1 IF EXISTS"),
+            DeleteBuilder::table("ks.t")
+                .column("m")
+                .element("m", Operand::from("k"))
+                .where_eq("id", &1i64)
+                .if_exists()
+                .build()
+                .unwrap()
+        );
+        assert_eq!(
+            parse_one::<Delete>(
+                "DELETE l[2] FROM t USING TIMESTAMP 5 WHERE id = ? AND ck > 3 IF version = 1"
+            ),
+            DeleteBuilder::table("t")
+                .element("l", &2)
+                .timestamp(5)
+                .where_eq("id", Operand::Param("?".to_string()))
+                .where_(RelationElement::from_column_gt(
+                    Identifier::new("ck"),
+                    Operand::from(&3)
+                ))
+                .if_eq("version", &1)
+                .build()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_delete_builder_errors() {
+        assert_eq!(
+            Err(DeleteBuildError::NoWhereClause),
+            DeleteBuilder::table("t").column("v").build()
+        );
+        assert_eq!(
+            Err(DeleteBuildError::IfExistsWithConditions),
+            DeleteBuilder::table("t")
+                .where_eq("id", &1)
+                .if_exists()
+                .if_eq("version", &1)
+                .build()
+        );
+    }
+
+    #[test]
+    fn test_delete_timestamp_marker() {
+        let qry = "DELETE FROM t USING TIMESTAMP ? WHERE id = :id";
+        let delete = parse_one::<Delete>(qry);
+        assert_eq!(qry, delete.to_string());
+        assert_eq!(Some(Operand::Param("?".to_string())), delete.timestamp);
+
+        let qry = "DELETE FROM t USING TIMESTAMP :ts WHERE id = 1";
+        assert_eq!(
+            Some(Operand::Param(":ts".to_string())),
+            parse_one::<Delete>(qry).timestamp
+        );
+
+        let qry = "DELETE FROM t USING TIMESTAMP -5 WHERE id = 1";
+        let delete = parse_one::<Delete>(qry);
+        assert_eq!(qry, delete.to_string());
+        assert_eq!(Ok(-5), delete.timestamp.unwrap().as_i64());
+    }
+}
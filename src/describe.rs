@@ -0,0 +1,200 @@
+use crate::common::FQName;
+use std::fmt::{Display, Formatter};
+
+/// the data for the cqlsh `DESCRIBE` (or `DESC`) command.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Describe {
+    /// the kind of thing described.
+    pub kind: DescribeKind,
+    /// the name of the thing described.  For `KEYSPACE` this is the keyspace name, it is
+    /// `None` when describing the current keyspace or a list (e.g. `TABLES`).
+    pub name: Option<FQName>,
+}
+
+impl Describe {
+    /// parses the text of a `DESCRIBE` command.  Returns `None` if the text is not a
+    /// `DESCRIBE` command.
+    pub fn parse(text: &str) -> Option<Describe> {
+        let text = text.trim().trim_end_matches(';').trim_end();
+        let mut words = text.split_whitespace();
+        if !words.next().map_or(false, |word| {
+            word.eq_ignore_ascii_case("DESCRIBE") || word.eq_ignore_ascii_case("DESC")
+        }) {
+            return None;
+        }
+        let first = words.next()?;
+        let kind = match first.to_uppercase().as_str() {
+            "CLUSTER" => DescribeKind::Cluster,
+            "SCHEMA" => DescribeKind::Schema,
+            "FULL" => {
+                if !words.next()?.eq_ignore_ascii_case("SCHEMA") {
+                    return None;
+                }
+                DescribeKind::FullSchema
+            }
+            "KEYSPACES" => DescribeKind::Keyspaces,
+            "KEYSPACE" => DescribeKind::Keyspace,
+            "TABLES" => DescribeKind::Tables,
+            "TABLE" | "COLUMNFAMILY" => DescribeKind::Table,
+            "TYPES" => DescribeKind::Types,
+            "TYPE" => DescribeKind::Type,
+            "FUNCTIONS" => DescribeKind::Functions,
+            "FUNCTION" => DescribeKind::Function,
+            "AGGREGATES" => DescribeKind::Aggregates,
+            "AGGREGATE" => DescribeKind::Aggregate,
+            "MATERIALIZED" => {
+                if !words.next()?.eq_ignore_ascii_case("VIEW") {
+                    return None;
+                }
+                DescribeKind::MaterializedView
+            }
+            "INDEX" => DescribeKind::Index,
+            _ => {
+                // `DESCRIBE name` describes whatever the name refers to.
+                return match words.next() {
+                    None => Some(Describe {
+                        kind: DescribeKind::Object,
+                        name: Some(FQName::from(first)),
+                    }),
+                    Some(_) => None,
+                };
+            }
+        };
+        let name = words.next().map(FQName::from);
+        let name_ok = if kind.has_name() {
+            name.is_some() || kind.name_optional()
+        } else {
+            name.is_none()
+        };
+        if !name_ok || words.next().is_some() {
+            return None;
+        }
+        Some(Describe { kind, name })
+    }
+}
+
+impl Display for Describe {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match (&self.kind, &self.name) {
+            (DescribeKind::Object, Some(name)) => write!(f, "DESCRIBE {}", name),
+            (kind, Some(name)) => write!(f, "DESCRIBE {} {}", kind, name),
+            (kind, None) => write!(f, "DESCRIBE {}", kind),
+        }
+    }
+}
+
+/// the kinds of thing that can be described.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum DescribeKind {
+    Cluster,
+    Schema,
+    FullSchema,
+    Keyspaces,
+    Keyspace,
+    Tables,
+    Table,
+    Types,
+    Type,
+    Functions,
+    Function,
+    Aggregates,
+    Aggregate,
+    MaterializedView,
+    Index,
+    /// `DESCRIBE name`, the server determines what the name refers to.
+    Object,
+}
+
+impl DescribeKind {
+    /// true if the kind describes a single named thing.
+    fn has_name(&self) -> bool {
+        !matches!(
+            self,
+            DescribeKind::Cluster
+                | DescribeKind::Schema
+                | DescribeKind::FullSchema
+                | DescribeKind::Keyspaces
+                | DescribeKind::Tables
+                | DescribeKind::Types
+                | DescribeKind::Functions
+                | DescribeKind::Aggregates
+        )
+    }
+
+    /// true if the name may be omitted, `DESCRIBE KEYSPACE` describes the current keyspace.
+    fn name_optional(&self) -> bool {
+        matches!(self, DescribeKind::Keyspace)
+    }
+}
+
+impl Display for DescribeKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            DescribeKind::Cluster => "CLUSTER",
+            DescribeKind::Schema => "SCHEMA",
+            DescribeKind::FullSchema => "FULL SCHEMA",
+            DescribeKind::Keyspaces => "KEYSPACES",
+            DescribeKind::Keyspace => "KEYSPACE",
+            DescribeKind::Tables => "TABLES",
+            DescribeKind::Table => "TABLE",
+            DescribeKind::Types => "TYPES",
+            DescribeKind::Type => "TYPE",
+            DescribeKind::Functions => "FUNCTIONS",
+            DescribeKind::Function => "FUNCTION",
+            DescribeKind::Aggregates => "AGGREGATES",
+            DescribeKind::Aggregate => "AGGREGATE",
+            DescribeKind::MaterializedView => "MATERIALIZED VIEW",
+            DescribeKind::Index => "INDEX",
+            DescribeKind::Object => "",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::FQName;
+    use crate::describe::{Describe, DescribeKind};
+
+    #[test]
+    fn test_describe_parse() {
+        let stmts = [
+            "DESCRIBE TABLES",
+            "describe keyspace ks;",
+            "DESC KEYSPACE",
+            "DESCRIBE TABLE ks.t",
+            "DESCRIBE FULL SCHEMA",
+            "DESCRIBE MATERIALIZED VIEW ks.v",
+            "DESCRIBE ks.t",
+        ];
+        let expected = [
+            "DESCRIBE TABLES",
+            "DESCRIBE KEYSPACE ks",
+            "DESCRIBE KEYSPACE",
+            "DESCRIBE TABLE ks.t",
+            "DESCRIBE FULL SCHEMA",
+            "DESCRIBE MATERIALIZED VIEW ks.v",
+            "DESCRIBE ks.t",
+        ];
+        for (stmt, expected) in stmts.iter().zip(expected.iter()) {
+            assert_eq!(*expected, Describe::parse(stmt).unwrap().to_string());
+        }
+        assert_eq!(
+            Some(Describe {
+                kind: DescribeKind::Keyspace,
+                name: Some(FQName::simple("ks")),
+            }),
+            Describe::parse("DESCRIBE KEYSPACE ks")
+        );
+        assert_eq!(
+            Some(Describe {
+                kind: DescribeKind::Tables,
+                name: None,
+            }),
+            Describe::parse("DESCRIBE TABLES")
+        );
+        assert_eq!(None, Describe::parse("SELECT * FROM t"));
+        assert_eq!(None, Describe::parse("DESCRIBE TABLE"));
+        assert_eq!(None, Describe::parse("DESCRIBE TABLES ks"));
+    }
+}
@@ -1,5 +1,5 @@
 use crate::begin_batch::BeginBatch;
-use crate::common::{FQName, Operand, TtlTimestamp};
+use crate::common::{FQName, Identifier, Operand, TtlTimestamp};
 use itertools::Itertools;
 use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter};
@@ -12,7 +12,7 @@ pub struct Insert {
     /// the table name
     pub table_name: FQName,
     /// an the list of of column names to insert into.
-    pub columns: Vec<String>,
+    pub columns: Vec<Identifier>,
     /// the `VALUES` to insert
     pub values: InsertValues,
     /// if set the timestamp for `USING TTL`
@@ -22,6 +22,22 @@ pub struct Insert {
 }
 
 impl Insert {
+    /// returns true if the insert is a lightweight transaction (`IF NOT EXISTS`).
+    pub fn is_lwt(&self) -> bool {
+        self.if_not_exists
+    }
+
+    /// returns true if executing the insert more than once has the same effect as executing
+    /// it once.  This is true unless the insert is a lightweight transaction or a value is a
+    /// function call such as `now()`.
+    pub fn is_idempotent(&self) -> bool {
+        !self.is_lwt()
+            && match &self.values {
+                InsertValues::Values(operands) => operands.iter().all(Operand::is_fixed_value),
+                InsertValues::Json(_) => true,
+            }
+    }
+
     /// return a sorted map of column names to Operands.
     pub fn get_value_map(&self) -> BTreeMap<String, &Operand> {
         let mut result = BTreeMap::new();
@@ -31,7 +47,7 @@ impl Insert {
                 // return an empty list
                 if self.columns.len() == operands.len() {
                     for (i, operand) in operands.iter().enumerate() {
-                        result.insert(self.columns[i].clone(), operand);
+                        result.insert(self.columns[i].to_string(), operand);
                     }
                 }
             }
@@ -39,7 +55,61 @@ impl Insert {
         }
         result
     }
+
+    /// return the column names paired with the values written to them, in statement order.
+    /// Returns `None` for the JSON form.  If the number of columns and values differ the
+    /// extra columns or values are ignored.  Quoted column names are returned without quotes.
+    pub fn get_column_values(&self) -> Option<Vec<(&str, &Operand)>> {
+        match &self.values {
+            InsertValues::Values(operands) => Some(
+                self.columns
+                    .iter()
+                    .map(|c| c.as_str())
+                    .zip(operands.iter())
+                    .collect(),
+            ),
+            InsertValues::Json(_) => None,
+        }
+    }
+
+    /// checks that the statement is valid.  Returns an error if the number of columns does
+    /// not match the number of values.
+    pub fn validate(&self) -> Result<(), InsertError> {
+        match &self.values {
+            InsertValues::Values(operands) if operands.len() != self.columns.len() => {
+                Err(InsertError::ColumnValueMismatch {
+                    columns: self.columns.len(),
+                    values: operands.len(),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// return the names of the columns that are explicitly set to `NULL`.  Columns that are
+    /// not listed in the statement are not written and so are not returned.
+    pub fn writes_null(&self) -> Vec<&str> {
+        self.get_column_values()
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|(column, value)| {
+                if *value == Operand::Null {
+                    Some(column)
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// return the names of the columns written by the statement.  For the JSON form these
+    /// are the columns listed in the statement, an opaque JSON document without a column list
+    /// yields an empty list.
+    pub fn columns_written(&self) -> Vec<&str> {
+        self.columns.iter().map(|c| c.as_str()).collect()
+    }
 }
+
 impl Display for Insert {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -49,7 +119,7 @@ impl Display for Insert {
                 .as_ref()
                 .map_or("".to_string(), |x| x.to_string()),
             self.table_name,
-            self.columns.join(", "),
+            self.columns.iter().join(", "),
             self.values,
             if self.if_not_exists {
                 " IF NOT EXISTS"
@@ -84,3 +154,345 @@ impl Display for InsertValues {
         }
     }
 }
+
+/// the problems found by `Insert::validate()`.
+#[derive(PartialEq, Debug, Clone)]
+pub enum InsertError {
+    /// the number of columns and the number of values differ.
+    ColumnValueMismatch { columns: usize, values: usize },
+}
+
+impl Display for InsertError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InsertError::ColumnValueMismatch { columns, values } => write!(
+                f,
+                "{} columns were specified but {} values were provided",
+                columns, values
+            ),
+        }
+    }
+}
+
+impl std::error::Error for InsertError {}
+
+/// builds `Insert` statements.
+#[derive(PartialEq, Debug, Clone)]
+pub struct InsertBuilder {
+    table_name: FQName,
+    columns: Vec<Identifier>,
+    values: Vec<Operand>,
+    json: Option<String>,
+    using_ttl: Option<TtlTimestamp>,
+    if_not_exists: bool,
+}
+
+impl InsertBuilder {
+    /// start building an insert into the table.
+    pub fn table(table_name: FQName) -> InsertBuilder {
+        InsertBuilder {
+            table_name,
+            columns: vec![],
+            values: vec![],
+            json: None,
+            using_ttl: None,
+            if_not_exists: false,
+        }
+    }
+
+    /// start building an insert into the table, either `table` or `keyspace.table`.  Table
+    /// and column names are written as in a statement, see `Identifier::from_cql()`.
+    pub fn into(table: &str) -> InsertBuilder {
+        InsertBuilder::table(FQName::from(table))
+    }
+
+    /// add a column and the value to insert into it.
+    pub fn value<T: Into<Operand>>(mut self, column: &str, value: T) -> InsertBuilder {
+        self.columns.push(Identifier::from_cql(column));
+        self.values.push(value.into());
+        self
+    }
+
+    /// add a column with a positional bind marker (`?`) as the value.
+    pub fn bind(self, column: &str) -> InsertBuilder {
+        self.value(column, Operand::Param("?".to_string()))
+    }
+
+    /// add a column with a named bind marker (`:name`) as the value.
+    pub fn bind_named(self, column: &str, name: &str) -> InsertBuilder {
+        self.value(column, Operand::Param(format!(":{}", name)))
+    }
+
+    /// add a column without a value.  Used with `json()`.
+    pub fn column(mut self, column: &str) -> InsertBuilder {
+        self.columns.push(Identifier::from_cql(column));
+        self
+    }
+
+    /// insert the JSON document rather than a list of values.
+    pub fn json(mut self, json: &str) -> InsertBuilder {
+        self.json = Some(Operand::from(json).to_string());
+        self
+    }
+
+    /// set the `USING TTL` value.
+    pub fn ttl(mut self, ttl: u64) -> InsertBuilder {
+        self.using_ttl
+            .get_or_insert(TtlTimestamp {
+                ttl: None,
+                timestamp: None,
+            })
+            .ttl = Some(Operand::from(&ttl));
+        self
+    }
+
+    /// set the `USING TIMESTAMP` value.
+    pub fn timestamp(mut self, timestamp: u64) -> InsertBuilder {
+        self.using_ttl
+            .get_or_insert(TtlTimestamp {
+                ttl: None,
+                timestamp: None,
+            })
+            .timestamp = Some(Operand::from(&timestamp));
+        self
+    }
+
+    /// add `IF NOT EXISTS` to the statement.
+    pub fn if_not_exists(mut self) -> InsertBuilder {
+        self.if_not_exists = true;
+        self
+    }
+
+    /// create the `Insert`.
+    pub fn build(self) -> Result<Insert, InsertBuildError> {
+        if self.columns.is_empty() {
+            return Err(InsertBuildError::NoColumns);
+        }
+        let values = match self.json {
+            Some(json) => {
+                if !self.values.is_empty() {
+                    return Err(InsertBuildError::ValuesWithJson);
+                }
+                InsertValues::Json(json)
+            }
+            None => {
+                if self.values.len() != self.columns.len() {
+                    return Err(InsertBuildError::MissingValues);
+                }
+                InsertValues::Values(self.values)
+            }
+        };
+        Ok(Insert {
+            begin_batch: None,
+            table_name: self.table_name,
+            columns: self.columns,
+            values,
+            using_ttl: self.using_ttl,
+            if_not_exists: self.if_not_exists,
+        })
+    }
+}
+
+/// the errors returned by `InsertBuilder::build()`.
+#[derive(PartialEq, Debug, Clone)]
+pub enum InsertBuildError {
+    /// no columns were specified.
+    NoColumns,
+    /// a column was added without a value and `json()` was not specified.
+    MissingValues,
+    /// values were added as well as a JSON document.
+    ValuesWithJson,
+}
+
+impl Display for InsertBuildError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InsertBuildError::NoColumns => write!(f, "an insert requires at least one column"),
+            InsertBuildError::MissingValues => write!(f, "every column requires a value"),
+            InsertBuildError::ValuesWithJson => {
+                write!(f, "values may not be specified with a JSON document")
+            }
+        }
+    }
+}
+
+impl std::error::Error for InsertBuildError {}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{FQName, Identifier, Operand, TtlTimestamp};
+    use crate::insert::{Insert, InsertBuildError, InsertBuilder, InsertError};
+    use crate::test_helpers::parse_one;
+
+    #[test]
+    fn test_insert_builder() {
+        assert_eq!(
+            parse_one::<Insert>(
+                "INSERT INTO ks.t (id, name) VALUES (5, 'bob') IF NOT EXISTS USING TTL 3600"
+            ),
+            InsertBuilder::into("ks.t")
+                .value("id", &5i64)
+                .value("name", "bob")
+                .ttl(3600)
+                .if_not_exists()
+                .build()
+                .unwrap()
+        );
+        assert_eq!(
+            parse_one::<Insert>(
+                "INSERT INTO t (id, name) VALUES (?, :name) USING TTL 5 AND TIMESTAMP 10"
+            ),
+            InsertBuilder::table(FQName::simple("t"))
+                .bind("id")
+                .bind_named("name", "name")
+                .timestamp(10)
+                .ttl(5)
+                .build()
+                .unwrap()
+        );
+        assert_eq!(
+            parse_one::<Insert>("INSERT INTO t (id, name) JSON '{\"id\": 5, \"name\": \"bob\"}'"),
+            InsertBuilder::table(FQName::simple("t"))
+                .column("id")
+                .column("name")
+                .json("{\"id\": 5, \"name\": \"bob\"}")
+                .build()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_get_column_values() {
+        let insert = parse_one::<Insert>("INSERT INTO t (id, name) VALUES (5, 'bob')");
+        let id = Operand::from(&5);
+        let name = Operand::from("bob");
+        assert_eq!(
+            Some(vec![("id", &id), ("name", &name)]),
+            insert.get_column_values()
+        );
+        assert_eq!(vec!["id", "name"], insert.columns_written());
+
+        let insert =
+            parse_one::<Insert>("INSERT INTO t (id, name) JSON '{\"id\": 5, \"name\": \"bob\"}'");
+        assert_eq!(None, insert.get_column_values());
+        assert_eq!(vec!["id", "name"], insert.columns_written());
+
+        let mut insert = parse_one::<Insert>("INSERT INTO t (id, name) VALUES (5, 'bob')");
+        insert.columns.push(Identifier::new("extra"));
+        assert_eq!(
+            Some(vec![("id", &id), ("name", &name)]),
+            insert.get_column_values()
+        );
+    }
+
+    #[test]
+    fn test_builder_names() {
+        let insert = InsertBuilder::into("ks.MyTable")
+            .value("MyCol", &1i64)
+            .value("\"Other\"", &2i64)
+            .value("my col", &3i64)
+            .build()
+            .unwrap();
+        assert_eq!(
+            "INSERT INTO ks.MyTable (MyCol, \"Other\", \"my col\") VALUES (1, 2, 3)",
+            insert.to_string()
+        );
+        assert_eq!(
+            parse_one::<Insert>(
+                "INSERT INTO ks.MyTable (MyCol, \"Other\", \"my col\") VALUES (1, 2, 3)"
+            ),
+            insert
+        );
+    }
+
+    #[test]
+    fn test_quoted_columns() {
+        let insert =
+            parse_one::<Insert>("INSERT INTO t (\"Order\", \"user-id\", name) VALUES (?, ?, ?)");
+        assert_eq!(vec!["Order", "user-id", "name"], insert.columns_written());
+        assert!(insert.columns[0].quoted);
+        assert!(!insert.columns[2].quoted);
+        assert_eq!(
+            "INSERT INTO t (\"Order\", \"user-id\", name) VALUES (?, ?, ?)",
+            insert.to_string()
+        );
+    }
+
+    #[test]
+    fn test_writes_null() {
+        let insert = parse_one::<Insert>("INSERT INTO t (a, b, c) VALUES (1, null, 'null')");
+        assert_eq!(
+            Some(vec![
+                ("a", &Operand::from(&1)),
+                ("b", &Operand::Null),
+                ("c", &Operand::from("null"))
+            ]),
+            insert.get_column_values()
+        );
+        assert_eq!(vec!["b"], insert.writes_null());
+        assert_eq!(
+            "INSERT INTO t (a, b, c) VALUES (1, NULL, 'null')",
+            insert.to_string()
+        );
+        assert!(parse_one::<Insert>("INSERT INTO t (a) VALUES (1)")
+            .writes_null()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(
+            Ok(()),
+            parse_one::<Insert>("INSERT INTO t (a, b) VALUES (1, 2)").validate()
+        );
+        assert_eq!(
+            Err(InsertError::ColumnValueMismatch {
+                columns: 3,
+                values: 2
+            }),
+            parse_one::<Insert>("INSERT INTO t (a, b, c) VALUES (1, 2)").validate()
+        );
+        assert_eq!(
+            Ok(()),
+            parse_one::<Insert>("INSERT INTO t (a, b) JSON '{}'").validate()
+        );
+    }
+
+    #[test]
+    fn test_using_bind_markers() {
+        assert_eq!(
+            Some(TtlTimestamp {
+                ttl: Some(Operand::Param("?".to_string())),
+                timestamp: Some(Operand::Param(":ts".to_string())),
+            }),
+            parse_one::<Insert>("INSERT INTO t (a) VALUES (1) USING TTL ? AND TIMESTAMP :ts")
+                .using_ttl
+        );
+    }
+
+    #[test]
+    fn test_insert_builder_errors() {
+        assert_eq!(
+            InsertBuilder::table(FQName::new("ks", "t")),
+            InsertBuilder::into("ks.t")
+        );
+        assert_eq!(
+            Err(InsertBuildError::NoColumns),
+            InsertBuilder::table(FQName::simple("t")).build()
+        );
+        assert_eq!(
+            Err(InsertBuildError::MissingValues),
+            InsertBuilder::table(FQName::simple("t"))
+                .value("id", &5)
+                .column("name")
+                .build()
+        );
+        assert_eq!(
+            Err(InsertBuildError::ValuesWithJson),
+            InsertBuilder::table(FQName::simple("t"))
+                .value("id", &5)
+                .json("{}")
+                .build()
+        );
+    }
+}
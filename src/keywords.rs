@@ -0,0 +1,108 @@
+use once_cell::sync::Lazy;
+use std::collections::HashSet;
+
+/// the reserved CQL keywords, these may only be used as identifiers when quoted.
+const RESERVED_KEYWORDS: &[&str] = &[
+    "ADD",
+    "ALLOW",
+    "ALTER",
+    "AND",
+    "APPLY",
+    "ASC",
+    "AUTHORIZE",
+    "BATCH",
+    "BEGIN",
+    "BY",
+    "COLUMNFAMILY",
+    "CREATE",
+    "DEFAULT",
+    "DELETE",
+    "DESC",
+    "DESCRIBE",
+    "DROP",
+    "ENTRIES",
+    "EXECUTE",
+    "FROM",
+    "FULL",
+    "GRANT",
+    "IF",
+    "IN",
+    "INDEX",
+    "INFINITY",
+    "INSERT",
+    "INTO",
+    "IS",
+    "KEYSPACE",
+    "LIMIT",
+    "MATERIALIZED",
+    "MBEAN",
+    "MBEANS",
+    "MODIFY",
+    "NAN",
+    "NORECURSIVE",
+    "NOT",
+    "NULL",
+    "OF",
+    "ON",
+    "OR",
+    "ORDER",
+    "PRIMARY",
+    "RENAME",
+    "REPLACE",
+    "REVOKE",
+    "SCHEMA",
+    "SELECT",
+    "SET",
+    "TABLE",
+    "TO",
+    "TOKEN",
+    "TRUNCATE",
+    "UNLOGGED",
+    "UNSET",
+    "UPDATE",
+    "USE",
+    "USING",
+    "VIEW",
+    "WHERE",
+    "WITH",
+];
+
+static KEYWORDS: Lazy<HashSet<&'static str>> =
+    Lazy::new(|| RESERVED_KEYWORDS.iter().copied().collect());
+
+/// returns the reserved CQL keywords in upper case.
+pub fn cql_keywords() -> &'static HashSet<&'static str> {
+    &KEYWORDS
+}
+
+/// returns true if the name is a reserved CQL keyword, ignoring case.
+pub fn is_reserved_keyword(name: &str) -> bool {
+    KEYWORDS.contains(name.to_uppercase().as_str())
+}
+
+/// returns the name wrapped in double quotes if it is a reserved keyword, otherwise the name
+/// unchanged.  The quoted name is case sensitive so `name` should be the stored (usually
+/// lower case) form.
+pub fn quote_if_reserved(name: &str) -> String {
+    if is_reserved_keyword(name) {
+        format!("\"{}\"", name)
+    } else {
+        name.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::keywords::{cql_keywords, is_reserved_keyword, quote_if_reserved};
+
+    #[test]
+    fn test_keywords() {
+        assert!(cql_keywords().contains("SELECT"));
+        assert!(is_reserved_keyword("select"));
+        assert!(is_reserved_keyword("Table"));
+        assert!(!is_reserved_keyword("ttl"));
+        assert!(!is_reserved_keyword("col"));
+        assert_eq!("\"select\"", quote_if_reserved("select"));
+        assert_eq!("col", quote_if_reserved("col"));
+    }
+}
@@ -3,6 +3,7 @@ pub mod alter_column;
 pub mod alter_materialized_view;
 pub mod alter_table;
 pub mod alter_type;
+pub mod batch;
 pub mod begin_batch;
 pub mod cassandra_ast;
 pub mod cassandra_statement;
@@ -18,9 +19,14 @@ pub mod create_trigger;
 pub mod create_type;
 pub mod create_user;
 pub mod delete;
+pub mod describe;
 pub mod drop_trigger;
 pub mod insert;
+pub mod keywords;
 pub mod list_role;
 pub mod role_common;
 pub mod select;
+pub mod table_options;
+#[cfg(test)]
+mod test_helpers;
 pub mod update;
@@ -1,3 +1,4 @@
+use crate::common::Operand;
 use itertools::Itertools;
 use std::fmt::{Display, Formatter};
 
@@ -6,8 +7,8 @@ use std::fmt::{Display, Formatter};
 pub struct RoleCommon {
     /// the name of the role
     pub name: String,
-    /// if specified the password for the role
-    pub password: Option<String>,
+    /// if specified the password for the role, normally a quoted constant.
+    pub password: Option<Operand>,
     /// if specified then the user is explicitly noted as `SUPERUER` or `NOSUPERUSER`
     pub superuser: Option<bool>,
     /// if specified the user LOGIN option is specified
@@ -18,6 +19,30 @@ pub struct RoleCommon {
     pub if_not_exists: bool,
 }
 
+impl RoleCommon {
+    /// replaces the password, if any, with `'*****'` so that the statement can be logged.
+    pub fn redact_password(&mut self) {
+        if let Some(password) = &mut self.password {
+            *password = Operand::from("*****");
+        }
+    }
+
+    /// redacts the password and the values of the `OPTIONS` whose names mention a password,
+    /// secret or token.
+    pub fn redact(&mut self) {
+        self.redact_password();
+        for (key, value) in &mut self.options {
+            let key = Operand::unescape(key).to_lowercase();
+            if ["password", "secret", "token"]
+                .iter()
+                .any(|word| key.contains(word))
+            {
+                *value = Operand::from("*****").to_string();
+            }
+        }
+    }
+}
+
 impl Display for RoleCommon {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let mut with = vec![];
@@ -1,4 +1,5 @@
-use crate::common::{FQName, OrderClause, RelationElement};
+use crate::cassandra_statement::CassandraStatement;
+use crate::common::{FQName, Identifier, Operand, OrderClause, RelationElement};
 use itertools::Itertools;
 use std::fmt::{Display, Formatter};
 
@@ -15,12 +16,17 @@ pub struct Select {
     pub columns: Vec<SelectElement>,
     /// the where clause
     pub where_clause: Vec<RelationElement>,
-    /// the optional ordering
-    pub order: Option<OrderClause>,
+    /// the `GROUP BY` columns, empty if there is no `GROUP BY` clause
+    #[cfg(feature = "dse")]
+    pub group_by: Vec<String>,
+    /// the ordering, empty if there is no `ORDER BY` clause
+    pub order: Vec<OrderClause>,
+    /// the number of items to return from each partition
+    pub per_partition_limit: Option<i32>,
     /// the number of items to return
     pub limit: Option<i32>,
-    /// if true ALLOW FILTERING is displayed
-    pub filtering: bool,
+    /// if true `ALLOW FILTERING` is displayed at the end of the statement
+    pub allow_filtering: bool,
 }
 
 impl Select {
@@ -46,21 +52,110 @@ impl Select {
         self.columns
             .iter()
             .map(|e| match e {
-                SelectElement::Column(named) => {
-                    named.alias.clone().unwrap_or_else(|| named.name.clone())
-                }
+                SelectElement::Column(named) => named
+                    .alias
+                    .clone()
+                    .unwrap_or_else(|| named.name.as_str().to_string()),
                 _ => "".to_string(),
             })
             .filter(|e| !e.as_str().eq(""))
             .collect()
     }
+
+    /// return true if the select only returns a row count.  That is, the only element
+    /// selected is `count(*)` or `count(1)`.  `count(column)` counts the non-null values of
+    /// the column and so is not a row count.
+    pub fn is_count_star(&self) -> bool {
+        match self.columns.as_slice() {
+            [SelectElement::Count(count)] => count.is_row_count(),
+            _ => false,
+        }
+    }
+
+    /// return true if the select returns all the columns, `SELECT *`.
+    pub fn is_star(&self) -> bool {
+        matches!(self.columns.as_slice(), [SelectElement::Star])
+    }
+
+    /// returns a copy of the select with the column `old` renamed to `new` in the selected
+    /// columns, the `count` argument, the `WHERE` clause and the `ORDER BY` clause.  Names
+    /// are compared ignoring case.  Column references within function calls are not
+    /// changed.  `new` is written as in a statement, see `Identifier::from_cql()`.  Returns an
+    /// error if `old` is not referenced, so stale queries can be found.
+    pub fn rename_column(&self, old: &str, new: &str) -> Result<Select, RenameError> {
+        let matches = |name: &str| Identifier::parse(name).as_str().eq_ignore_ascii_case(old);
+        let replacement = Identifier::from_cql(new);
+        let mut result = self.clone();
+        let mut found = false;
+        for element in result.columns.iter_mut() {
+            match element {
+                SelectElement::Column(Named { name, .. })
+                | SelectElement::Count(Count {
+                    argument: CountArgument::Column(name),
+                    ..
+                }) if matches(name.as_str()) => {
+                    *name = replacement.clone();
+                    found = true;
+                }
+                _ => {}
+            }
+        }
+        for relation in result.where_clause.iter_mut() {
+            found |= Select::rename_operand(&mut relation.obj, &matches, &replacement);
+            found |= Select::rename_operand(&mut relation.value, &matches, &replacement);
+        }
+        #[cfg(feature = "dse")]
+        for name in result.group_by.iter_mut() {
+            if matches(name) {
+                *name = replacement.to_string();
+                found = true;
+            }
+        }
+        for order in result.order.iter_mut() {
+            if matches(&order.name) {
+                order.name = replacement.to_string();
+                found = true;
+            }
+        }
+        if found {
+            Ok(result)
+        } else {
+            Err(RenameError::ColumnNotFound(old.to_string()))
+        }
+    }
+
+    /// renames the column operand, or the columns of a tuple such as `(c1, c2) > (1, 2)`.
+    /// Returns true if a column was renamed.
+    fn rename_operand<F>(operand: &mut Operand, matches: &F, replacement: &Identifier) -> bool
+    where
+        F: Fn(&str) -> bool,
+    {
+        match operand {
+            Operand::Column(name) if matches(name.as_str()) => {
+                *name = replacement.clone();
+                true
+            }
+            Operand::Tuple(operands) => operands.iter_mut().fold(false, |found, operand| {
+                Select::rename_operand(operand, matches, replacement) | found
+            }),
+            _ => false,
+        }
+    }
 }
 
 impl Display for Select {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        #[cfg(feature = "dse")]
+        let group_by = if !self.group_by.is_empty() {
+            format!(" GROUP BY {}", self.group_by.join(", "))
+        } else {
+            "".to_string()
+        };
+        #[cfg(not(feature = "dse"))]
+        let group_by = "";
         write!(
             f,
-            "SELECT {}{}{} FROM {}{}{}{}{}",
+            "SELECT {}{}{} FROM {}{}{}{}{}{}{}",
             if self.distinct { "DISTINCT " } else { "" },
             if self.json { "JSON " } else { "" },
             self.columns.iter().join(", "),
@@ -70,12 +165,17 @@ impl Display for Select {
             } else {
                 "".to_string()
             },
-            self.order
-                .as_ref()
-                .map_or("".to_string(), |x| format!(" ORDER BY {}", x)),
+            group_by,
+            if !self.order.is_empty() {
+                format!(" ORDER BY {}", self.order.iter().join(", "))
+            } else {
+                "".to_string()
+            },
+            self.per_partition_limit
+                .map_or("".to_string(), |x| format!(" PER PARTITION LIMIT {}", x)),
             self.limit
                 .map_or("".to_string(), |x| format!(" LIMIT {}", x)),
-            if self.filtering {
+            if self.allow_filtering {
                 " ALLOW FILTERING"
             } else {
                 ""
@@ -91,22 +191,95 @@ pub enum SelectElement {
     Star,
     /// a named column.  May have an alias specified.
     Column(Named),
-    /// a named column.  May have an alias specified.
-    Function(Named),
+    /// a function call.  May have an alias specified.
+    Function(FunctionCall),
+    /// a `count` function call.
+    Count(Count),
 }
 
 impl Display for SelectElement {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             SelectElement::Star => write!(f, "*"),
-            SelectElement::Column(named) | SelectElement::Function(named) => write!(f, "{}", named),
+            SelectElement::Column(named) => write!(f, "{}", named),
+            SelectElement::Function(function) => write!(f, "{}", function),
+            SelectElement::Count(count) => write!(f, "{}", count),
         }
     }
 }
 
+/// a function call other than `count`.
 #[derive(PartialEq, Debug, Clone)]
-pub struct Named {
+pub struct FunctionCall {
+    /// the text of the function call as written in the statement.
+    pub text: String,
+    /// the alias, if any.
+    pub alias: Option<String>,
+}
+
+impl Display for FunctionCall {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.alias {
+            None => write!(f, "{}", self.text),
+            Some(a) => write!(f, "{} AS {}", self.text, a),
+        }
+    }
+}
+
+/// a call to the `count` function.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Count {
+    /// the function name as written in the statement.
     pub name: String,
+    /// the argument to the function.
+    pub argument: CountArgument,
+    /// the alias, if any.
+    pub alias: Option<String>,
+}
+
+impl Count {
+    /// return true if the count is of rows (`count(*)` or `count(1)`) rather than of the
+    /// non-null values of a column.
+    pub fn is_row_count(&self) -> bool {
+        !matches!(self.argument, CountArgument::Column(_))
+    }
+}
+
+impl Display for Count {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}({})", self.name, self.argument)?;
+        match &self.alias {
+            None => Ok(()),
+            Some(a) => write!(f, " AS {}", a),
+        }
+    }
+}
+
+/// the argument to a `count` function.
+#[derive(PartialEq, Debug, Clone)]
+pub enum CountArgument {
+    /// `count(*)`
+    Star,
+    /// `count(1)`
+    One,
+    /// `count(column)`
+    Column(Identifier),
+}
+
+impl Display for CountArgument {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CountArgument::Star => write!(f, "*"),
+            CountArgument::One => write!(f, "1"),
+            CountArgument::Column(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Named {
+    /// the column name.
+    pub name: Identifier,
     pub alias: Option<String>,
 }
 
@@ -114,7 +287,7 @@ pub struct Named {
 impl Named {
     pub fn alias_or_name(&self) -> &str {
         match &self.alias {
-            None => &self.name,
+            None => self.name.as_str(),
             Some(alias) => alias,
         }
     }
@@ -129,9 +302,178 @@ impl Display for Named {
     }
 }
 
+/// builds `SELECT` statements.
+#[derive(PartialEq, Debug, Clone)]
+pub struct QueryBuilder {
+    columns: Vec<SelectElement>,
+    table_name: Option<FQName>,
+    where_clause: Vec<RelationElement>,
+    order: Vec<OrderClause>,
+    per_partition_limit: Option<i32>,
+    limit: Option<i32>,
+    allow_filtering: bool,
+}
+
+impl QueryBuilder {
+    /// start building a select of the columns.  An empty list selects `*`.  Table and column
+    /// names are written as in a statement, see `Identifier::from_cql()`.
+    pub fn select(columns: &[&str]) -> QueryBuilder {
+        QueryBuilder {
+            columns: if columns.is_empty() {
+                vec![SelectElement::Star]
+            } else {
+                columns
+                    .iter()
+                    .map(|column| {
+                        SelectElement::Column(Named {
+                            name: Identifier::from_cql(column),
+                            alias: None,
+                        })
+                    })
+                    .collect()
+            },
+            table_name: None,
+            where_clause: vec![],
+            order: vec![],
+            per_partition_limit: None,
+            limit: None,
+            allow_filtering: false,
+        }
+    }
+
+    /// set the table to select from, either `table` or `keyspace.table`.
+    pub fn from(mut self, table: &str) -> QueryBuilder {
+        self.table_name = Some(FQName::from(table));
+        self
+    }
+
+    /// add `column = value` to the where clause.
+    pub fn where_eq<T: Into<Operand>>(mut self, column: &str, value: T) -> QueryBuilder {
+        self.where_clause.push(RelationElement::from_column_eq(
+            Identifier::from_cql(column),
+            value.into(),
+        ));
+        self
+    }
+
+    /// add `column IN (values)` to the where clause.
+    pub fn where_in<T: Into<Operand>>(mut self, column: &str, values: Vec<T>) -> QueryBuilder {
+        self.where_clause.push(RelationElement::from_column_in(
+            Identifier::from_cql(column),
+            values.into_iter().map(|v| v.into()).collect(),
+        ));
+        self
+    }
+
+    /// add `column < value` to the where clause.
+    pub fn where_lt<T: Into<Operand>>(mut self, column: &str, value: T) -> QueryBuilder {
+        self.where_clause.push(RelationElement::from_column_lt(
+            Identifier::from_cql(column),
+            value.into(),
+        ));
+        self
+    }
+
+    /// add `column > value` to the where clause.
+    pub fn where_gt<T: Into<Operand>>(mut self, column: &str, value: T) -> QueryBuilder {
+        self.where_clause.push(RelationElement::from_column_gt(
+            Identifier::from_cql(column),
+            value.into(),
+        ));
+        self
+    }
+
+    /// add the column to the `ORDER BY` clause.
+    pub fn order_by(mut self, column: &str, desc: bool) -> QueryBuilder {
+        self.order.push(OrderClause {
+            name: Identifier::from_cql(column).to_string(),
+            desc,
+        });
+        self
+    }
+
+    /// set the `PER PARTITION LIMIT` value.
+    pub fn per_partition_limit(mut self, limit: i32) -> QueryBuilder {
+        self.per_partition_limit = Some(limit);
+        self
+    }
+
+    /// set the `LIMIT` value.
+    pub fn limit(mut self, limit: i32) -> QueryBuilder {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// add `ALLOW FILTERING` to the statement.
+    pub fn allow_filtering(mut self) -> QueryBuilder {
+        self.allow_filtering = true;
+        self
+    }
+
+    /// create the `SELECT` statement.
+    pub fn build(self) -> Result<CassandraStatement, BuildError> {
+        let table_name = self.table_name.ok_or(BuildError::NoTable)?;
+        Ok(CassandraStatement::Select(Select {
+            distinct: false,
+            json: false,
+            table_name,
+            columns: self.columns,
+            where_clause: self.where_clause,
+            #[cfg(feature = "dse")]
+            group_by: vec![],
+            order: self.order,
+            per_partition_limit: self.per_partition_limit,
+            limit: self.limit,
+            allow_filtering: self.allow_filtering,
+        }))
+    }
+}
+
+/// the errors returned by `QueryBuilder::build()`.
+#[derive(PartialEq, Debug, Clone)]
+pub enum BuildError {
+    /// no `FROM` table was specified.
+    NoTable,
+}
+
+impl Display for BuildError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuildError::NoTable => write!(f, "a select requires a FROM table"),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+/// the errors returned by `Select::rename_column()`.
+#[derive(PartialEq, Debug, Clone)]
+pub enum RenameError {
+    /// the column is not referenced by the statement.
+    ColumnNotFound(String),
+}
+
+impl Display for RenameError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RenameError::ColumnNotFound(name) => {
+                write!(f, "column {} is not referenced by the select", name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RenameError {}
+
 #[cfg(test)]
 mod tests {
-    use crate::select::{Named, SelectElement};
+    use crate::cassandra_ast::CassandraAST;
+    use crate::common::Identifier;
+    use crate::select::{
+        BuildError, Count, CountArgument, FunctionCall, Named, QueryBuilder, RenameError, Select,
+        SelectElement,
+    };
+    use crate::test_helpers::parse_one;
 
     #[test]
     fn test_select_element_display() {
@@ -139,15 +481,15 @@ mod tests {
         assert_eq!(
             "col",
             SelectElement::Column(Named {
-                name: "col".to_string(),
+                name: Identifier::new("col"),
                 alias: None
             })
             .to_string()
         );
         assert_eq!(
             "func",
-            SelectElement::Function(Named {
-                name: "func".to_string(),
+            SelectElement::Function(FunctionCall {
+                text: "func".to_string(),
                 alias: None
             })
             .to_string()
@@ -155,18 +497,124 @@ mod tests {
         assert_eq!(
             "col AS alias",
             SelectElement::Column(Named {
-                name: "col".to_string(),
+                name: Identifier::new("col"),
                 alias: Some("alias".to_string())
             })
             .to_string()
         );
         assert_eq!(
             "func AS alias",
-            SelectElement::Function(Named {
-                name: "func".to_string(),
+            SelectElement::Function(FunctionCall {
+                text: "func".to_string(),
                 alias: Some("alias".to_string())
             })
             .to_string()
         );
     }
+
+    #[test]
+    fn test_count_display() {
+        let count = |name: &str, argument: CountArgument, alias: Option<&str>| {
+            SelectElement::Count(Count {
+                name: name.to_string(),
+                argument,
+                alias: alias.map(|a| a.to_string()),
+            })
+            .to_string()
+        };
+        assert_eq!("count(*)", count("count", CountArgument::Star, None));
+        assert_eq!("COUNT(1)", count("COUNT", CountArgument::One, None));
+        assert_eq!(
+            "count(col) AS total",
+            count(
+                "count",
+                CountArgument::Column(Identifier::new("col")),
+                Some("total")
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_count() {
+        let select = parse_one::<Select>("SELECT count(\"Col\") AS n, count(a, b) FROM t");
+        assert_eq!(
+            vec![
+                SelectElement::Count(Count {
+                    name: "count".to_string(),
+                    argument: CountArgument::Column(Identifier::parse("\"Col\"")),
+                    alias: Some("n".to_string()),
+                }),
+                SelectElement::Function(FunctionCall {
+                    text: "count(a, b)".to_string(),
+                    alias: None,
+                }),
+            ],
+            select.columns
+        );
+    }
+
+    #[test]
+    fn test_query_builder() {
+        let stmt = QueryBuilder::select(&["id", "name"])
+            .from("ks.t")
+            .where_eq("id", &1)
+            .limit(100)
+            .build()
+            .unwrap();
+        assert_eq!(
+            "SELECT id, name FROM ks.t WHERE id = 1 LIMIT 100",
+            stmt.to_string()
+        );
+        assert_eq!(
+            CassandraAST::new(&stmt.to_string()).statements[0].statement,
+            stmt
+        );
+
+        let stmt = QueryBuilder::select(&[])
+            .from("t")
+            .where_in("id", vec![&1, &2])
+            .where_gt("c", &3)
+            .where_lt("c", &9)
+            .order_by("c", true)
+            .per_partition_limit(2)
+            .limit(10)
+            .allow_filtering()
+            .build()
+            .unwrap();
+        assert_eq!(
+            "SELECT * FROM t WHERE id IN (1, 2) AND c > 3 AND c < 9 ORDER BY c DESC PER PARTITION LIMIT 2 LIMIT 10 ALLOW FILTERING",
+            stmt.to_string()
+        );
+
+        assert_eq!(
+            Err(BuildError::NoTable),
+            QueryBuilder::select(&["id"]).build()
+        );
+    }
+
+    #[test]
+    fn test_rename_column() {
+        let original = parse_one::<Select>("SELECT id, Name AS n, count(name) FROM ks.t WHERE name = 'x' AND (c, NAME) > (1, 2) ORDER BY name DESC");
+        let renamed = original.rename_column("name", "full_name").unwrap();
+        assert_eq!(
+            "SELECT id, full_name AS n, count(full_name) FROM ks.t WHERE full_name = 'x' AND (c, full_name) > (1, 2) ORDER BY full_name DESC",
+            renamed.to_string()
+        );
+        // the original is unchanged.
+        assert_eq!(
+            "SELECT id, Name AS n, count(name) FROM ks.t WHERE name = 'x' AND (c, NAME) > (1, 2) ORDER BY name DESC",
+            original.to_string()
+        );
+        assert_eq!(
+            "SELECT * FROM t WHERE k = 1 AND v > 2",
+            parse_one::<Select>("SELECT * FROM t WHERE k = 1 AND val > 2")
+                .rename_column("VAL", "v")
+                .unwrap()
+                .to_string()
+        );
+        assert_eq!(
+            Err(RenameError::ColumnNotFound("missing".to_string())),
+            original.rename_column("missing", "other")
+        );
+    }
 }
@@ -0,0 +1,545 @@
+use crate::common::{OptionValue, WithItem};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// returns the value of the `WITH` option.  The key matches case-insensitively.
+pub(crate) fn find_option<'a>(items: &'a [WithItem], key: &str) -> Option<&'a OptionValue> {
+    items.iter().find_map(|item| match item {
+        WithItem::Option { key: k, value } if k.eq_ignore_ascii_case(key) => Some(value),
+        _ => None,
+    })
+}
+
+/// parses the value into the slot.  Returns false, leaving the slot unchanged, if the value
+/// does not parse.
+fn parse_into<T: FromStr>(slot: &mut Option<T>, value: &str) -> bool {
+    match value.parse() {
+        Ok(parsed) => {
+            *slot = Some(parsed);
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// splits the entries of a map option into the known entries and the others.  `known` is
+/// called with the unquoted lowercase key and the unquoted value of each entry and returns
+/// true if it used the entry, the other entries are returned as written.  Returns `None` if
+/// the value is not a map.
+fn split_entries<F>(value: &OptionValue, mut known: F) -> Option<Vec<(String, String)>>
+where
+    F: FnMut(&str, &str) -> bool,
+{
+    match value {
+        OptionValue::Map(items) => Some(
+            items
+                .iter()
+                .filter(|(k, v)| {
+                    !known(
+                        &OptionValue::unquote(k).to_lowercase(),
+                        OptionValue::unquote(v),
+                    )
+                })
+                .cloned()
+                .collect(),
+        ),
+        OptionValue::Literal(_) => None,
+    }
+}
+
+/// the `compaction` strategy with the sub-options specific to it.
+#[derive(PartialEq, Debug, Clone)]
+pub enum CompactionStrategy {
+    /// `SizeTieredCompactionStrategy` (STCS).
+    SizeTiered {
+        min_threshold: Option<u32>,
+        max_threshold: Option<u32>,
+    },
+    /// `LeveledCompactionStrategy` (LCS).
+    Leveled { sstable_size_in_mb: Option<u32> },
+    /// `TimeWindowCompactionStrategy` (TWCS).
+    TimeWindow {
+        compaction_window_unit: Option<String>,
+        compaction_window_size: Option<u32>,
+    },
+    /// `UnifiedCompactionStrategy` (UCS).
+    Unified { scaling_parameters: Option<String> },
+    /// any other strategy, contains the class as written without quotes.
+    Other(String),
+}
+
+/// a typed view of the `compaction` table option.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Compaction {
+    /// the strategy named by the `class` entry.
+    pub strategy: CompactionStrategy,
+    /// the `enabled` entry.
+    pub enabled: Option<bool>,
+    /// the entries that are not otherwise represented, as written in the statement.
+    pub other: Vec<(String, String)>,
+}
+
+impl Compaction {
+    /// creates the view from the `compaction` option value.  The class may be the short name
+    /// or the fully qualified name.  Returns `None` if the value is not a map or has no
+    /// `class` entry.
+    pub fn from_option(value: &OptionValue) -> Option<Compaction> {
+        let class = value.as_map_entry("class")?;
+        let mut strategy = match class.rsplit('.').next().unwrap_or(class) {
+            "SizeTieredCompactionStrategy" => CompactionStrategy::SizeTiered {
+                min_threshold: None,
+                max_threshold: None,
+            },
+            "LeveledCompactionStrategy" => CompactionStrategy::Leveled {
+                sstable_size_in_mb: None,
+            },
+            "TimeWindowCompactionStrategy" => CompactionStrategy::TimeWindow {
+                compaction_window_unit: None,
+                compaction_window_size: None,
+            },
+            "UnifiedCompactionStrategy" => CompactionStrategy::Unified {
+                scaling_parameters: None,
+            },
+            _ => CompactionStrategy::Other(class.to_string()),
+        };
+        let mut enabled = None;
+        let other = split_entries(value, |key, value| match (key, &mut strategy) {
+            ("class", _) => true,
+            ("enabled", _) => parse_into(&mut enabled, value),
+            ("min_threshold", CompactionStrategy::SizeTiered { min_threshold, .. }) => {
+                parse_into(min_threshold, value)
+            }
+            ("max_threshold", CompactionStrategy::SizeTiered { max_threshold, .. }) => {
+                parse_into(max_threshold, value)
+            }
+            ("sstable_size_in_mb", CompactionStrategy::Leveled { sstable_size_in_mb }) => {
+                parse_into(sstable_size_in_mb, value)
+            }
+            (
+                "compaction_window_unit",
+                CompactionStrategy::TimeWindow {
+                    compaction_window_unit,
+                    ..
+                },
+            ) => parse_into(compaction_window_unit, value),
+            (
+                "compaction_window_size",
+                CompactionStrategy::TimeWindow {
+                    compaction_window_size,
+                    ..
+                },
+            ) => parse_into(compaction_window_size, value),
+            ("scaling_parameters", CompactionStrategy::Unified { scaling_parameters }) => {
+                parse_into(scaling_parameters, value)
+            }
+            _ => false,
+        })?;
+        Some(Compaction {
+            strategy,
+            enabled,
+            other,
+        })
+    }
+}
+
+/// a typed view of the `compression` table option.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct Compression {
+    /// the compressor named by the `class` (or older `sstable_compression`) entry.
+    pub class: Option<String>,
+    /// the `chunk_length_in_kb` (or older `chunk_length_kb`) entry.
+    pub chunk_length_in_kb: Option<u32>,
+    /// the `crc_check_chance` entry.
+    pub crc_check_chance: Option<f64>,
+    /// the `enabled` entry.
+    pub enabled: Option<bool>,
+    /// the entries that are not otherwise represented, as written in the statement.
+    pub other: Vec<(String, String)>,
+}
+
+impl Compression {
+    /// creates the view from the `compression` option value.  Returns `None` if the value is
+    /// not a map.
+    pub fn from_option(value: &OptionValue) -> Option<Compression> {
+        let mut result = Compression::default();
+        result.other = split_entries(value, |key, value| match key {
+            "class" | "sstable_compression" => parse_into(&mut result.class, value),
+            "chunk_length_in_kb" | "chunk_length_kb" => {
+                parse_into(&mut result.chunk_length_in_kb, value)
+            }
+            "crc_check_chance" => parse_into(&mut result.crc_check_chance, value),
+            "enabled" => parse_into(&mut result.enabled, value),
+            _ => false,
+        })?;
+        Some(result)
+    }
+}
+
+/// the number of rows per partition to cache.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum RowsPerPartition {
+    All,
+    None,
+    Count(u32),
+}
+
+impl FromStr for RowsPerPartition {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("ALL") {
+            Ok(RowsPerPartition::All)
+        } else if s.eq_ignore_ascii_case("NONE") {
+            Ok(RowsPerPartition::None)
+        } else {
+            s.parse().map(RowsPerPartition::Count).map_err(|_| ())
+        }
+    }
+}
+
+/// a typed view of the `caching` table option.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct Caching {
+    /// the `keys` entry, true for `ALL` and false for `NONE`.
+    pub keys: Option<bool>,
+    /// the `rows_per_partition` entry.
+    pub rows_per_partition: Option<RowsPerPartition>,
+    /// the entries that are not otherwise represented, as written in the statement.
+    pub other: Vec<(String, String)>,
+}
+
+impl Caching {
+    /// creates the view from the `caching` option value.  Returns `None` if the value is not
+    /// a map.
+    pub fn from_option(value: &OptionValue) -> Option<Caching> {
+        let mut result = Caching::default();
+        result.other = split_entries(value, |key, value| match key {
+            "keys" if value.eq_ignore_ascii_case("ALL") => {
+                result.keys = Some(true);
+                true
+            }
+            "keys" if value.eq_ignore_ascii_case("NONE") => {
+                result.keys = Some(false);
+                true
+            }
+            "rows_per_partition" => parse_into(&mut result.rows_per_partition, value),
+            _ => false,
+        })?;
+        Some(result)
+    }
+}
+
+/// returns the value as an integer.  Only a plain integer literal is accepted, as the server
+/// does, so `'3600'`, `3600.0` and `1e3` return `None`.
+fn integer_value(value: &OptionValue) -> Option<i64> {
+    value.as_literal()?.parse::<i64>().ok()
+}
+
+/// returns the value of the option.  Returns `None` if the option is not present or is not
+/// a non-negative integer.
+pub(crate) fn integer_option(items: &[WithItem], key: &str) -> Option<u64> {
+    find_option(items, key)
+        .and_then(integer_value)
+        .and_then(|number| u64::try_from(number).ok())
+}
+
+/// returns the `default_time_to_live` option in seconds.  Returns `None` if the option is
+/// not present or is not a non-negative integer that fits in a `u32`.
+pub(crate) fn default_ttl(items: &[WithItem]) -> Option<u32> {
+    integer_option(items, "default_time_to_live").and_then(|ttl| u32::try_from(ttl).ok())
+}
+
+/// checks the options that the server validates when the statement is executed.  The
+/// parser accepts any option value so these problems are only found here.
+pub fn validate_options(items: &[WithItem]) -> Vec<OptionIssue> {
+    let mut result = vec![];
+    for key in ["default_time_to_live", "gc_grace_seconds"] {
+        if let Some(value) = find_option(items, key) {
+            let text = value.to_string();
+            match integer_value(value) {
+                Some(number) if number < 0 => result.push(OptionIssue::Negative {
+                    key: key.to_string(),
+                    value: text,
+                }),
+                Some(_) => {}
+                None => result.push(OptionIssue::NotAnInteger {
+                    key: key.to_string(),
+                    value: text,
+                }),
+            }
+        }
+    }
+    if let Some(value) = find_option(items, "speculative_retry") {
+        if !value
+            .as_string()
+            .map_or(false, |txt| is_speculative_retry(&txt))
+        {
+            result.push(OptionIssue::InvalidSpeculativeRetry(value.to_string()));
+        }
+    }
+    result
+}
+
+/// true if the text is a valid `speculative_retry` value: `ALWAYS`, `NEVER` (or `NONE`), a
+/// threshold, or `MIN` or `MAX` of two thresholds.
+fn is_speculative_retry(text: &str) -> bool {
+    let text = text.trim().to_uppercase();
+    let min_max = text
+        .strip_prefix("MIN(")
+        .or_else(|| text.strip_prefix("MAX("))
+        .and_then(|args| args.strip_suffix(')'));
+    match min_max {
+        Some(args) => {
+            let args: Vec<&str> = args.split(',').collect();
+            args.len() == 2 && args.iter().all(|arg| is_retry_threshold(arg.trim()))
+        }
+        None => matches!(text.as_str(), "ALWAYS" | "NEVER" | "NONE") || is_retry_threshold(&text),
+    }
+}
+
+/// true if the upper case text is a percentile (`99PERCENTILE` or `99P`) or a latency
+/// (`50MS`).
+fn is_retry_threshold(text: &str) -> bool {
+    let number = |txt: &str| {
+        txt.parse::<f64>()
+            .ok()
+            .filter(|n| n.is_finite() && *n >= 0.0)
+    };
+    if let Some(latency) = text.strip_suffix("MS") {
+        number(latency).is_some()
+    } else {
+        text.strip_suffix("PERCENTILE")
+            .or_else(|| text.strip_suffix('P'))
+            .and_then(number)
+            .map_or(false, |percentile| percentile <= 100.0)
+    }
+}
+
+/// a table option value that the server will reject.
+#[derive(PartialEq, Debug, Clone)]
+pub enum OptionIssue {
+    /// the option must be a whole number.
+    NotAnInteger { key: String, value: String },
+    /// the option may not be negative.
+    Negative { key: String, value: String },
+    /// the `speculative_retry` value is not `ALWAYS`, `NEVER`, a percentile (e.g. `'99p'`), a
+    /// latency (e.g. `'50ms'`) or `MIN` or `MAX` of a percentile and a latency.
+    InvalidSpeculativeRetry(String),
+}
+
+impl Display for OptionIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OptionIssue::NotAnInteger { key, value } => {
+                write!(f, "{} must be a whole number, not {}", key, value)
+            }
+            OptionIssue::Negative { key, value } => {
+                write!(f, "{} may not be negative, found {}", key, value)
+            }
+            OptionIssue::InvalidSpeculativeRetry(value) => {
+                write!(f, "invalid speculative_retry value {}", value)
+            }
+        }
+    }
+}
+
+impl std::error::Error for OptionIssue {}
+
+#[cfg(test)]
+mod tests {
+    use crate::cassandra_statement::CassandraStatement;
+    use crate::common::OptionValue;
+    use crate::create_table::CreateTable;
+    use crate::table_options::{
+        is_speculative_retry, validate_options, Caching, Compaction, CompactionStrategy,
+        Compression, OptionIssue, RowsPerPartition,
+    };
+    use crate::test_helpers::parse_one;
+
+    #[test]
+    fn test_table_options() {
+        let qry = "CREATE TABLE t (pk INT PRIMARY KEY, v TEXT) WITH compaction = {'class':'org.apache.cassandra.db.compaction.TimeWindowCompactionStrategy', 'compaction_window_unit':'DAYS', 'compaction_window_size':'1', 'unchecked_tombstone_compaction':'true'} AND compression = {'class':'LZ4Compressor', 'chunk_length_in_kb':'64', 'extra':'x'} AND caching = {'keys':'ALL', 'rows_per_partition':'100'} AND comment = 'c'";
+        let create = parse_one::<CreateTable>(qry);
+        assert_eq!(
+            Some(Compaction {
+                strategy: CompactionStrategy::TimeWindow {
+                    compaction_window_unit: Some("DAYS".to_string()),
+                    compaction_window_size: Some(1),
+                },
+                enabled: None,
+                other: vec![(
+                    "'unchecked_tombstone_compaction'".to_string(),
+                    "'true'".to_string()
+                )],
+            }),
+            create.compaction()
+        );
+        assert_eq!(
+            Some(Compression {
+                class: Some("LZ4Compressor".to_string()),
+                chunk_length_in_kb: Some(64),
+                crc_check_chance: None,
+                enabled: None,
+                other: vec![("'extra'".to_string(), "'x'".to_string())],
+            }),
+            create.compression()
+        );
+        assert_eq!(
+            Some(Caching {
+                keys: Some(true),
+                rows_per_partition: Some(RowsPerPartition::Count(100)),
+                other: vec![],
+            }),
+            create.caching()
+        );
+        // the raw options are unchanged.
+        assert_eq!(
+            qry,
+            CassandraStatement::CreateTable(create.clone()).to_string()
+        );
+
+        let create =
+            parse_one::<CreateTable>("CREATE TABLE t (pk INT PRIMARY KEY) WITH comment = 'c'");
+        assert_eq!(None, create.compaction());
+        assert_eq!(None, create.compression());
+        assert_eq!(None, create.caching());
+    }
+
+    #[test]
+    fn test_compaction_strategies() {
+        let compaction = |entries: &[(&str, &str)]| {
+            Compaction::from_option(&OptionValue::Map(
+                entries
+                    .iter()
+                    .map(|(k, v)| (format!("'{}'", k), format!("'{}'", v)))
+                    .collect(),
+            ))
+            .unwrap()
+        };
+        let stcs = compaction(&[
+            ("class", "SizeTieredCompactionStrategy"),
+            ("min_threshold", "4"),
+            ("max_threshold", "many"),
+            ("enabled", "false"),
+        ]);
+        assert_eq!(
+            CompactionStrategy::SizeTiered {
+                min_threshold: Some(4),
+                max_threshold: None,
+            },
+            stcs.strategy
+        );
+        assert_eq!(Some(false), stcs.enabled);
+        assert_eq!(
+            vec![("'max_threshold'".to_string(), "'many'".to_string())],
+            stcs.other
+        );
+        assert_eq!(
+            CompactionStrategy::Leveled {
+                sstable_size_in_mb: Some(160)
+            },
+            compaction(&[
+                ("class", "LeveledCompactionStrategy"),
+                ("sstable_size_in_mb", "160")
+            ])
+            .strategy
+        );
+        assert_eq!(
+            CompactionStrategy::Unified {
+                scaling_parameters: Some("T4".to_string())
+            },
+            compaction(&[
+                ("class", "UnifiedCompactionStrategy"),
+                ("scaling_parameters", "T4")
+            ])
+            .strategy
+        );
+        let custom = compaction(&[("class", "com.example.Custom"), ("min_threshold", "4")]);
+        assert_eq!(
+            CompactionStrategy::Other("com.example.Custom".to_string()),
+            custom.strategy
+        );
+        assert_eq!(
+            vec![("'min_threshold'".to_string(), "'4'".to_string())],
+            custom.other
+        );
+        assert_eq!(
+            None,
+            Compaction::from_option(&OptionValue::Literal("'x'".to_string()))
+        );
+        assert_eq!(
+            None,
+            Compaction::from_option(&OptionValue::Map(vec![(
+                "'enabled'".to_string(),
+                "'true'".to_string()
+            )]))
+        );
+    }
+
+    #[test]
+    fn test_validate_options() {
+        let create = parse_one::<CreateTable>("CREATE TABLE t (pk INT PRIMARY KEY) WITH default_time_to_live = 3600 AND gc_grace_seconds = 0 AND speculative_retry = '99p'");
+        assert_eq!(Some(3600), create.default_ttl());
+        assert!(create.validate_options().is_empty());
+
+        let create = parse_one::<CreateTable>("CREATE TABLE t (pk INT PRIMARY KEY) WITH default_time_to_live = -1 AND gc_grace_seconds = 'ten' AND speculative_retry = '99 percent'");
+        assert_eq!(None, create.default_ttl());
+        assert_eq!(
+            vec![
+                OptionIssue::Negative {
+                    key: "default_time_to_live".to_string(),
+                    value: "-1".to_string(),
+                },
+                OptionIssue::NotAnInteger {
+                    key: "gc_grace_seconds".to_string(),
+                    value: "'ten'".to_string(),
+                },
+                OptionIssue::InvalidSpeculativeRetry("'99 percent'".to_string()),
+            ],
+            validate_options(create.with_clause.as_slice())
+        );
+
+        for value in ["3600.0", "1e3", "'3600'"] {
+            let create = parse_one::<CreateTable>(&format!(
+                "CREATE TABLE t (pk INT PRIMARY KEY) WITH default_time_to_live = {}",
+                value
+            ));
+            assert_eq!(None, create.default_ttl());
+            assert_eq!(
+                vec![OptionIssue::NotAnInteger {
+                    key: "default_time_to_live".to_string(),
+                    value: value.to_string(),
+                }],
+                create.validate_options()
+            );
+        }
+    }
+
+    #[test]
+    fn test_speculative_retry() {
+        for valid in [
+            "ALWAYS",
+            "never",
+            "NONE",
+            "99PERCENTILE",
+            "99.9p",
+            "50ms",
+            "0.5MS",
+            "MIN(99p, 50ms)",
+            "max(99PERCENTILE,10ms)",
+        ] {
+            assert!(is_speculative_retry(valid), "{}", valid);
+        }
+        for invalid in [
+            "",
+            "sometimes",
+            "101p",
+            "-1ms",
+            "ms",
+            "MIN(99p)",
+            "MAX(1ms, 2ms, 3ms)",
+        ] {
+            assert!(!is_speculative_retry(invalid), "{}", invalid);
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use crate::cassandra_ast::CassandraAST;
+use crate::cassandra_statement::CassandraStatement;
+use crate::create_index::CreateIndex;
+use crate::create_table::CreateTable;
+use crate::delete::Delete;
+use crate::insert::Insert;
+use crate::role_common::RoleCommon;
+use crate::select::Select;
+use crate::update::Update;
+
+/// a statement model that can be taken out of a `CassandraStatement` by the tests.
+pub(crate) trait FromStatement: Sized {
+    fn from_statement(statement: CassandraStatement) -> Option<Self>;
+}
+
+macro_rules! from_statement {
+    ($model:ty, $($variant:ident),+) => {
+        impl FromStatement for $model {
+            fn from_statement(statement: CassandraStatement) -> Option<Self> {
+                match statement {
+                    $(CassandraStatement::$variant(model) => Some(model),)+
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+from_statement!(CreateIndex, CreateIndex);
+from_statement!(CreateTable, CreateTable);
+from_statement!(Delete, Delete);
+from_statement!(Insert, Insert);
+from_statement!(RoleCommon, CreateRole, AlterRole);
+from_statement!(Select, Select);
+from_statement!(Update, Update);
+
+/// parses a single statement that must be free of errors and returns its model.
+pub(crate) fn parse_one<T: FromStatement>(qry: &str) -> T {
+    let ast = CassandraAST::new(qry);
+    assert!(!ast.has_error(), "{}: {:?}", qry, ast.errors());
+    let statement = ast.statements.into_iter().next().expect("no statement");
+    T::from_statement(statement.statement)
+        .unwrap_or_else(|| panic!("unexpected statement: {}", qry))
+}
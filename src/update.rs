@@ -1,5 +1,5 @@
 use crate::begin_batch::BeginBatch;
-use crate::common::{FQName, Operand, RelationElement, TtlTimestamp};
+use crate::common::{FQName, Identifier, Operand, RelationElement, TtlTimestamp};
 use crate::delete::IndexedColumn;
 use itertools::Itertools;
 use std::fmt::{Display, Formatter};
@@ -23,6 +23,47 @@ pub struct Update {
     pub if_exists: bool,
 }
 
+impl Update {
+    /// returns the `IF` conditions.  These are checked atomically by the server and are
+    /// distinct from the `WHERE` clause.  Empty unless the update is conditional.
+    pub fn conditions(&self) -> &[RelationElement] {
+        &self.if_clause
+    }
+
+    /// returns true if the statement is a lightweight transaction (`IF EXISTS` or `IF condition`).
+    pub fn is_lwt(&self) -> bool {
+        self.if_exists || !self.if_clause.is_empty()
+    }
+
+    /// returns true if executing the update more than once has the same effect as executing
+    /// it once.  This is false for lightweight transactions and otherwise true when every
+    /// assignment is idempotent, see `AssignmentElement::is_idempotent()`.
+    pub fn is_idempotent(&self) -> bool {
+        !self.is_lwt() && self.assignments.iter().all(|a| a.is_idempotent())
+    }
+
+    /// returns true if every assignment increments or decrements a counter, for example
+    /// `hits = hits + 1, misses = misses - ?`.
+    pub fn is_counter_update(&self) -> bool {
+        !self.assignments.is_empty() && self.assignments.iter().all(|a| a.is_counter_update())
+    }
+
+    /// returns true if some assignments have the counter form and others do not.  Cassandra
+    /// rejects such statements as counter and non-counter columns can not be mixed.
+    pub fn mixes_counter_updates(&self) -> bool {
+        self.assignments.iter().any(|a| a.is_counter_update())
+            && !self.assignments.iter().all(|a| a.is_counter_update())
+    }
+
+    /// returns each assigned column with the kind of change made to it.
+    pub fn assignment_kinds(&self) -> Vec<(&IndexedColumn, AssignmentKind)> {
+        self.assignments
+            .iter()
+            .map(|a| (&a.name, a.kind()))
+            .collect()
+    }
+}
+
 impl Display for Update {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -57,11 +98,76 @@ pub struct AssignmentElement {
     pub value: Operand,
     /// an optional +/- value
     pub operator: Option<AssignmentOperator>,
+    /// if true the assignment was written as `col += value` or `col -= value`.  The `value`
+    /// is then the column itself and the `operator` holds the right hand side.
+    pub shorthand: bool,
+}
+
+impl AssignmentElement {
+    /// returns true if the assignment has the counter form `col = col + n` or `col = col - n`
+    /// where `n` is an integer literal or a bind marker.
+    pub fn is_counter_update(&self) -> bool {
+        let is_delta = |op: &Operand| matches!(op, Operand::Param(_)) || op.as_i64().is_ok();
+        match (&self.value, &self.operator) {
+            (
+                Operand::Column(column),
+                Some(AssignmentOperator::Plus(delta) | AssignmentOperator::Minus(delta)),
+            ) => self.name.idx.is_none() && *column == self.name.column && is_delta(delta),
+            _ => false,
+        }
+    }
+
+    /// returns true if applying the assignment more than once has the same effect as applying
+    /// it once.  The column types are not known so this is a heuristic:
+    ///  * setting a column, map entry or element to a fixed value is idempotent, a function
+    ///    call such as `now()` is not.
+    ///  * adding a set literal, adding map entries and removing a set or list literal are
+    ///    idempotent.
+    ///  * counter updates, list appends and prepends are not, nor are additions and removals
+    ///    of bind markers as the collection type is not known.
+    pub fn is_idempotent(&self) -> bool {
+        let rhs = match &self.operator {
+            Some(AssignmentOperator::Plus(rhs) | AssignmentOperator::Minus(rhs)) => rhs,
+            None => &self.value,
+        };
+        match self.kind() {
+            AssignmentKind::Set | AssignmentKind::Put => {
+                self.value.is_fixed_value() && rhs.is_fixed_value()
+            }
+            AssignmentKind::Append => matches!(rhs, Operand::Set(_)),
+            AssignmentKind::Remove => matches!(rhs, Operand::Set(_) | Operand::List(_)),
+            AssignmentKind::Prepend => false,
+        }
+    }
+
+    /// classifies the change made to the column.  The `+` and `-` forms are only collection
+    /// (or counter) mutations when the assigned column is one of the operands, otherwise the
+    /// assignment is a `Set`.
+    pub fn kind(&self) -> AssignmentKind {
+        let is_target = |op: &Operand| matches!(op, Operand::Column(c) if *c == self.name.column);
+        match &self.operator {
+            None if self.name.idx.is_some() => AssignmentKind::Put,
+            None => AssignmentKind::Set,
+            Some(AssignmentOperator::Plus(rhs)) if is_target(&self.value) => match rhs {
+                Operand::Map(_) => AssignmentKind::Put,
+                _ => AssignmentKind::Append,
+            },
+            Some(AssignmentOperator::Plus(rhs)) if is_target(rhs) => AssignmentKind::Prepend,
+            Some(AssignmentOperator::Minus(_)) if is_target(&self.value) => AssignmentKind::Remove,
+            Some(_) => AssignmentKind::Set,
+        }
+    }
 }
 
 impl Display for AssignmentElement {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match &self.operator {
+            Some(AssignmentOperator::Plus(op)) if self.shorthand => {
+                write!(f, "{} += {}", self.name, op)
+            }
+            Some(AssignmentOperator::Minus(op)) if self.shorthand => {
+                write!(f, "{} -= {}", self.name, op)
+            }
             Some(x) => write!(f, "{} = {}{}", self.name, self.value, x),
             None => write!(f, "{} = {}", self.name, self.value),
         }
@@ -83,3 +189,370 @@ impl Display for AssignmentOperator {
         }
     }
 }
+
+/// The kind of change an assignment makes to a column.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum AssignmentKind {
+    /// the column is replaced, `col = value`
+    Set,
+    /// the value is added to the end of a list, to a set, or to a counter, `col = col + value`
+    Append,
+    /// the value is added to the start of a list, `col = value + col`
+    Prepend,
+    /// the values are removed from a collection, or subtracted from a counter,
+    /// `col = col - value`
+    Remove,
+    /// map entries are added, or an element is set by key or index, `col = col + {k:v}` or
+    /// `col[k] = value`
+    Put,
+}
+
+/// builds `Update` statements.  CQL requires the `WHERE` clause to restrict at least the
+/// full partition key, the builder can only check that there is a `WHERE` clause as it does not
+/// have the table schema.
+#[derive(PartialEq, Debug, Clone)]
+pub struct UpdateBuilder {
+    table_name: FQName,
+    using_ttl: Option<TtlTimestamp>,
+    assignments: Vec<AssignmentElement>,
+    where_clause: Vec<RelationElement>,
+    if_clause: Vec<RelationElement>,
+}
+
+impl UpdateBuilder {
+    /// start building an update of the table, either a `FQName` or `table` or
+    /// `keyspace.table`.  Table and column names are written as in a statement, see
+    /// `Identifier::from_cql()`.
+    pub fn table<T: Into<FQName>>(table_name: T) -> UpdateBuilder {
+        UpdateBuilder {
+            table_name: table_name.into(),
+            using_ttl: None,
+            assignments: vec![],
+            where_clause: vec![],
+            if_clause: vec![],
+        }
+    }
+
+    /// add `column = value` to the assignments.
+    pub fn set<T: Into<Operand>>(self, column: &str, value: T) -> UpdateBuilder {
+        self.assign(column, value.into(), None)
+    }
+
+    /// add `column = column + value` to the assignments.  Appends to a list or set, adds
+    /// map entries, or increments a counter.
+    pub fn append<T: Into<Operand>>(self, column: &str, value: T) -> UpdateBuilder {
+        let operator = AssignmentOperator::Plus(value.into());
+        self.assign(
+            column,
+            Operand::Column(Identifier::from_cql(column)),
+            Some(operator),
+        )
+    }
+
+    /// add `column = column - value` to the assignments.  Removes values from a collection
+    /// or decrements a counter.
+    pub fn remove<T: Into<Operand>>(self, column: &str, value: T) -> UpdateBuilder {
+        let operator = AssignmentOperator::Minus(value.into());
+        self.assign(
+            column,
+            Operand::Column(Identifier::from_cql(column)),
+            Some(operator),
+        )
+    }
+
+    fn assign(
+        mut self,
+        column: &str,
+        value: Operand,
+        operator: Option<AssignmentOperator>,
+    ) -> UpdateBuilder {
+        self.assignments.push(AssignmentElement {
+            name: IndexedColumn {
+                column: Identifier::from_cql(column),
+                idx: None,
+                field: None,
+            },
+            value,
+            operator,
+            shorthand: false,
+        });
+        self
+    }
+
+    /// add the relation to the `WHERE` clause.
+    pub fn where_(mut self, relation: RelationElement) -> UpdateBuilder {
+        self.where_clause.push(relation);
+        self
+    }
+
+    /// add `column = value` to the `WHERE` clause.
+    pub fn where_eq<T: Into<Operand>>(self, column: &str, value: T) -> UpdateBuilder {
+        self.where_(RelationElement::from_column_eq(
+            Identifier::from_cql(column),
+            value.into(),
+        ))
+    }
+
+    /// add the condition to the `IF` clause.
+    pub fn if_condition(mut self, condition: RelationElement) -> UpdateBuilder {
+        self.if_clause.push(condition);
+        self
+    }
+
+    /// add `column = value` to the `IF` clause.
+    pub fn if_eq<T: Into<Operand>>(self, column: &str, value: T) -> UpdateBuilder {
+        self.if_condition(RelationElement::from_column_eq(
+            Identifier::from_cql(column),
+            value.into(),
+        ))
+    }
+
+    /// set the `USING TTL` value.
+    pub fn ttl(mut self, ttl: u64) -> UpdateBuilder {
+        self.using_ttl
+            .get_or_insert(TtlTimestamp {
+                ttl: None,
+                timestamp: None,
+            })
+            .ttl = Some(Operand::from(&ttl));
+        self
+    }
+
+    /// create the `Update`.
+    pub fn build(self) -> Result<Update, UpdateBuildError> {
+        if self.assignments.is_empty() {
+            return Err(UpdateBuildError::NoAssignments);
+        }
+        if self.where_clause.is_empty() {
+            return Err(UpdateBuildError::NoWhereClause);
+        }
+        Ok(Update {
+            begin_batch: None,
+            table_name: self.table_name,
+            using_ttl: self.using_ttl,
+            assignments: self.assignments,
+            where_clause: self.where_clause,
+            if_clause: self.if_clause,
+            if_exists: false,
+        })
+    }
+}
+
+/// the errors returned by `UpdateBuilder::build()`.
+#[derive(PartialEq, Debug, Clone)]
+pub enum UpdateBuildError {
+    /// no columns were assigned.
+    NoAssignments,
+    /// no `WHERE` relations were specified.
+    NoWhereClause,
+}
+
+impl Display for UpdateBuildError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UpdateBuildError::NoAssignments => {
+                write!(f, "an update requires at least one assignment")
+            }
+            UpdateBuildError::NoWhereClause => {
+                write!(f, "an update requires at least one WHERE relation")
+            }
+        }
+    }
+}
+
+impl std::error::Error for UpdateBuildError {}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{FQName, Identifier, Operand, RelationElement};
+    use crate::delete::IndexedColumn;
+    use crate::test_helpers::parse_one;
+    use crate::update::{AssignmentKind, Update, UpdateBuildError, UpdateBuilder};
+
+    #[test]
+    fn test_counter_update() {
+        let qry = "UPDATE counters SET hits = hits + 1, misses = misses - ? WHERE pk = 1";
+        let update = parse_one::<Update>(qry);
+        assert_eq!(qry, update.to_string());
+        assert!(update.is_counter_update());
+        assert!(
+            parse_one::<Update>("UPDATE counters SET hits = hits + :delta WHERE pk = 1")
+                .is_counter_update()
+        );
+        assert!(
+            !parse_one::<Update>("UPDATE t SET hits = hits + 1, name = 'x' WHERE pk = 1")
+                .is_counter_update()
+        );
+        assert!(!parse_one::<Update>("UPDATE t SET l = l + [1] WHERE pk = 1").is_counter_update());
+        assert!(
+            !parse_one::<Update>("UPDATE t SET hits = other + 1 WHERE pk = 1").is_counter_update()
+        );
+        assert!(!parse_one::<Update>("UPDATE t SET c = c + '1' WHERE pk = 1").is_counter_update());
+
+        let plain = parse_one::<Update>("UPDATE t SET name = 'x' WHERE pk = 1");
+        assert!(!plain.is_counter_update());
+        assert!(!plain.assignments[0].is_counter_update());
+        assert!(!plain.mixes_counter_updates());
+        assert!(!update.mixes_counter_updates());
+        assert!(
+            parse_one::<Update>("UPDATE t SET hits = hits + 1, name = 'x' WHERE pk = 1")
+                .mixes_counter_updates()
+        );
+    }
+
+    #[test]
+    fn test_shorthand_assignments() {
+        let qry = "UPDATE t SET l += [1], m -= {'k'}, c += 3, d = d - 1, e = 5 WHERE pk = 1";
+        let update = parse_one::<Update>(qry);
+        assert_eq!(qry, update.to_string());
+        assert_eq!(
+            vec![true, true, true, false, false],
+            update
+                .assignments
+                .iter()
+                .map(|a| a.shorthand)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![
+                AssignmentKind::Append,
+                AssignmentKind::Remove,
+                AssignmentKind::Append,
+                AssignmentKind::Remove,
+                AssignmentKind::Set,
+            ],
+            update
+                .assignments
+                .iter()
+                .map(|a| a.kind())
+                .collect::<Vec<_>>()
+        );
+        assert!(
+            parse_one::<Update>("UPDATE t SET c += 3, d -= ? WHERE pk = 1").is_counter_update()
+        );
+    }
+
+    #[test]
+    fn test_assignment_targets() {
+        let qry =
+            "UPDATE t SET m['key'] = 'v', l[0] = ?, l[:idx] = 1, addr.street = 'main' WHERE pk = 1";
+        let update = parse_one::<Update>(qry);
+        assert_eq!(qry, update.to_string());
+        let targets: Vec<&IndexedColumn> = update.assignments.iter().map(|a| &a.name).collect();
+        assert_eq!(
+            IndexedColumn {
+                column: Identifier::new("m"),
+                idx: Some(Operand::from("key")),
+                field: None,
+            },
+            *targets[0]
+        );
+        assert_eq!(Some(Operand::Const("0".to_string())), targets[1].idx);
+        assert_eq!(Some(Operand::Param(":idx".to_string())), targets[2].idx);
+        assert_eq!(
+            IndexedColumn {
+                column: Identifier::new("addr"),
+                idx: None,
+                field: Some(Identifier::new("street")),
+            },
+            *targets[3]
+        );
+    }
+
+    #[test]
+    fn test_assignment_kinds() {
+        let qry = "UPDATE t SET l = l + [1], l = [0] + l, s = s - {'x'}, m = m + {'k':'v'}, m['a'] = 'b', c = 5 WHERE pk = 1";
+        let update = parse_one::<Update>(qry);
+        assert_eq!(qry, update.to_string());
+        assert_eq!(
+            vec![
+                ("l", AssignmentKind::Append),
+                ("l", AssignmentKind::Prepend),
+                ("s", AssignmentKind::Remove),
+                ("m", AssignmentKind::Put),
+                ("m", AssignmentKind::Put),
+                ("c", AssignmentKind::Set),
+            ],
+            update
+                .assignment_kinds()
+                .into_iter()
+                .map(|(column, kind)| (column.column.as_str(), kind))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![AssignmentKind::Set],
+            parse_one::<Update>("UPDATE t SET col1 = col2 + 5 WHERE pk = 1")
+                .assignments
+                .iter()
+                .map(|a| a.kind())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_update_builder() {
+        assert_eq!(
+            parse_one::<Update>(
+                "UPDATE ks.counters SET hits = hits + 1, misses = misses - ? WHERE pk = 1"
+            ),
+            UpdateBuilder::table(FQName::new("ks", "counters"))
+                .append("hits", &1)
+                .remove("misses", Operand::Param("?".to_string()))
+                .where_(RelationElement::from_column_eq(
+                    Identifier::new("pk"),
+                    Operand::from(&1)
+                ))
+                .build()
+                .unwrap()
+        );
+        assert_eq!(
+            parse_one::<Update>(
+                "UPDATE t USING TTL 60 SET tags = tags + {'a', 'b'}, l = l - [1], name = 'x' WHERE pk = 1 IF owner = 'me'"
+            ),
+            UpdateBuilder::table(FQName::simple("t"))
+                .append("tags", Operand::Set(vec!["'a'".to_string(), "'b'".to_string()]))
+                .remove("l", vec!["1".to_string()])
+                .set("name", "x")
+                .where_(RelationElement::from_column_eq(Identifier::new("pk"), Operand::from(&1)))
+                .if_condition(RelationElement::from_column_eq(Identifier::new("owner"), Operand::from("me")))
+                .ttl(60)
+                .build()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_update_builder_conditions() {
+        assert_eq!(
+            parse_one::<Update>(
+                "UPDATE ks.t USING TTL 86400 SET col = 'x' WHERE pk = 5 IF version = 1"
+            ),
+            UpdateBuilder::table("ks.t")
+                .set("col", "x")
+                .where_eq("pk", &5)
+                .ttl(86400)
+                .if_eq("version", &1)
+                .build()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_update_builder_errors() {
+        assert_eq!(
+            Err(UpdateBuildError::NoAssignments),
+            UpdateBuilder::table(FQName::simple("t"))
+                .where_(RelationElement::from_column_eq(
+                    Identifier::new("pk"),
+                    Operand::from(&1)
+                ))
+                .build()
+        );
+        assert_eq!(
+            Err(UpdateBuildError::NoWhereClause),
+            UpdateBuilder::table(FQName::simple("t"))
+                .set("v", &1)
+                .build()
+        );
+    }
+}